@@ -1,9 +1,18 @@
 //! Darwinia CLI library.
 
+// --- std ---
+use std::{
+	net::{Ipv4Addr, SocketAddr},
+	path::PathBuf,
+};
 // --- crates ---
 use structopt::StructOpt;
 // --- substrate ---
-use sc_cli::{KeySubcommand, SignCmd, VanityCmd, VerifyCmd};
+use sc_cli::{SignCmd, VanityCmd, VerifyCmd};
+use sp_core::crypto::Ss58Codec;
+// --- darwinia ---
+use crate::{conf::ConfFormat, session_keys::SessionKeysCmd, testnet::TestnetCmd, tx::TxCmd};
+use darwinia_primitives::AccountId;
 
 #[allow(missing_docs)]
 #[derive(Debug, StructOpt)]
@@ -23,6 +32,38 @@ pub enum Subcommand {
 
 	/// Sign a message, with a given (secret) key.
 	Sign(SignCmd),
+
+	/// Inspect the boot configuration.
+	Config(ConfigCmd),
+
+	/// Build, sign and submit transactions offline.
+	Tx(TxCmd),
+
+	/// Launch a local Crab testnet of several validators.
+	Testnet(TestnetCmd),
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, StructOpt)]
+pub enum KeySubcommand {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	Base(sc_cli::KeySubcommand),
+
+	/// Generate the session keys into a keystore, and print them SCALE encoded.
+	SessionKeys(SessionKeysCmd),
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, StructOpt)]
+pub enum ConfigCmd {
+	/// Print the configuration the node would run with, merged from the command line, the
+	/// `--conf` file and the `DARWINIA_CONF_*` environment variables.
+	Dump {
+		/// The format to print, `toml`, `yaml` or `json`.
+		#[structopt(long = "format", value_name = "FORMAT", default_value = "toml")]
+		format: ConfFormat,
+	},
 }
 
 #[allow(missing_docs)]
@@ -35,6 +76,148 @@ pub struct RunCmd {
 	/// Force using Crab native runtime.
 	#[structopt(long = "force-crab")]
 	pub force_crab: bool,
+
+	/// Sign the BABE and GRANDPA equivocation reports with the fisherman key, generating one if
+	/// the keystore holds none, so that the reporter is rewarded when the offender is slashed.
+	#[structopt(long = "fisherman")]
+	pub fisherman: bool,
+
+	/// The Ethereum JSON-RPC endpoint the fisherman checks the relayed Ethereum headers against,
+	/// reporting the ones off the Ethereum chain. Needs `--fisherman`, and the offchain worker
+	/// enabled, e.g. `--offchain-worker Always` on a full node.
+	#[structopt(long = "fisherman-ethereum-rpc", value_name = "URL")]
+	pub fisherman_ethereum_rpc: Option<String>,
+
+	/// The maximum balancing iterations run by the offchain phragmen election miner, instead of
+	/// the default of the runtime. 0 disables the balancing.
+	#[structopt(long = "election-max-iterations", value_name = "COUNT")]
+	pub election_max_iterations: Option<u32>,
+
+	/// The GRANDPA gossip duration, instead of the one of the chain spec.
+	#[structopt(long = "grandpa-gossip-duration", value_name = "MILLISECONDS")]
+	pub grandpa_gossip_duration: Option<u64>,
+
+	/// The blocks between two GRANDPA justifications, instead of the one of the chain spec.
+	#[structopt(long = "grandpa-justification-period", value_name = "BLOCKS")]
+	pub grandpa_justification_period: Option<u32>,
+
+	/// Enable or disable the GRANDPA observer protocol, instead of the chain spec.
+	#[structopt(long = "grandpa-observer", value_name = "BOOL")]
+	pub grandpa_observer: Option<bool>,
+
+	/// Force a GRANDPA authority set at a block, in addition to the hard forks of the chain spec.
+	/// `AUTHORITIES` is a JSON file of the `[authority, weight]` pairs of the new set. Can be
+	/// given multiple times.
+	#[structopt(
+		long = "grandpa-hard-fork",
+		value_name = "SET_ID:BLOCK_HASH:BLOCK_NUMBER:AUTHORITIES"
+	)]
+	pub grandpa_hard_forks: Vec<String>,
+
+	/// Serve the `/health` and `/ready` HTTP endpoints on the specified port.
+	#[structopt(long = "health-port", value_name = "PORT")]
+	pub health_port: Option<u16>,
+
+	/// Listen to all the interfaces for the health endpoints.
+	///
+	/// Default is local.
+	#[structopt(long = "health-external")]
+	pub health_external: bool,
+
+	/// The peers below which `/ready` fails.
+	#[structopt(long = "health-min-peers", value_name = "COUNT", default_value = "1")]
+	pub health_min_peers: usize,
+
+	/// The blocks the finalized block may lag behind the best block before `/ready` fails.
+	#[structopt(
+		long = "health-max-finality-lag",
+		value_name = "BLOCKS",
+		default_value = "50"
+	)]
+	pub health_max_finality_lag: u32,
+
+	/// The stash of the validator run by this node, whose session keys `/ready` checks the
+	/// keystore holds.
+	#[structopt(long = "health-validator", value_name = "STASH")]
+	pub health_validator: Option<String>,
+
+	/// Export each imported block with its extrinsics and decoded events to <PATH>, a directory
+	/// of JSONL files or a SQLite database file, following `--export-format`.
+	#[structopt(long = "export-blocks", value_name = "PATH", parse(from_os_str))]
+	pub export_blocks: Option<PathBuf>,
+
+	/// The format of the block export.
+	#[structopt(
+		long = "export-format",
+		value_name = "FORMAT",
+		possible_values = &["jsonl", "sqlite"],
+		default_value = "jsonl"
+	)]
+	pub export_format: String,
+
+	/// Export the finalized blocks only, instead of each imported block.
+	#[structopt(long = "export-finalized-only")]
+	pub export_finalized_only: bool,
+
+	/// The blocks of a JSONL export file before rotating to the next one.
+	#[structopt(
+		long = "export-rotate-blocks",
+		value_name = "BLOCKS",
+		default_value = "100000"
+	)]
+	pub export_rotate_blocks: u32,
+}
+
+impl RunCmd {
+	/// The Darwinia specific options of a full node.
+	pub fn full_node_options(&self) -> Result<darwinia_service::FullNodeOptions, String> {
+		Ok(darwinia_service::FullNodeOptions {
+			fisherman: self.fisherman,
+			fisherman_ethereum_rpc: self.fisherman_ethereum_rpc.clone(),
+			election_max_iterations: self.election_max_iterations,
+			grandpa_gossip_duration_millis: self.grandpa_gossip_duration,
+			grandpa_justification_period: self.grandpa_justification_period,
+			grandpa_observer_enabled: self.grandpa_observer,
+			grandpa_hard_forks: self
+				.grandpa_hard_forks
+				.iter()
+				.map(|hard_fork| hard_fork.parse())
+				.collect::<Result<_, _>>()?,
+			health: match self.health_port {
+				Some(port) => {
+					let interface = if self.health_external {
+						Ipv4Addr::UNSPECIFIED
+					} else {
+						Ipv4Addr::LOCALHOST
+					};
+
+					Some(darwinia_service::HealthConfig {
+						listen_addr: SocketAddr::new(interface.into(), port),
+						min_peers: self.health_min_peers,
+						max_finality_lag: self.health_max_finality_lag,
+						validator: match &self.health_validator {
+							Some(validator) => {
+								Some(AccountId::from_ss58check(validator).map_err(|e| {
+									format!("Invalid validator `{}`: {:?}", validator, e)
+								})?)
+							}
+							None => None,
+						},
+					})
+				}
+				None => None,
+			},
+			exporter: match &self.export_blocks {
+				Some(path) => Some(darwinia_service::ExportConfig {
+					path: path.clone(),
+					format: self.export_format.parse()?,
+					finalized_only: self.export_finalized_only,
+					rotate_blocks: self.export_rotate_blocks,
+				}),
+				None => None,
+			},
+		})
+	}
 }
 
 #[allow(missing_docs)]
@@ -48,7 +231,9 @@ pub struct Cli {
 	#[structopt(flatten)]
 	pub run: RunCmd,
 
-	/// Load the boot configuration json file from <PATH>. Command line input will be overwritten by this.
+	/// Load the boot configuration file from <PATH>, TOML, YAML or JSON by its extension. Command
+	/// line input will be overwritten by this, and this by the `DARWINIA_CONF_*` environment
+	/// variables.
 	#[structopt(long = "conf", value_name = "PATH")]
-	pub conf: Option<std::path::PathBuf>,
+	pub conf: Option<PathBuf>,
 }
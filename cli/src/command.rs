@@ -1,13 +1,13 @@
-// --- std ---
-use std::path::PathBuf;
 // --- crates ---
 use log::info;
 // --- substrate ---
-use sc_cli::{Role, RunCmd, RuntimeVersion, SubstrateCli};
+use sc_cli::{Role, RuntimeVersion, SubstrateCli};
 use sp_core::crypto::Ss58AddressFormat;
 // --- darwinia ---
-use crate::cli::{Cli, Subcommand};
-use darwinia_cli::{Configuration, DarwiniaCli};
+use crate::{
+	cli::{Cli, ConfigCmd, KeySubcommand, Subcommand},
+	conf::BootConf,
+};
 use darwinia_service::{crab_runtime, IdentifyVariant};
 
 impl SubstrateCli for Cli {
@@ -86,18 +86,18 @@ impl SubstrateCli for Cli {
 	}
 }
 
-impl DarwiniaCli for Cli {
-	fn conf(&self) -> &Option<PathBuf> {
-		&self.conf
-	}
-
-	fn base(&self) -> &RunCmd {
-		&self.run.base
-	}
-
-	fn mut_base(&mut self) -> &mut RunCmd {
-		&mut self.run.base
-	}
+// Override the command line with the boot configuration, and check the result if `validate`.
+fn apply_boot_conf(cli: &mut Cli, validate: bool) -> sc_cli::Result<()> {
+	BootConf::load(cli.conf.as_deref())
+		.and_then(|boot_conf| boot_conf.apply(&mut cli.run))
+		.and_then(|_| {
+			if validate {
+				BootConf::capture(&cli.run).validate()
+			} else {
+				Ok(())
+			}
+		})
+		.map_err(sc_cli::Error::Input)
 }
 
 fn get_exec_name() -> Option<String> {
@@ -109,7 +109,17 @@ fn get_exec_name() -> Option<String> {
 
 /// Parses Darwinia specific CLI arguments and run the service.
 pub fn run() -> sc_cli::Result<()> {
-	let cli = Cli::from_args();
+	let mut cli = Cli::from_args();
+
+	// Only the node and the subcommands reading its settings take the boot configuration, and
+	// only the node checks it, `config dump` printing it as is.
+	match cli.subcommand {
+		None => apply_boot_conf(&mut cli, true)?,
+		Some(Subcommand::Base(_)) | Some(Subcommand::Config(_)) => {
+			apply_boot_conf(&mut cli, false)?
+		}
+		_ => (),
+	}
 
 	fn set_default_ss58_version(spec: &Box<dyn darwinia_service::ChainSpec>) {
 		let ss58_version = if spec.is_crab() {
@@ -125,7 +135,8 @@ pub fn run() -> sc_cli::Result<()> {
 
 	match &cli.subcommand {
 		None => {
-			let runtime = Configuration::create_runner(cli)?;
+			let options = cli.run.full_node_options().map_err(sc_cli::Error::Input)?;
+			let runtime = cli.create_runner(&cli.run.base)?;
 			let chain_spec = &runtime.config().chain_spec;
 
 			set_default_ss58_version(chain_spec);
@@ -140,12 +151,13 @@ pub fn run() -> sc_cli::Result<()> {
 			if chain_spec.is_crab() {
 				runtime.run_node_until_exit(|config| match config.role {
 					Role::Light => darwinia_service::crab_new_light(config),
-					_ => darwinia_service::crab_new_full(config).map(|(components, _)| components),
+					_ => darwinia_service::crab_new_full(config, options.clone())
+						.map(|(components, _)| components),
 				})
 			} else if chain_spec.is_darwinia() {
 				runtime.run_node_until_exit(|config| match config.role {
 					Role::Light => darwinia_service::darwinia_new_light(config),
-					_ => darwinia_service::darwinia_new_full(config)
+					_ => darwinia_service::darwinia_new_full(config, options.clone())
 						.map(|(components, _)| components),
 				})
 			} else {
@@ -178,9 +190,21 @@ pub fn run() -> sc_cli::Result<()> {
 				unreachable!()
 			}
 		}
-		Some(Subcommand::Key(cmd)) => cmd.run(),
+		Some(Subcommand::Testnet(cmd)) => cmd.run(),
+		Some(Subcommand::Key(KeySubcommand::Base(cmd))) => cmd.run(),
+		Some(Subcommand::Key(KeySubcommand::SessionKeys(cmd))) => cmd.run(),
 		Some(Subcommand::Sign(cmd)) => cmd.run(),
 		Some(Subcommand::Verify(cmd)) => cmd.run(),
 		Some(Subcommand::Vanity(cmd)) => cmd.run(),
+		Some(Subcommand::Tx(cmd)) => cmd.run(),
+		Some(Subcommand::Config(ConfigCmd::Dump { format })) => {
+			let boot_conf = BootConf::capture(&cli.run)
+				.dump(*format)
+				.map_err(sc_cli::Error::Input)?;
+
+			println!("{}", boot_conf);
+
+			Ok(())
+		}
 	}
 }
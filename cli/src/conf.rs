@@ -0,0 +1,547 @@
+//! The boot configuration of the node.
+//!
+//! The settings are read from the `--conf` file, TOML, YAML or JSON by its extension, which
+//! overrides the command line. The `DARWINIA_CONF_<KEY>` environment variables override the
+//! file, `<KEY>` being the key in upper case with `_` for `-`, e.g. `DARWINIA_CONF_RPC_PORT`.
+//! The value of a string key is taken as is, the others are parsed as JSON, a list as a JSON
+//! array.
+
+// --- std ---
+use std::{
+	collections::BTreeSet,
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+// --- crates ---
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+// --- substrate ---
+use sc_network::config::MultiaddrWithPeerId;
+// --- darwinia ---
+use crate::cli::RunCmd;
+
+/// The prefix of the environment variables overriding the boot configuration.
+const ENV_PREFIX: &str = "DARWINIA_CONF_";
+/// The keys of `BootConf` holding a string, or a path.
+const STRING_KEYS: &[&str] = &[
+	"base-path",
+	"chain",
+	"name",
+	"fisherman-ethereum-rpc",
+	"health-validator",
+	"export-blocks",
+	"export-format",
+];
+
+/// The formats of a boot configuration file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfFormat {
+	Toml,
+	Yaml,
+	Json,
+}
+impl ConfFormat {
+	fn of(path: &Path) -> Result<Self, String> {
+		path.extension()
+			.and_then(|extension| extension.to_str())
+			.unwrap_or_default()
+			.parse()
+	}
+}
+impl FromStr for ConfFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_ref() {
+			"toml" => Ok(Self::Toml),
+			"yaml" | "yml" => Ok(Self::Yaml),
+			"json" => Ok(Self::Json),
+			_ => Err(format!(
+				"Unsupported configuration format `{}`, expected `toml`, `yaml` or `json`",
+				s
+			)),
+		}
+	}
+}
+
+/// The settings of the boot configuration, an unset key keeps the command line's.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BootConf {
+	// --- substrate ---
+	pub base_path: Option<PathBuf>,
+	pub chain: Option<String>,
+	pub dev: Option<bool>,
+	pub name: Option<String>,
+	pub log: Option<Vec<String>>,
+	pub validator: Option<bool>,
+	pub port: Option<u16>,
+	pub bootnodes: Option<Vec<String>>,
+	pub reserved_nodes: Option<Vec<String>>,
+	pub reserved_only: Option<bool>,
+	pub rpc_port: Option<u16>,
+	pub rpc_external: Option<bool>,
+	pub unsafe_rpc_external: Option<bool>,
+	pub ws_port: Option<u16>,
+	pub ws_external: Option<bool>,
+	pub unsafe_ws_external: Option<bool>,
+	pub prometheus_port: Option<u16>,
+	pub prometheus_external: Option<bool>,
+	pub no_prometheus: Option<bool>,
+	pub no_telemetry: Option<bool>,
+	// --- darwinia ---
+	pub force_crab: Option<bool>,
+	pub fisherman: Option<bool>,
+	pub fisherman_ethereum_rpc: Option<String>,
+	pub election_max_iterations: Option<u32>,
+	pub grandpa_gossip_duration: Option<u64>,
+	pub grandpa_justification_period: Option<u32>,
+	pub grandpa_observer: Option<bool>,
+	pub grandpa_hard_fork: Option<Vec<String>>,
+	pub health_port: Option<u16>,
+	pub health_external: Option<bool>,
+	pub health_min_peers: Option<usize>,
+	pub health_max_finality_lag: Option<u32>,
+	pub health_validator: Option<String>,
+	pub export_blocks: Option<PathBuf>,
+	pub export_format: Option<String>,
+	pub export_finalized_only: Option<bool>,
+	pub export_rotate_blocks: Option<u32>,
+}
+impl BootConf {
+	/// Load the boot configuration of `path` if any, and override it with the environment.
+	pub fn load(path: Option<&Path>) -> Result<Self, String> {
+		let boot_conf = match path {
+			Some(path) => Self::from_file(path)?,
+			None => Self::default(),
+		};
+
+		boot_conf.overlay_env(std::env::vars())
+	}
+
+	fn from_file(path: &Path) -> Result<Self, String> {
+		let format = ConfFormat::of(path)?;
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("Failed to read `{}`: {}", path.display(), e))?;
+
+		match format {
+			ConfFormat::Toml => toml::from_str(&content).map_err(|e| e.to_string()),
+			ConfFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+			ConfFormat::Json => serde_json::from_str(&content).map_err(|e| e.to_string()),
+		}
+		.map_err(|e| format!("Invalid configuration `{}`: {}", path.display(), e))
+	}
+
+	fn overlay_env(self, vars: impl Iterator<Item = (String, String)>) -> Result<Self, String> {
+		let mut boot_conf = match serde_json::to_value(self).map_err(|e| e.to_string())? {
+			Value::Object(boot_conf) => boot_conf,
+			_ => Map::new(),
+		};
+
+		for (var, value) in vars {
+			if let Some(key) = var.strip_prefix(ENV_PREFIX) {
+				let key = key.to_lowercase().replace('_', "-");
+				let value = if STRING_KEYS.contains(&key.as_str()) {
+					Value::String(value)
+				} else {
+					// Not a JSON value, left to fail with the type expected by the key.
+					serde_json::from_str(&value).unwrap_or(Value::String(value))
+				};
+
+				boot_conf.insert(key, value);
+			}
+		}
+
+		// The settings of the file always round trip, so only the environment can be invalid.
+		serde_json::from_value(Value::Object(boot_conf))
+			.map_err(|e| format!("Invalid `{}*` environment variables: {}", ENV_PREFIX, e))
+	}
+
+	/// Check the settings are usable together.
+	pub fn validate(&self) -> Result<(), String> {
+		let mut ports = BTreeSet::new();
+
+		for (key, port) in &[
+			("port", self.port),
+			("rpc-port", self.rpc_port),
+			("ws-port", self.ws_port),
+			("prometheus-port", self.prometheus_port),
+			("health-port", self.health_port),
+		] {
+			if let Some(port) = port {
+				if *port == 0 {
+					return Err(format!("`{}` must not be 0", key));
+				}
+				if !ports.insert(*port) {
+					return Err(format!(
+						"`{}` {} is already taken by another key",
+						key, port
+					));
+				}
+			}
+		}
+
+		for (key, addresses) in &[
+			("bootnodes", &self.bootnodes),
+			("reserved-nodes", &self.reserved_nodes),
+		] {
+			for address in addresses.iter().flatten() {
+				address
+					.parse::<MultiaddrWithPeerId>()
+					.map_err(|e| format!("Invalid `{}` address `{}`: {}", key, address, e))?;
+			}
+		}
+
+		for hard_fork in self.grandpa_hard_fork.iter().flatten() {
+			hard_fork
+				.parse::<darwinia_service::GrandpaHardFork>()
+				.and_then(|hard_fork| hard_fork.validate())?;
+		}
+
+		if let Some(export_format) = &self.export_format {
+			export_format.parse::<darwinia_service::ExportFormat>()?;
+		}
+		if self.export_rotate_blocks == Some(0) {
+			return Err("`export-rotate-blocks` must not be 0".into());
+		}
+
+		if self.reserved_only == Some(true) && self.reserved_nodes.iter().flatten().count() == 0 {
+			return Err("`reserved-only` requires some `reserved-nodes`".into());
+		}
+
+		Ok(())
+	}
+
+	/// Override the command line with the set keys.
+	pub fn apply(self, run: &mut RunCmd) -> Result<(), String> {
+		let base = &mut run.base;
+
+		macro_rules! apply {
+			($($key:ident => $field:expr),+ $(,)?) => {
+				$(
+					if let Some(value) = self.$key {
+						$field = value;
+					}
+				)+
+			};
+		}
+		macro_rules! apply_option {
+			($($key:ident => $field:expr),+ $(,)?) => {
+				$(
+					if self.$key.is_some() {
+						$field = self.$key;
+					}
+				)+
+			};
+		}
+
+		apply_option! {
+			base_path => base.shared_params.base_path,
+			chain => base.shared_params.chain,
+			name => base.name,
+			port => base.network_params.port,
+			rpc_port => base.rpc_port,
+			ws_port => base.ws_port,
+			prometheus_port => base.prometheus_port,
+			fisherman_ethereum_rpc => run.fisherman_ethereum_rpc,
+			election_max_iterations => run.election_max_iterations,
+			grandpa_gossip_duration => run.grandpa_gossip_duration,
+			grandpa_justification_period => run.grandpa_justification_period,
+			grandpa_observer => run.grandpa_observer,
+			health_port => run.health_port,
+			health_validator => run.health_validator,
+			export_blocks => run.export_blocks,
+		}
+		apply! {
+			dev => base.shared_params.dev,
+			log => base.shared_params.log,
+			validator => base.validator,
+			reserved_only => base.network_params.reserved_only,
+			rpc_external => base.rpc_external,
+			unsafe_rpc_external => base.unsafe_rpc_external,
+			ws_external => base.ws_external,
+			unsafe_ws_external => base.unsafe_ws_external,
+			prometheus_external => base.prometheus_external,
+			no_prometheus => base.no_prometheus,
+			no_telemetry => base.no_telemetry,
+			force_crab => run.force_crab,
+			fisherman => run.fisherman,
+			grandpa_hard_fork => run.grandpa_hard_forks,
+			health_external => run.health_external,
+			health_min_peers => run.health_min_peers,
+			health_max_finality_lag => run.health_max_finality_lag,
+			export_format => run.export_format,
+			export_finalized_only => run.export_finalized_only,
+			export_rotate_blocks => run.export_rotate_blocks,
+		}
+
+		if let Some(bootnodes) = self.bootnodes {
+			base.network_params.bootnodes = parse_addresses(&bootnodes)?;
+		}
+		if let Some(reserved_nodes) = self.reserved_nodes {
+			base.network_params.reserved_nodes = parse_addresses(&reserved_nodes)?;
+		}
+
+		Ok(())
+	}
+
+	/// The settings the node runs with.
+	pub fn capture(run: &RunCmd) -> Self {
+		let base = &run.base;
+		let addresses = |addresses: &[MultiaddrWithPeerId]| {
+			Some(addresses.iter().map(ToString::to_string).collect())
+		};
+
+		Self {
+			base_path: base.shared_params.base_path.clone(),
+			chain: base.shared_params.chain.clone(),
+			dev: Some(base.shared_params.dev),
+			name: base.name.clone(),
+			log: Some(base.shared_params.log.clone()),
+			validator: Some(base.validator),
+			port: base.network_params.port,
+			bootnodes: addresses(&base.network_params.bootnodes),
+			reserved_nodes: addresses(&base.network_params.reserved_nodes),
+			reserved_only: Some(base.network_params.reserved_only),
+			rpc_port: base.rpc_port,
+			rpc_external: Some(base.rpc_external),
+			unsafe_rpc_external: Some(base.unsafe_rpc_external),
+			ws_port: base.ws_port,
+			ws_external: Some(base.ws_external),
+			unsafe_ws_external: Some(base.unsafe_ws_external),
+			prometheus_port: base.prometheus_port,
+			prometheus_external: Some(base.prometheus_external),
+			no_prometheus: Some(base.no_prometheus),
+			no_telemetry: Some(base.no_telemetry),
+			force_crab: Some(run.force_crab),
+			fisherman: Some(run.fisherman),
+			fisherman_ethereum_rpc: run.fisherman_ethereum_rpc.clone(),
+			election_max_iterations: run.election_max_iterations,
+			grandpa_gossip_duration: run.grandpa_gossip_duration,
+			grandpa_justification_period: run.grandpa_justification_period,
+			grandpa_observer: run.grandpa_observer,
+			grandpa_hard_fork: Some(run.grandpa_hard_forks.clone()),
+			health_port: run.health_port,
+			health_external: Some(run.health_external),
+			health_min_peers: Some(run.health_min_peers),
+			health_max_finality_lag: Some(run.health_max_finality_lag),
+			health_validator: run.health_validator.clone(),
+			export_blocks: run.export_blocks.clone(),
+			export_format: Some(run.export_format.clone()),
+			export_finalized_only: Some(run.export_finalized_only),
+			export_rotate_blocks: Some(run.export_rotate_blocks),
+		}
+	}
+
+	/// Print the settings in `format`, leaving the unset keys out.
+	pub fn dump(&self, format: ConfFormat) -> Result<String, String> {
+		let boot_conf = match serde_json::to_value(self).map_err(|e| e.to_string())? {
+			Value::Object(boot_conf) => boot_conf
+				.into_iter()
+				.filter(|(_, value)| !value.is_null())
+				.collect::<Map<_, _>>(),
+			_ => Map::new(),
+		};
+
+		match format {
+			ConfFormat::Toml => toml::to_string(&boot_conf).map_err(|e| e.to_string()),
+			ConfFormat::Yaml => serde_yaml::to_string(&boot_conf).map_err(|e| e.to_string()),
+			ConfFormat::Json => serde_json::to_string_pretty(&boot_conf).map_err(|e| e.to_string()),
+		}
+	}
+}
+
+fn parse_addresses(addresses: &[String]) -> Result<Vec<MultiaddrWithPeerId>, String> {
+	addresses
+		.iter()
+		.map(|address| {
+			address
+				.parse()
+				.map_err(|e| format!("Invalid address `{}`: {}", address, e))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	// --- darwinia ---
+	use super::*;
+
+	const BOOTNODE: &str =
+		"/ip4/127.0.0.1/tcp/30333/p2p/QmNQatwxYrvx45JHzALe54be3KTBVQrLtHdPfkmvNNhQkw";
+
+	// Write `content` to a file named `name` in a directory of its own.
+	fn conf_file(name: &str, content: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("darwinia-conf-{}", std::process::id()));
+		let path = dir.join(name);
+
+		fs::create_dir_all(&dir).unwrap();
+		fs::write(&path, content).unwrap();
+
+		path
+	}
+
+	fn env(vars: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+		vars.iter()
+			.map(|(var, value)| (var.to_string(), value.to_string()))
+			.collect::<Vec<_>>()
+			.into_iter()
+	}
+
+	#[test]
+	fn from_file_should_parse_each_format() {
+		for path in &[
+			conf_file(
+				"parse.toml",
+				&format!(
+					"name = \"alice\"\nvalidator = true\nrpc-port = 9933\nbootnodes = [\"{}\"]\n",
+					BOOTNODE
+				),
+			),
+			conf_file(
+				"parse.yml",
+				&format!(
+					"name: alice\nvalidator: true\nrpc-port: 9933\nbootnodes:\n  - \"{}\"\n",
+					BOOTNODE
+				),
+			),
+			conf_file(
+				"parse.json",
+				&format!(
+					r#"{{"name":"alice","validator":true,"rpc-port":9933,"bootnodes":["{}"]}}"#,
+					BOOTNODE
+				),
+			),
+		] {
+			let boot_conf = BootConf::from_file(path).unwrap();
+
+			assert_eq!(boot_conf.name.as_deref(), Some("alice"));
+			assert_eq!(boot_conf.validator, Some(true));
+			assert_eq!(boot_conf.rpc_port, Some(9933));
+			assert_eq!(boot_conf.bootnodes, Some(vec![BOOTNODE.to_string()]));
+			assert_eq!(boot_conf.ws_port, None);
+		}
+
+		assert!(BootConf::from_file(&conf_file("parse.ini", "name = alice")).is_err());
+	}
+
+	#[test]
+	fn from_file_should_deny_unknown_keys() {
+		for path in &[
+			conf_file("unknown.toml", "rpc_port = 9933\n"),
+			conf_file("unknown.yaml", "rpc-ports: 9933\n"),
+			conf_file("unknown.json", r#"{"rpc-port":9933,"ws":true}"#),
+		] {
+			let e = BootConf::from_file(path).unwrap_err();
+
+			assert!(e.contains("unknown field"), "{}", e);
+		}
+	}
+
+	#[test]
+	fn overlay_env_should_override_the_file() {
+		let boot_conf = BootConf {
+			name: Some("alice".into()),
+			rpc_port: Some(9933),
+			ws_port: Some(9944),
+			..Default::default()
+		}
+		.overlay_env(env(&[
+			("DARWINIA_CONF_RPC_PORT", "19933"),
+			("DARWINIA_CONF_NAME", "1234"),
+			("DARWINIA_CONF_VALIDATOR", "true"),
+			("DARWINIA_CONF_BOOTNODES", &format!(r#"["{}"]"#, BOOTNODE)),
+			(
+				"DARWINIA_CONF_FISHERMAN_ETHEREUM_RPC",
+				"http://127.0.0.1:8545",
+			),
+			("RPC_PORT", "1"),
+		]))
+		.unwrap();
+
+		assert_eq!(boot_conf.rpc_port, Some(19933));
+		assert_eq!(boot_conf.ws_port, Some(9944));
+		// A string key is taken as is, even if it reads as JSON.
+		assert_eq!(boot_conf.name.as_deref(), Some("1234"));
+		assert_eq!(boot_conf.validator, Some(true));
+		assert_eq!(boot_conf.bootnodes, Some(vec![BOOTNODE.to_string()]));
+		assert_eq!(
+			boot_conf.fisherman_ethereum_rpc.as_deref(),
+			Some("http://127.0.0.1:8545")
+		);
+	}
+
+	#[test]
+	fn overlay_env_should_reject_invalid_values_and_unknown_keys() {
+		assert!(BootConf::default()
+			.overlay_env(env(&[("DARWINIA_CONF_RPC_PORT", "rpc")]))
+			.is_err());
+		assert!(BootConf::default()
+			.overlay_env(env(&[("DARWINIA_CONF_RPC_PORT", "65536")]))
+			.is_err());
+		assert!(BootConf::default()
+			.overlay_env(env(&[("DARWINIA_CONF_RPC_PORTS", "9933")]))
+			.is_err());
+	}
+
+	#[test]
+	fn validate_should_check_the_settings_together() {
+		let valid = || BootConf {
+			port: Some(30333),
+			rpc_port: Some(9933),
+			ws_port: Some(9944),
+			bootnodes: Some(vec![BOOTNODE.into()]),
+			reserved_nodes: Some(vec![BOOTNODE.into()]),
+			reserved_only: Some(true),
+			export_format: Some("jsonl".into()),
+			..Default::default()
+		};
+
+		assert_eq!(valid().validate(), Ok(()));
+		assert_eq!(BootConf::default().validate(), Ok(()));
+		assert!(BootConf {
+			health_port: Some(9933),
+			..valid()
+		}
+		.validate()
+		.unwrap_err()
+		.contains("already taken"));
+		assert!(BootConf {
+			prometheus_port: Some(0),
+			..valid()
+		}
+		.validate()
+		.is_err());
+		assert!(BootConf {
+			bootnodes: Some(vec!["/ip4/127.0.0.1/tcp/30333".into()]),
+			..valid()
+		}
+		.validate()
+		.is_err());
+		assert!(BootConf {
+			reserved_nodes: Some(vec![]),
+			..valid()
+		}
+		.validate()
+		.is_err());
+		assert!(BootConf {
+			export_format: Some("csv".into()),
+			..valid()
+		}
+		.validate()
+		.is_err());
+		assert!(BootConf {
+			export_rotate_blocks: Some(0),
+			..valid()
+		}
+		.validate()
+		.is_err());
+		assert!(BootConf {
+			grandpa_hard_fork: Some(vec!["1:2".into()]),
+			..valid()
+		}
+		.validate()
+		.is_err());
+	}
+}
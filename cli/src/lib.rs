@@ -9,6 +9,14 @@ mod browser;
 mod cli;
 #[cfg(feature = "cli")]
 mod command;
+#[cfg(feature = "cli")]
+mod conf;
+#[cfg(feature = "cli")]
+mod session_keys;
+#[cfg(feature = "cli")]
+mod testnet;
+#[cfg(feature = "cli")]
+mod tx;
 
 #[cfg(feature = "cli")]
 pub use command::run;
@@ -0,0 +1,111 @@
+//! Generate the session keys of a validator offline.
+
+// --- std ---
+use std::path::PathBuf;
+// --- crates ---
+use codec::{Decode, Encode};
+use structopt::StructOpt;
+// --- substrate ---
+use sc_cli::{Error, Result};
+use sc_keystore::Store as Keystore;
+use sp_core::{
+	crypto::{key_types, KeyTypeId},
+	hexdisplay::HexDisplay,
+	traits::BareCryptoStore,
+};
+use sp_runtime::traits::OpaqueKeys;
+// --- darwinia ---
+use darwinia_service::crab_runtime::{Call, SessionKeys};
+
+/// Generate the session keys into a keystore and print them SCALE encoded.
+#[derive(Debug, StructOpt)]
+pub struct SessionKeysCmd {
+	/// The keystore directory to put the keys in.
+	#[structopt(long = "keystore-path", value_name = "PATH")]
+	keystore_path: PathBuf,
+
+	/// The password of the keystore.
+	#[structopt(long = "password", value_name = "PASSWORD")]
+	password: Option<String>,
+
+	/// Derive each key from `<SURI>//<KEY TYPE>`, e.g. `<SURI>//babe` or `<SURI>//gran`, instead
+	/// of generating a new one.
+	#[structopt(long = "suri", value_name = "SURI")]
+	suri: Option<String>,
+
+	/// Also print the unsigned `Session::set_keys` call, for the controller to sign.
+	#[structopt(long = "set-keys-call")]
+	set_keys_call: bool,
+}
+impl SessionKeysCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		let keystore = Keystore::open(
+			self.keystore_path.clone(),
+			self.password.clone().map(Into::into),
+		)
+		.map_err(|e| Error::Input(format!("Failed to open keystore: {:?}", e)))?;
+		let mut keystore = keystore.write();
+		let mut raw_keys = vec![];
+
+		for key_type in SessionKeys::key_ids() {
+			let seed = self
+				.suri
+				.as_ref()
+				.map(|suri| format!("{}//{}", suri, key_type_name(*key_type)));
+			let public = generate_key(&mut keystore, *key_type, seed.as_deref())?;
+
+			println!(
+				"{}: 0x{}",
+				key_type_name(*key_type),
+				HexDisplay::from(&public)
+			);
+
+			raw_keys.extend_from_slice(&public);
+		}
+
+		let session_keys = SessionKeys::decode(&mut &raw_keys[..])
+			.map_err(|e| Error::Input(format!("Invalid session keys: {:?}", e)))?;
+
+		println!(
+			"Session keys: 0x{}",
+			HexDisplay::from(&session_keys.encode())
+		);
+
+		if self.set_keys_call {
+			let call = Call::Session(pallet_session::Call::set_keys(session_keys, vec![]));
+
+			println!("Set keys call: 0x{}", HexDisplay::from(&call.encode()));
+		}
+
+		Ok(())
+	}
+}
+
+/// Generate a key of `key_type` into `keystore`, derived from `seed` if any, and return its public
+/// key. The GRANDPA keys are ed25519 ones, the others sr25519 ones.
+pub(crate) fn generate_key(
+	keystore: &mut Keystore,
+	key_type: KeyTypeId,
+	seed: Option<&str>,
+) -> Result<Vec<u8>> {
+	match key_type {
+		key_types::GRANDPA => keystore
+			.ed25519_generate_new(key_type, seed)
+			.map(|public| public.0.to_vec()),
+		_ => keystore
+			.sr25519_generate_new(key_type, seed)
+			.map(|public| public.0.to_vec()),
+	}
+	.map_err(|e| {
+		Error::Input(format!(
+			"Failed to generate `{}` key: {:?}",
+			key_type_name(key_type),
+			e
+		))
+	})
+}
+
+pub(crate) fn key_type_name(key_type: KeyTypeId) -> String {
+	String::from_utf8_lossy(&key_type.0).into()
+}
@@ -0,0 +1,331 @@
+//! Launch a local Crab testnet of several validators in one command.
+//!
+//! A validator is launched as a child process of this executable, in its own directory of the
+//! base path, listening on loopback. The ports of the `i`th validator are the base ports plus
+//! `i`, which must not overlap across the p2p, HTTP and WebSockets ports, and each validator
+//! bootstraps from the others.
+
+// --- std ---
+use std::{
+	fs::{self, File},
+	path::{Path, PathBuf},
+	process::Stdio,
+	time::Duration,
+};
+// --- crates ---
+use futures::future;
+use structopt::StructOpt;
+use tokio::process::{Child, Command};
+// --- substrate ---
+use sc_cli::{Error, Result};
+use sc_keystore::Store as Keystore;
+use sc_network::{config::identity, PeerId};
+use sp_core::{blake2_256, hexdisplay::HexDisplay};
+use sp_runtime::traits::OpaqueKeys;
+// --- darwinia ---
+use crate::session_keys::generate_key;
+use darwinia_service::{chain_spec, crab_runtime::SessionKeys};
+
+/// The time the validators have to exit on Ctrl-C, before they are killed.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Launch a local Crab testnet of several validators.
+#[derive(Debug, StructOpt)]
+pub struct TestnetCmd {
+	/// The validators to launch, named after the well known seeds, `Alice`, `Bob`, ..., `Ferdie`,
+	/// then `Validator7`, `Validator8`, ...
+	#[structopt(long = "validators", value_name = "COUNT", default_value = "2")]
+	pub validators: u16,
+
+	/// The directory of the chain spec and of the validators, which keep their database across
+	/// launches.
+	#[structopt(long = "base-path", value_name = "PATH", parse(from_os_str))]
+	pub base_path: PathBuf,
+
+	/// The p2p port of the first validator.
+	#[structopt(long = "port", value_name = "PORT", default_value = "30333")]
+	pub port: u16,
+
+	/// The HTTP RPC port of the first validator.
+	#[structopt(long = "rpc-port", value_name = "PORT", default_value = "9933")]
+	pub rpc_port: u16,
+
+	/// The WebSockets RPC port of the first validator.
+	#[structopt(long = "ws-port", value_name = "PORT", default_value = "9944")]
+	pub ws_port: u16,
+}
+impl TestnetCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		if self.validators == 0 {
+			return Err(Error::Input("`--validators` must not be 0".into()));
+		}
+
+		self.check_ports()?;
+
+		let seeds = chain_spec::testnet_validator_seeds(self.validators);
+		let chain_spec_path = self.base_path.join("chain-spec.json");
+		let validators = seeds
+			.iter()
+			.enumerate()
+			.map(|(i, seed)| Validator::new(&self.base_path, i as u16, seed))
+			.collect::<Result<Vec<_>>>()?;
+
+		fs::create_dir_all(&self.base_path)?;
+		fs::write(
+			&chain_spec_path,
+			chain_spec::crab_multi_validator_testnet_config(&seeds)
+				.as_json(true)
+				.map_err(Error::Other)?,
+		)?;
+
+		// The validators are spawned on the reactor of the runtime, which must outlive them.
+		tokio::runtime::Runtime::new()?.block_on(async {
+			let mut children = vec![];
+
+			for validator in &validators {
+				validator.insert_session_keys()?;
+
+				children.push(self.launch(validator, &validators, &chain_spec_path)?);
+
+				println!(
+					"{}: http://127.0.0.1:{} ws://127.0.0.1:{} (log: {})",
+					validator.seed,
+					self.rpc_port + validator.index,
+					self.ws_port + validator.index,
+					validator.log_path().display()
+				);
+			}
+
+			let interrupted = tokio::select! {
+				_ = tokio::signal::ctrl_c() => {
+					println!("Shutting the testnet down");
+
+					true
+				}
+				(exit_status, i, _) = future::select_all(children.iter_mut()) => {
+					eprintln!(
+						"{} exited with {:?}, shutting the testnet down",
+						validators[i].seed,
+						exit_status
+					);
+
+					false
+				}
+			};
+
+			for (validator, child) in validators.iter().zip(children.iter_mut()) {
+				// The validators share the terminal, so they get Ctrl-C as well.
+				if interrupted
+					&& tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut *child)
+						.await
+						.is_ok()
+				{
+					continue;
+				}
+				if interrupted {
+					eprintln!("{} didn't exit in time, killing it", validator.seed);
+				}
+
+				let _ = child.kill();
+			}
+
+			Ok::<_, Error>(())
+		})
+	}
+
+	// Check the ports of the validators, the base ports plus their index, fit in a `u16` and don't
+	// overlap each other.
+	fn check_ports(&self) -> Result<()> {
+		let ranges = [
+			("--port", self.port),
+			("--rpc-port", self.rpc_port),
+			("--ws-port", self.ws_port),
+		]
+		.iter()
+		.map(|&(flag, first)| {
+			first
+				.checked_add(self.validators - 1)
+				.map(|last| (flag, first, last))
+				.ok_or_else(|| {
+					Error::Input(format!(
+						"`{} {}` leaves no room for the ports of {} validators",
+						flag, first, self.validators
+					))
+				})
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+		for (i, (flag, first, last)) in ranges.iter().enumerate() {
+			for (other_flag, other_first, other_last) in &ranges[i + 1..] {
+				if first <= other_last && other_first <= last {
+					return Err(Error::Input(format!(
+						"The ports {}..={} of `{}` overlap the ports {}..={} of `{}`",
+						first, last, flag, other_first, other_last, other_flag
+					)));
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn launch(
+		&self,
+		validator: &Validator,
+		validators: &[Validator],
+		chain_spec_path: &Path,
+	) -> Result<Child> {
+		let log = File::create(validator.log_path())?;
+		let mut command = Command::new(std::env::current_exe()?);
+
+		command
+			.arg("--chain")
+			.arg(chain_spec_path)
+			.arg("--force-crab")
+			.arg("--base-path")
+			.arg(&validator.base_path)
+			.arg("--keystore-path")
+			.arg(validator.keystore_path())
+			.arg("--name")
+			.arg(&validator.seed)
+			.arg("--node-key")
+			.arg(&validator.node_key)
+			.arg("--port")
+			.arg((self.port + validator.index).to_string())
+			.arg("--rpc-port")
+			.arg((self.rpc_port + validator.index).to_string())
+			.arg("--ws-port")
+			.arg((self.ws_port + validator.index).to_string())
+			.args(&[
+				"--validator",
+				"--no-mdns",
+				"--no-prometheus",
+				"--no-telemetry",
+			]);
+
+		for other in validators {
+			if other.index != validator.index {
+				command.arg("--bootnodes").arg(format!(
+					"/ip4/127.0.0.1/tcp/{}/p2p/{}",
+					self.port + other.index,
+					other.peer_id
+				));
+			}
+		}
+
+		command
+			.stdout(log.try_clone()?)
+			.stderr(log)
+			.stdin(Stdio::null())
+			.kill_on_drop(true)
+			.spawn()
+			.map_err(|e| Error::Other(format!("Failed to launch {}: {}", validator.seed, e)))
+	}
+}
+
+struct Validator {
+	index: u16,
+	seed: String,
+	base_path: PathBuf,
+	/// The hex encoded ed25519 secret of the libp2p identity, derived from the seed.
+	node_key: String,
+	peer_id: PeerId,
+}
+impl Validator {
+	fn new(base_path: &Path, index: u16, seed: &str) -> Result<Self> {
+		let mut secret = blake2_256(format!("//{}//node-key", seed).as_bytes());
+		let node_key = HexDisplay::from(&secret).to_string();
+		let secret = identity::ed25519::SecretKey::from_bytes(&mut secret)
+			.map_err(|e| Error::Other(format!("Invalid node key: {}", e)))?;
+		let peer_id = identity::Keypair::Ed25519(secret.into())
+			.public()
+			.into_peer_id();
+
+		Ok(Self {
+			index,
+			seed: seed.to_owned(),
+			base_path: base_path.join(seed.to_lowercase()),
+			node_key,
+			peer_id,
+		})
+	}
+
+	fn keystore_path(&self) -> PathBuf {
+		self.base_path.join("keystore")
+	}
+
+	fn log_path(&self) -> PathBuf {
+		self.base_path.join("node.log")
+	}
+
+	// Insert the session keys of the chain spec, derived from `//<SEED>`.
+	fn insert_session_keys(&self) -> Result<()> {
+		let keystore = Keystore::open(self.keystore_path(), None)
+			.map_err(|e| Error::Input(format!("Failed to open keystore: {:?}", e)))?;
+		let mut keystore = keystore.write();
+		let seed = format!("//{}", self.seed);
+
+		for key_type in SessionKeys::key_ids() {
+			generate_key(&mut keystore, *key_type, Some(seed.as_str()))?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- darwinia ---
+	use super::*;
+
+	fn testnet_cmd(validators: u16, port: u16, rpc_port: u16, ws_port: u16) -> TestnetCmd {
+		TestnetCmd {
+			validators,
+			base_path: PathBuf::new(),
+			port,
+			rpc_port,
+			ws_port,
+		}
+	}
+
+	#[test]
+	fn check_ports_should_accept_the_default_ports() {
+		assert!(testnet_cmd(2, 30333, 9933, 9944).check_ports().is_ok());
+		// The last ports of each flag are adjacent.
+		assert!(testnet_cmd(11, 30333, 9933, 9944).check_ports().is_ok());
+		assert!(testnet_cmd(1, 65535, 9933, 9944).check_ports().is_ok());
+	}
+
+	#[test]
+	fn check_ports_should_reject_the_overlapping_ports() {
+		assert!(testnet_cmd(12, 30333, 9933, 9944).check_ports().is_err());
+		assert!(testnet_cmd(2, 9944, 9933, 9945).check_ports().is_err());
+		assert!(testnet_cmd(1, 9933, 9933, 9944).check_ports().is_err());
+	}
+
+	#[test]
+	fn check_ports_should_reject_the_ports_beyond_u16() {
+		assert!(testnet_cmd(2, 65535, 9933, 9944).check_ports().is_err());
+		assert!(testnet_cmd(u16::MAX, 1, 9933, 9944).check_ports().is_err());
+	}
+
+	#[test]
+	fn testnet_validator_seeds_should_name_the_well_known_seeds_first() {
+		assert!(chain_spec::testnet_validator_seeds(0).is_empty());
+		assert_eq!(chain_spec::testnet_validator_seeds(2), vec!["Alice", "Bob"]);
+		assert_eq!(
+			chain_spec::testnet_validator_seeds(8),
+			vec![
+				"Alice",
+				"Bob",
+				"Charlie",
+				"Dave",
+				"Eve",
+				"Ferdie",
+				"Validator7",
+				"Validator8",
+			]
+		);
+	}
+}
@@ -0,0 +1,602 @@
+//! Build, sign and submit Crab transactions offline.
+//!
+//! A call is given in JSON, as its pallet, its call and its arguments by name, e.g.
+//! `{"pallet": "Balances", "call": "transfer", "args": {"dest": "5Grw...", "value": "1000"}}`.
+//! The pallet and the call are looked up in the metadata of the native runtime. The common
+//! arguments are given as plain JSON values, and any other one as its SCALE encoding,
+//! `{"scale": "0x..."}`.
+
+// --- std ---
+use std::{convert::TryFrom, str::FromStr};
+// --- crates ---
+use codec::{Compact, Decode, Encode};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use structopt::StructOpt;
+// --- substrate ---
+use frame_metadata::{DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed};
+use sc_cli::{Error, Result};
+use sp_core::{
+	crypto::{Pair, Ss58Codec},
+	ecdsa, ed25519,
+	hexdisplay::HexDisplay,
+	sr25519, H256,
+};
+use sp_runtime::{generic::Era, traits::IdentifyAccount, MultiSignature};
+// --- darwinia ---
+use darwinia_primitives::{AccountId, AccountPublic, Balance, BlockNumber, Hash, Nonce};
+use darwinia_service::{
+	crab_runtime::{self, Call, Runtime, SignedExtra, SignedPayload, UncheckedExtrinsic},
+	types::{
+		normalize, ACCOUNT_ID_TYPES, BALANCE_TYPES, BYTES_TYPES, CALLS_TYPES, CALL_TYPES,
+		HASH_TYPES, U32_TYPES, U64_TYPES,
+	},
+};
+
+#[allow(missing_docs)]
+#[derive(Debug, StructOpt)]
+pub enum TxCmd {
+	/// Build a transaction. Print it signed with `--suri`, or signed by `--signer` with
+	/// `--signature`, otherwise print the payload to sign.
+	Build(BuildCmd),
+
+	/// Submit a signed transaction to a node.
+	Submit(SubmitCmd),
+}
+impl TxCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		match self {
+			Self::Build(cmd) => cmd.run(),
+			Self::Submit(cmd) => cmd.run(),
+		}
+	}
+}
+
+/// The crypto scheme of a signing key.
+#[derive(Clone, Copy, Debug)]
+pub enum Scheme {
+	Sr25519,
+	Ed25519,
+	Ecdsa,
+}
+impl FromStr for Scheme {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s.to_lowercase().as_ref() {
+			"sr25519" => Ok(Self::Sr25519),
+			"ed25519" => Ok(Self::Ed25519),
+			"ecdsa" => Ok(Self::Ecdsa),
+			_ => Err(format!(
+				"Unsupported scheme `{}`, expected `sr25519`, `ed25519` or `ecdsa`",
+				s
+			)),
+		}
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, StructOpt)]
+pub struct BuildCmd {
+	/// The call in JSON.
+	#[structopt(long = "call", value_name = "JSON")]
+	call: String,
+
+	/// The nonce of the signer.
+	#[structopt(long = "nonce", value_name = "NONCE")]
+	nonce: Nonce,
+
+	/// The tip for the block author.
+	#[structopt(long = "tip", value_name = "TIP", default_value = "0")]
+	tip: Balance,
+
+	/// The blocks the transaction is valid for, from `--era-block-number`. Immortal if not set.
+	#[structopt(long = "era-period", value_name = "BLOCKS")]
+	era_period: Option<u64>,
+
+	/// The block the mortal transaction is valid from.
+	#[structopt(long = "era-block-number", value_name = "NUMBER")]
+	era_block_number: Option<BlockNumber>,
+
+	/// The hash of `--era-block-number`.
+	#[structopt(long = "era-block-hash", value_name = "HASH", parse(try_from_str = parse_hash))]
+	era_block_hash: Option<Hash>,
+
+	/// The genesis hash of the chain.
+	#[structopt(long = "genesis-hash", value_name = "HASH", parse(try_from_str = parse_hash))]
+	genesis_hash: Hash,
+
+	/// The spec version of the runtime, the native one's if not set.
+	#[structopt(long = "spec-version", value_name = "VERSION")]
+	spec_version: Option<u32>,
+
+	/// The transaction version of the runtime, the native one's if not set.
+	#[structopt(long = "tx-version", value_name = "VERSION")]
+	tx_version: Option<u32>,
+
+	/// Sign with the secret URI.
+	#[structopt(long = "suri", value_name = "SURI", conflicts_with = "signature")]
+	suri: Option<String>,
+
+	/// The password of `--suri`.
+	#[structopt(long = "password", value_name = "PASSWORD")]
+	password: Option<String>,
+
+	/// The crypto scheme of `--suri`, `sr25519`, `ed25519` or `ecdsa`.
+	#[structopt(long = "scheme", value_name = "SCHEME", default_value = "sr25519")]
+	scheme: Scheme,
+
+	/// The account which signed the payload, with `--signature`.
+	#[structopt(long = "signer", value_name = "SS58", requires = "signature")]
+	signer: Option<String>,
+
+	/// The SCALE encoded `MultiSignature` of the payload, by `--signer`.
+	#[structopt(long = "signature", value_name = "HEX", requires = "signer")]
+	signature: Option<String>,
+}
+impl BuildCmd {
+	fn run(&self) -> Result<()> {
+		let call = serde_json::from_str(&self.call)
+			.map_err(|e| format!("Invalid call JSON: {}", e))
+			.and_then(|call| build_call(&call))
+			.map_err(Error::Input)?;
+		let (era, era_hash) = self.era().map_err(Error::Input)?;
+		let extra: SignedExtra = (
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(era),
+			frame_system::CheckNonce::<Runtime>::from(self.nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			darwinia_runtime_common::fee_payment::ChargeTransactionPayment::<Runtime>::from(
+				self.tip,
+			),
+			darwinia_ethereum_relay::CheckEthereumRelayHeaderHash::<Runtime>::new(),
+		);
+		// What `SignedExtra::additional_signed` reads from the chain.
+		let additional_signed = (
+			self.spec_version
+				.unwrap_or(crab_runtime::VERSION.spec_version),
+			self.tx_version
+				.unwrap_or(crab_runtime::VERSION.transaction_version),
+			self.genesis_hash,
+			era_hash,
+			(),
+			(),
+			(),
+			(),
+		);
+		let payload = SignedPayload::from_raw(call, extra, additional_signed);
+		let (signer, signature) = if let Some(suri) = &self.suri {
+			let password = self.password.as_deref();
+
+			match self.scheme {
+				Scheme::Sr25519 => sign::<sr25519::Pair>(suri, password, &payload),
+				Scheme::Ed25519 => sign::<ed25519::Pair>(suri, password, &payload),
+				Scheme::Ecdsa => sign::<ecdsa::Pair>(suri, password, &payload),
+			}
+		} else if let (Some(signer), Some(signature)) = (&self.signer, &self.signature) {
+			AccountId::from_ss58check(signer)
+				.map_err(|e| format!("Invalid signer `{}`: {:?}", signer, e))
+				.and_then(|signer| {
+					decode_hex(signature)
+						.and_then(|signature| {
+							MultiSignature::decode(&mut &signature[..])
+								.map_err(|e| format!("Invalid signature: {}", e))
+						})
+						.map(|signature| (signer, signature))
+				})
+		} else {
+			println!(
+				"Signing payload: 0x{}",
+				HexDisplay::from(&payload.using_encoded(|payload| payload.to_vec()))
+			);
+
+			return Ok(());
+		}
+		.map_err(Error::Input)?;
+		let (call, extra, _) = payload.deconstruct();
+		let extrinsic = UncheckedExtrinsic::new_signed(call, signer, signature, extra);
+
+		println!("Extrinsic: 0x{}", HexDisplay::from(&extrinsic.encode()));
+
+		Ok(())
+	}
+
+	// The era and the block hash it is signed with.
+	fn era(&self) -> std::result::Result<(Era, Hash), String> {
+		let period = match self.era_period {
+			Some(period) => period,
+			None => return Ok((Era::Immortal, self.genesis_hash)),
+		};
+		let (number, hash) = match (self.era_block_number, self.era_block_hash) {
+			(Some(number), Some(hash)) => (number, hash),
+			_ => {
+				return Err(
+					"A mortal transaction requires `--era-block-number` and `--era-block-hash`"
+						.into(),
+				)
+			}
+		};
+		let era = Era::mortal(period, number.into());
+		let birth = era.birth(number.into());
+
+		if birth != u64::from(number) {
+			return Err(format!(
+				"The era of {} blocks starts at block #{}, use `--era-block-number {}` and its hash",
+				period, birth, birth
+			));
+		}
+
+		Ok((era, hash))
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, StructOpt)]
+pub struct SubmitCmd {
+	/// The SCALE encoded signed transaction.
+	#[structopt(long = "extrinsic", value_name = "HEX")]
+	extrinsic: String,
+
+	/// The HTTP RPC endpoint of the node.
+	#[structopt(
+		long = "url",
+		value_name = "URL",
+		default_value = "http://127.0.0.1:9933"
+	)]
+	url: String,
+}
+impl SubmitCmd {
+	fn run(&self) -> Result<()> {
+		let extrinsic = decode_hex(&self.extrinsic)
+			.and_then(|extrinsic| {
+				UncheckedExtrinsic::decode(&mut &extrinsic[..])
+					.map_err(|e| format!("Invalid extrinsic: {}", e))
+			})
+			.map_err(Error::Input)?;
+		let request = json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"method": "author_submitExtrinsic",
+			"params": [format!("0x{}", HexDisplay::from(&extrinsic.encode()))],
+		});
+		let response = tokio::runtime::Runtime::new()?
+			.block_on(post(&self.url, request))
+			.map_err(Error::Other)?;
+
+		match (response.get("result"), response.get("error")) {
+			(Some(hash), _) => {
+				println!("Submitted: {}", hash);
+
+				Ok(())
+			}
+			(_, Some(error)) => Err(Error::Other(format!("Rejected: {}", error))),
+			_ => Err(Error::Other(format!("Unexpected response: {}", response))),
+		}
+	}
+}
+
+async fn post(url: &str, request: Value) -> std::result::Result<Value, String> {
+	let request = hyper::Request::post(url)
+		.header(hyper::header::CONTENT_TYPE, "application/json")
+		.body(hyper::Body::from(request.to_string()))
+		.map_err(|e| e.to_string())?;
+	let response = hyper::Client::new()
+		.request(request)
+		.await
+		.map_err(|e| format!("Failed to reach `{}`: {}", url, e))?;
+	let body = hyper::body::to_bytes(response.into_body())
+		.await
+		.map_err(|e| e.to_string())?;
+
+	serde_json::from_slice(&body).map_err(|e| format!("Invalid response: {}", e))
+}
+
+fn sign<P>(
+	suri: &str,
+	password: Option<&str>,
+	payload: &SignedPayload,
+) -> std::result::Result<(AccountId, MultiSignature), String>
+where
+	P: Pair,
+	AccountPublic: From<P::Public>,
+	MultiSignature: From<P::Signature>,
+{
+	let pair = P::from_string(suri, password).map_err(|e| format!("Invalid SURI: {:?}", e))?;
+	let signature = payload.using_encoded(|payload| pair.sign(payload));
+
+	Ok((
+		AccountPublic::from(pair.public()).into_account(),
+		signature.into(),
+	))
+}
+
+/// A call in JSON.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonCall {
+	pallet: String,
+	call: String,
+	#[serde(default)]
+	args: Map<String, Value>,
+}
+
+fn build_call(json_call: &JsonCall) -> std::result::Result<Call, String> {
+	let encoded_call = encode_call(json_call)?;
+	let mut input = &encoded_call[..];
+	let call = Call::decode(&mut input).map_err(|e| {
+		format!(
+			"Invalid arguments of `{}::{}`: {}",
+			json_call.pallet, json_call.call, e
+		)
+	})?;
+
+	if !input.is_empty() {
+		return Err(format!(
+			"Invalid arguments of `{}::{}`: {} bytes left",
+			json_call.pallet,
+			json_call.call,
+			input.len()
+		));
+	}
+
+	Ok(call)
+}
+
+// The SCALE encoded call, looked up in the metadata of the native runtime.
+fn encode_call(json_call: &JsonCall) -> std::result::Result<Vec<u8>, String> {
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &Runtime::metadata().encode()[..])
+		.map_err(|e| format!("Invalid metadata: {}", e))?;
+	let modules = match metadata.1 {
+		RuntimeMetadata::V11(metadata) => decoded(metadata.modules)?,
+		_ => return Err("Unsupported metadata version".into()),
+	};
+	// The calls of the pallets without any are not indexed.
+	let (pallet_index, calls) = modules
+		.into_iter()
+		.filter_map(|module| {
+			let name = module.name;
+
+			module.calls.map(|calls| (name, calls))
+		})
+		.enumerate()
+		.find_map(|(i, (name, calls))| match decoded(name) {
+			Ok(name) if name == json_call.pallet => Some((i, calls)),
+			_ => None,
+		})
+		.ok_or_else(|| format!("No callable pallet `{}`", json_call.pallet))?;
+	let (call_index, call) = decoded(calls)?
+		.into_iter()
+		.enumerate()
+		.find(|(_, call)| match &call.name {
+			DecodeDifferent::Decoded(name) => name == &json_call.call,
+			_ => false,
+		})
+		.ok_or_else(|| format!("No call `{}::{}`", json_call.pallet, json_call.call))?;
+	let arguments = decoded(call.arguments)?;
+	let mut encoded_call = vec![pallet_index as u8, call_index as u8];
+
+	for name in json_call.args.keys() {
+		if !arguments
+			.iter()
+			.any(|argument| matches!(&argument.name, DecodeDifferent::Decoded(n) if n == name))
+		{
+			return Err(format!(
+				"No argument `{}` in `{}::{}`",
+				name, json_call.pallet, json_call.call
+			));
+		}
+	}
+	for argument in arguments {
+		let name = decoded(argument.name)?;
+		let ty = decoded(argument.ty)?;
+		let value = json_call.args.get(&name).ok_or_else(|| {
+			format!(
+				"Missing argument `{}: {}` of `{}::{}`",
+				name, ty, json_call.pallet, json_call.call
+			)
+		})?;
+
+		encode_argument(&ty, value, &mut encoded_call)
+			.map_err(|e| format!("Invalid argument `{}: {}`: {}", name, ty, e))?;
+	}
+
+	Ok(encoded_call)
+}
+
+fn decoded<B, O>(value: DecodeDifferent<B, O>) -> std::result::Result<O, String>
+where
+	B: 'static,
+	O: 'static,
+{
+	match value {
+		DecodeDifferent::Decoded(value) => Ok(value),
+		DecodeDifferent::Encode(_) => Err("Undecoded metadata".into()),
+	}
+}
+
+// Encode the argument `value` of type `ty`, as named in the metadata.
+fn encode_argument(
+	ty: &str,
+	value: &Value,
+	encoded: &mut Vec<u8>,
+) -> std::result::Result<(), String> {
+	let ty = normalize(ty);
+	let ty = ty.as_str();
+
+	if let Some(scale) = value.get("scale") {
+		let scale = scale.as_str().ok_or("`scale` must be a hex string")?;
+
+		encoded.extend(decode_hex(scale)?);
+
+		return Ok(());
+	}
+
+	if let Some(ty) = ty
+		.strip_prefix("Compact<")
+		.and_then(|ty| ty.strip_suffix('>'))
+	{
+		if U32_TYPES.contains(&ty) {
+			Compact(as_uint::<u32>(value, ty)?).encode_to(encoded);
+		} else if U64_TYPES.contains(&ty) {
+			Compact(as_uint::<u64>(value, ty)?).encode_to(encoded);
+		} else if BALANCE_TYPES.contains(&ty) {
+			Compact(as_u128(value)?).encode_to(encoded);
+		} else {
+			return Err(unknown_type());
+		}
+	} else if ty == "bool" {
+		value
+			.as_bool()
+			.ok_or("expected a boolean")?
+			.encode_to(encoded);
+	} else if ty == "u8" {
+		as_uint::<u8>(value, ty)?.encode_to(encoded);
+	} else if ty == "u16" {
+		as_uint::<u16>(value, ty)?.encode_to(encoded);
+	} else if U32_TYPES.contains(&ty) {
+		as_uint::<u32>(value, ty)?.encode_to(encoded);
+	} else if U64_TYPES.contains(&ty) {
+		as_uint::<u64>(value, ty)?.encode_to(encoded);
+	} else if BALANCE_TYPES.contains(&ty) {
+		as_u128(value)?.encode_to(encoded);
+	} else if ACCOUNT_ID_TYPES.contains(&ty) {
+		let account = value.as_str().ok_or("expected an SS58 address")?;
+
+		AccountId::from_ss58check(account)
+			.map_err(|e| format!("invalid SS58 address: {:?}", e))?
+			.encode_to(encoded);
+	} else if HASH_TYPES.contains(&ty) {
+		let hash = value.as_str().ok_or("expected a hex hash")?;
+
+		parse_hash(hash)?.encode_to(encoded);
+	} else if BYTES_TYPES.contains(&ty) {
+		let bytes = value.as_str().ok_or("expected a hex or a plain string")?;
+
+		if bytes.starts_with("0x") {
+			decode_hex(bytes)?.encode_to(encoded);
+		} else {
+			bytes.as_bytes().encode_to(encoded);
+		}
+	} else if CALL_TYPES.contains(&ty) || CALLS_TYPES.contains(&ty) {
+		let calls = |value: &Value| {
+			serde_json::from_value::<JsonCall>(value.clone())
+				.map_err(|e| format!("invalid call: {}", e))
+				.and_then(|call| encode_call(&call))
+		};
+
+		if CALLS_TYPES.contains(&ty) {
+			let values = value.as_array().ok_or("expected an array of calls")?;
+
+			Compact(values.len() as u32).encode_to(encoded);
+
+			for value in values {
+				encoded.extend(calls(value)?);
+			}
+		} else {
+			encoded.extend(calls(value)?);
+		}
+	} else {
+		return Err(unknown_type());
+	}
+
+	Ok(())
+}
+
+fn unknown_type() -> String {
+	"unknown type, give it as `{\"scale\": \"0x...\"}`".into()
+}
+
+// The integer `value` of type `ty`, checked to fit in `T`.
+fn as_uint<T: TryFrom<u128>>(value: &Value, ty: &str) -> std::result::Result<T, String> {
+	let value = as_u128(value)?;
+
+	T::try_from(value).map_err(|_| format!("{} is out of the range of `{}`", value, ty))
+}
+
+fn as_u128(value: &Value) -> std::result::Result<u128, String> {
+	match value {
+		Value::Number(number) => number
+			.as_u64()
+			.map(Into::into)
+			.ok_or("expected an unsigned integer".into()),
+		Value::String(number) => number
+			.parse()
+			.map_err(|e| format!("invalid integer `{}`: {}", number, e)),
+		_ => Err("expected an integer, or an integer string".into()),
+	}
+}
+
+fn decode_hex(hex: &str) -> std::result::Result<Vec<u8>, String> {
+	sp_core::bytes::from_hex(hex).map_err(|e| format!("Invalid hex `{}`: {:?}", hex, e))
+}
+
+fn parse_hash(hash: &str) -> std::result::Result<Hash, String> {
+	H256::from_str(hash.trim_start_matches("0x")).map_err(|e| format!("Invalid hash: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	// --- darwinia ---
+	use super::*;
+
+	fn encode(ty: &str, value: Value) -> std::result::Result<Vec<u8>, String> {
+		let mut encoded = vec![];
+
+		encode_argument(ty, &value, &mut encoded).map(|_| encoded)
+	}
+
+	#[test]
+	fn encode_argument_should_encode_the_known_types() {
+		assert_eq!(encode("u8", json!(255)), Ok(vec![255]));
+		assert_eq!(encode("u16", json!("513")), Ok(vec![1, 2]));
+		assert_eq!(encode("T::BlockNumber", json!(1)), Ok(1u32.encode()));
+		assert_eq!(encode("Compact<T::BlockNumber>", json!(1)), Ok(vec![4]));
+		assert_eq!(encode("Weight", json!(1)), Ok(1u64.encode()));
+		assert_eq!(
+			encode("BalanceOf<T, I>", json!(u128::max_value().to_string())),
+			Ok(u128::max_value().encode())
+		);
+		assert_eq!(encode("bool", json!(true)), Ok(vec![1]));
+		assert_eq!(encode("Vec<u8>", json!("0x0102")), Ok(vec![8, 1, 2]));
+		assert_eq!(encode("Bytes", json!("ab")), Ok(vec![8, b'a', b'b']));
+	}
+
+	#[test]
+	fn encode_argument_should_check_the_range_of_the_integers() {
+		for (ty, value) in &[
+			("u8", json!(256)),
+			("u16", json!(65_536)),
+			("T::BlockNumber", json!(u32::max_value() as u64 + 1)),
+			("Compact<EraIndex>", json!(u32::max_value() as u64 + 1)),
+			("Moment", json!("18446744073709551616")),
+		] {
+			let e = encode(ty, value.clone()).unwrap_err();
+
+			assert!(e.contains("out of the range"), "{}: {}", ty, e);
+		}
+
+		assert!(encode("Balance", json!("340282366920938463463374607431768211456")).is_err());
+		assert!(encode("u32", json!(-1)).is_err());
+	}
+
+	#[test]
+	fn encode_argument_should_reject_the_unknown_types() {
+		for ty in &[
+			"Vec<Balance>",
+			"Option<T::BlockNumber>",
+			"MyIndex",
+			"Compact<Perbill>",
+			"Vec<T::AccountId>",
+		] {
+			assert_eq!(encode(ty, json!(1)), Err(unknown_type()), "{}", ty);
+		}
+
+		assert_eq!(
+			encode("Vec<Balance>", json!({ "scale": "0x0401" })),
+			Ok(vec![4, 1])
+		);
+	}
+}
@@ -1,5 +1,7 @@
 //! Darwinia chain configurations.
 
+// --- std ---
+use std::{collections::BTreeSet, fs::File, str::FromStr};
 // --- crates ---
 use serde::{Deserialize, Serialize};
 // --- substrate ---
@@ -15,7 +17,7 @@ use sp_runtime::{traits::IdentifyAccount, Perbill, Perquintill};
 // --- darwinia ---
 use array_bytes::fixed_hex_bytes_unchecked;
 use crab_runtime::{constants::currency::COIN, GenesisConfig as CrabGenesisConfig};
-use darwinia_primitives::{AccountId, AccountPublic, Balance};
+use darwinia_primitives::{AccountId, AccountPublic, Balance, BlockNumber, Hash};
 
 /// The `ChainSpec parametrised for Crab runtime`.
 pub type CrabChainSpec = sc_service::GenericChainSpec<CrabGenesisConfig, Extensions>;
@@ -34,6 +36,123 @@ pub struct Extensions {
 	pub fork_blocks: sc_client_api::ForkBlocks<darwinia_primitives::Block>,
 	/// Known bad block hashes.
 	pub bad_blocks: sc_client_api::BadBlocks<darwinia_primitives::Block>,
+	/// GRANDPA settings.
+	#[serde(default)]
+	pub grandpa: GrandpaSettings,
+	/// GRANDPA authority set hard forks.
+	#[serde(default)]
+	pub grandpa_hard_forks: Vec<GrandpaHardFork>,
+}
+
+/// GRANDPA settings, customizable from the chain spec and overridable from the command line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrandpaSettings {
+	/// The gossip duration of a round, in milliseconds.
+	pub gossip_duration_millis: u64,
+	/// The number of blocks between two justifications generated for a block.
+	pub justification_period: u32,
+	/// Whether the GRANDPA observer protocol is enabled.
+	pub observer_enabled: bool,
+}
+impl Default for GrandpaSettings {
+	fn default() -> Self {
+		Self {
+			gossip_duration_millis: 1000,
+			justification_period: 512,
+			observer_enabled: false,
+		}
+	}
+}
+
+/// A GRANDPA authority set hard fork, forcing the authority set `set_id` at the given block.
+///
+/// Customizable from the chain spec and from the command line as
+/// `set_id:block_hash:block_number:authorities.json`, where the JSON file holds the
+/// `[authority, weight]` pairs of the new authority set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrandpaHardFork {
+	/// The id of the forced authority set.
+	pub set_id: u64,
+	/// The hash of the block the authority set is forced at.
+	pub block_hash: Hash,
+	/// The number of the block the authority set is forced at.
+	pub block_number: BlockNumber,
+	/// The forced authority set, with the weight of each authority.
+	pub authorities: Vec<(GrandpaId, u64)>,
+}
+impl GrandpaHardFork {
+	/// Check the forced authority set is usable.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.authorities.is_empty() {
+			return Err(format!(
+				"GRANDPA hard fork of set {} has no authorities",
+				self.set_id
+			));
+		}
+		if self.authorities.iter().any(|(_, weight)| *weight == 0) {
+			return Err(format!(
+				"GRANDPA hard fork of set {} has an authority of zero weight",
+				self.set_id
+			));
+		}
+
+		let mut authorities = BTreeSet::new();
+
+		for (authority, _) in &self.authorities {
+			if !authorities.insert(authority) {
+				return Err(format!(
+					"GRANDPA hard fork of set {} has the duplicated authority {}",
+					self.set_id, authority
+				));
+			}
+		}
+
+		Ok(())
+	}
+}
+impl FromStr for GrandpaHardFork {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let parts = s.splitn(4, ':').collect::<Vec<_>>();
+
+		if parts.len() != 4 {
+			return Err(format!(
+				"Invalid GRANDPA hard fork `{}`, expected `set_id:block_hash:block_number:authorities.json`",
+				s
+			));
+		}
+
+		let set_id = parts[0]
+			.parse()
+			.map_err(|e| format!("Invalid GRANDPA hard fork set id `{}`: {}", parts[0], e))?;
+		let block_hash = parts[1]
+			.trim_start_matches("0x")
+			.parse()
+			.map_err(|e| format!("Invalid GRANDPA hard fork block hash `{}`: {}", parts[1], e))?;
+		let block_number = parts[2].parse().map_err(|e| {
+			format!(
+				"Invalid GRANDPA hard fork block number `{}`: {}",
+				parts[2], e
+			)
+		})?;
+		let authorities = File::open(parts[3])
+			.map_err(|e| format!("Failed to open GRANDPA authorities `{}`: {}", parts[3], e))
+			.and_then(|file| {
+				serde_json::from_reader(file).map_err(|e| {
+					format!("Failed to read GRANDPA authorities `{}`: {}", parts[3], e)
+				})
+			})?;
+
+		Ok(Self {
+			set_id,
+			block_hash,
+			block_number,
+			authorities,
+		})
+	}
 }
 
 pub fn crab_config() -> Result<CrabChainSpec, String> {
@@ -328,6 +447,12 @@ pub fn crab_build_spec_genesis() -> CrabGenesisConfig {
 		darwinia_crab_issuing: Some(crab_runtime::CrabIssuingConfig {
 			total_mapped_ring: 40_000_000 * COIN,
 		}),
+		vesting_Instance0: Some(crab_runtime::VestingConfig { vesting: vec![] }),
+		vesting_Instance1: Some(crab_runtime::KtonVestingConfig { vesting: vec![] }),
+		nft_bridge: Some(crab_runtime::NftBridgeConfig {
+			// Minting stays disabled until the `NftLocker` contract is deployed and set here.
+			nft_locker_address: Default::default(),
+		}),
 	}
 }
 
@@ -441,7 +566,7 @@ pub fn crab_testnet_genesis(
 		}),
 		darwinia_staking: Some(crab_runtime::StakingConfig {
 			minimum_validator_count: 1,
-			validator_count: 2,
+			validator_count: initial_authorities.len().max(2) as _,
 			stakers: initial_authorities
 				.iter()
 				.cloned()
@@ -516,6 +641,12 @@ pub fn crab_testnet_genesis(
 		darwinia_crab_issuing: Some(crab_runtime::CrabIssuingConfig {
 			total_mapped_ring: 1 << 60
 		}),
+		vesting_Instance0: Some(crab_runtime::VestingConfig { vesting: vec![] }),
+		vesting_Instance1: Some(crab_runtime::KtonVestingConfig { vesting: vec![] }),
+		nft_bridge: Some(crab_runtime::NftBridgeConfig {
+			// Minting stays disabled until the `NftLocker` contract is deployed and set here.
+			nft_locker_address: Default::default(),
+		}),
 	}
 }
 
@@ -577,3 +708,51 @@ pub fn crab_local_testnet_config() -> CrabChainSpec {
 		Default::default(),
 	)
 }
+
+/// The seeds of the validators of a local testnet, the well known ones first.
+pub fn testnet_validator_seeds(validators: u16) -> Vec<String> {
+	const WELL_KNOWN_SEEDS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+	(0..validators as usize)
+		.map(|i| match WELL_KNOWN_SEEDS.get(i) {
+			Some(seed) => (*seed).to_owned(),
+			None => format!("Validator{}", i + 1),
+		})
+		.collect()
+}
+
+/// Crab local testnet config (a validator for each of `seeds`, the first one being root)
+pub fn crab_multi_validator_testnet_config(seeds: &[String]) -> CrabChainSpec {
+	let initial_authorities = seeds
+		.iter()
+		.map(|seed| get_authority_keys_from_seed(seed))
+		.collect::<Vec<_>>();
+	let root_key = get_account_id_from_seed::<sr25519::Public>(&seeds[0]);
+	let endowed_accounts = seeds
+		.iter()
+		.flat_map(|seed| {
+			vec![
+				get_account_id_from_seed::<sr25519::Public>(seed),
+				get_account_id_from_seed::<sr25519::Public>(&format!("{}//stash", seed)),
+			]
+		})
+		.collect::<Vec<_>>();
+
+	CrabChainSpec::from_genesis(
+		"Crab Local Testnet",
+		"crab_local_testnet",
+		ChainType::Local,
+		move || {
+			crab_testnet_genesis(
+				initial_authorities.clone(),
+				root_key.clone(),
+				Some(endowed_accounts.clone()),
+			)
+		},
+		vec![],
+		None,
+		Some(DEFAULT_PROTOCOL_ID),
+		Some(crab_properties()),
+		Default::default(),
+	)
+}
@@ -0,0 +1,1037 @@
+//! Export the blocks, along with their extrinsics and events, to JSONL files or to a SQLite
+//! database for the indexers.
+//!
+//! The extrinsics and the events are decoded with the metadata of the runtime of their block. The
+//! arguments of the known types, matched by their exact name, are exported as JSON values, the rest
+//! as `{"scale": "0x.."}`.
+//! The events of the native runtime are decoded with its types first, which tells where each of
+//! them ends, so an argument of unknown type only leaves the rest of its own event undecoded. In
+//! the blocks of the other runtimes, the end of such an event can't be told, so it ends the events
+//! of its block. An extrinsic with an unknown signed extension is exported as an unknown call.
+//! The JSONL files are rotated every `rotate_blocks` blocks and named after the first block
+//! they may hold, a line being either a `block` or the `finalized` block number and hash.
+
+// --- std ---
+use std::{
+	fs::{self, File, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::Arc,
+};
+// --- crates ---
+use codec::{Compact, Decode, Encode};
+use frame_metadata::{DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed};
+use futures::{
+	future,
+	stream::{self, StreamExt},
+};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+// --- substrate ---
+use frame_system::{EventRecord, Phase};
+use sc_client_api::{Backend, BlockBackend, BlockchainEvents, StorageProvider};
+use sp_api::{Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	crypto::Ss58Codec, hexdisplay::HexDisplay, storage::StorageKey, twox_128, H160, U256,
+};
+use sp_runtime::{
+	generic::{BlockId, Era},
+	traits::{BlakeTwo256, Hash as HashT, Header as HeaderT},
+	DispatchError, MultiSignature,
+};
+// --- darwinia ---
+use crate::types::{
+	normalize, ACCOUNT_ID_TYPES, BALANCE_TYPES, BYTES_TYPES, ETHEREUM_ADDRESS_TYPES, HASH_TYPES,
+	RELAY_AFFIRMATION_ID_TYPES, U32_TYPES, U64_TYPES,
+};
+use darwinia_primitives::{AccountId, Block, BlockNumber, Hash};
+
+const SQLITE_SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS blocks (
+		hash TEXT PRIMARY KEY,
+		number INTEGER NOT NULL,
+		parent_hash TEXT NOT NULL,
+		finalized INTEGER NOT NULL
+	);
+	CREATE INDEX IF NOT EXISTS blocks_number ON blocks (number);
+	CREATE TABLE IF NOT EXISTS extrinsics (
+		block_hash TEXT NOT NULL,
+		idx INTEGER NOT NULL,
+		hash TEXT NOT NULL,
+		signer TEXT,
+		pallet TEXT NOT NULL,
+		call TEXT NOT NULL,
+		args TEXT NOT NULL,
+		success INTEGER,
+		PRIMARY KEY (block_hash, idx)
+	);
+	CREATE INDEX IF NOT EXISTS extrinsics_signer ON extrinsics (signer);
+	CREATE TABLE IF NOT EXISTS events (
+		block_hash TEXT NOT NULL,
+		idx INTEGER NOT NULL,
+		extrinsic_idx INTEGER,
+		pallet TEXT NOT NULL,
+		event TEXT NOT NULL,
+		args TEXT NOT NULL,
+		PRIMARY KEY (block_hash, idx)
+	);
+	CREATE INDEX IF NOT EXISTS events_name ON events (pallet, event);
+";
+
+/// The formats of the block export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+	Jsonl,
+	Sqlite,
+}
+impl FromStr for ExportFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_ref() {
+			"jsonl" => Ok(Self::Jsonl),
+			"sqlite" => Ok(Self::Sqlite),
+			_ => Err(format!(
+				"Unsupported export format `{}`, expected `jsonl` or `sqlite`",
+				s
+			)),
+		}
+	}
+}
+
+/// The settings of the block export.
+#[derive(Clone, Debug)]
+pub struct ExportConfig {
+	/// The directory of the JSONL files, or the SQLite database file.
+	pub path: PathBuf,
+	pub format: ExportFormat,
+	/// Export the finalized blocks only, instead of each imported block.
+	pub finalized_only: bool,
+	/// The blocks of a JSONL file.
+	pub rotate_blocks: BlockNumber,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedBlock {
+	number: BlockNumber,
+	hash: Hash,
+	parent_hash: Hash,
+	finalized: bool,
+	extrinsics: Vec<ExportedExtrinsic>,
+	events: Vec<ExportedEvent>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedExtrinsic {
+	index: u32,
+	hash: Hash,
+	/// `None` if the extrinsic is unsigned.
+	signer: Option<String>,
+	pallet: String,
+	call: String,
+	args: Value,
+	/// `None` if the block has no dispatch event for the extrinsic.
+	success: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedEvent {
+	index: u32,
+	/// `None` if the event is emitted by the initialization or the finalization of the block.
+	extrinsic_index: Option<u32>,
+	pallet: String,
+	event: String,
+	args: Vec<Value>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum JsonlLine<'a> {
+	Block(&'a ExportedBlock),
+	Finalized { number: BlockNumber, hash: Hash },
+}
+
+enum Sink {
+	Jsonl {
+		directory: PathBuf,
+		rotate_blocks: BlockNumber,
+		/// The first block of the open file, and the file.
+		file: Option<(BlockNumber, File)>,
+	},
+	Sqlite(Connection),
+}
+impl Sink {
+	fn open(config: &ExportConfig) -> Result<Self, String> {
+		match config.format {
+			ExportFormat::Jsonl => {
+				fs::create_dir_all(&config.path).map_err(|e| e.to_string())?;
+
+				Ok(Self::Jsonl {
+					directory: config.path.clone(),
+					rotate_blocks: config.rotate_blocks.max(1),
+					file: None,
+				})
+			}
+			ExportFormat::Sqlite => {
+				if let Some(directory) = config.path.parent() {
+					fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+				}
+
+				let connection = Connection::open(&config.path).map_err(|e| e.to_string())?;
+
+				connection
+					.execute_batch(SQLITE_SCHEMA)
+					.map_err(|e| e.to_string())?;
+
+				Ok(Self::Sqlite(connection))
+			}
+		}
+	}
+
+	fn write_block(&mut self, block: &ExportedBlock) -> Result<(), String> {
+		match self {
+			Self::Jsonl { .. } => self.write_line(block.number, &JsonlLine::Block(block)),
+			Self::Sqlite(connection) => {
+				let transaction = connection.transaction().map_err(|e| e.to_string())?;
+				let block_hash = to_hex(block.hash.as_bytes());
+
+				transaction
+					.execute(
+						"INSERT OR REPLACE INTO blocks VALUES (?1, ?2, ?3, ?4)",
+						params![
+							block_hash,
+							block.number,
+							to_hex(block.parent_hash.as_bytes()),
+							block.finalized
+						],
+					)
+					.map_err(|e| e.to_string())?;
+
+				for extrinsic in &block.extrinsics {
+					transaction
+						.execute(
+							"INSERT OR REPLACE INTO extrinsics VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+							params![
+								block_hash,
+								extrinsic.index,
+								to_hex(extrinsic.hash.as_bytes()),
+								extrinsic.signer,
+								extrinsic.pallet,
+								extrinsic.call,
+								extrinsic.args.to_string(),
+								extrinsic.success
+							],
+						)
+						.map_err(|e| e.to_string())?;
+				}
+				for event in &block.events {
+					transaction
+						.execute(
+							"INSERT OR REPLACE INTO events VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+							params![
+								block_hash,
+								event.index,
+								event.extrinsic_index,
+								event.pallet,
+								event.event,
+								Value::Array(event.args.clone()).to_string()
+							],
+						)
+						.map_err(|e| e.to_string())?;
+				}
+
+				transaction.commit().map_err(|e| e.to_string())
+			}
+		}
+	}
+
+	fn write_finalized(&mut self, number: BlockNumber, hash: Hash) -> Result<(), String> {
+		match self {
+			Self::Jsonl { .. } => self.write_line(number, &JsonlLine::Finalized { number, hash }),
+			Self::Sqlite(connection) => connection
+				.execute(
+					"UPDATE blocks SET finalized = 1 WHERE hash = ?1",
+					params![to_hex(hash.as_bytes())],
+				)
+				.map(|_| ())
+				.map_err(|e| e.to_string()),
+		}
+	}
+
+	// Append `line` to the file of block `number`, rotating the open one if it is another.
+	fn write_line(&mut self, number: BlockNumber, line: &JsonlLine) -> Result<(), String> {
+		if let Self::Jsonl {
+			directory,
+			rotate_blocks,
+			file,
+		} = self
+		{
+			let first_block = number / *rotate_blocks * *rotate_blocks;
+
+			if file.as_ref().map(|(first, _)| *first) != Some(first_block) {
+				*file = Some((first_block, open_jsonl(directory, first_block)?));
+			}
+			if let Some((_, file)) = file {
+				let mut line = serde_json::to_vec(line).map_err(|e| e.to_string())?;
+
+				line.push(b'\n');
+				file.write_all(&line).map_err(|e| e.to_string())?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// `(pallet, [(call, [(argument, type)])])` of the callable pallets, by pallet index.
+type CallNames = Vec<(String, Vec<(String, Vec<(String, String)>)>)>;
+/// `(pallet, [(event, [type])])` of the pallets with events, by pallet index.
+type EventNames = Vec<(String, Vec<(String, Vec<String>)>)>;
+
+/// The names of the calls and events of a runtime, read in its metadata.
+struct RuntimeNames {
+	calls: CallNames,
+	events: EventNames,
+	/// The signed extensions of the extrinsics, in order.
+	signed_extensions: Vec<String>,
+	/// Whether the runtime is the native one, whose events can be decoded with its types.
+	native: bool,
+}
+impl RuntimeNames {
+	fn from_metadata(metadata: &[u8]) -> Result<Self, String> {
+		let (modules, signed_extensions) = match RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+			.map_err(|e| format!("Invalid metadata: {}", e))?
+			.1
+		{
+			RuntimeMetadata::V11(metadata) => (
+				decoded(metadata.modules)?,
+				metadata
+					.extrinsic
+					.signed_extensions
+					.into_iter()
+					.map(decoded)
+					.collect::<Result<_, _>>()?,
+			),
+			_ => return Err("Unsupported metadata version".into()),
+		};
+		let mut runtime_names = Self {
+			calls: vec![],
+			events: vec![],
+			signed_extensions,
+			native: metadata == &crab_runtime::Runtime::metadata().encode()[..],
+		};
+
+		for module in modules {
+			let pallet = decoded(module.name)?;
+
+			if let Some(calls) = module.calls {
+				let mut call_names = vec![];
+
+				for call in decoded(calls)? {
+					let mut arguments = vec![];
+
+					for argument in decoded(call.arguments)? {
+						arguments.push((decoded(argument.name)?, decoded(argument.ty)?));
+					}
+
+					call_names.push((decoded(call.name)?, arguments));
+				}
+
+				runtime_names.calls.push((pallet.clone(), call_names));
+			}
+			if let Some(events) = module.event {
+				let mut event_names = vec![];
+
+				for event in decoded(events)? {
+					event_names.push((decoded(event.name)?, decoded(event.arguments)?));
+				}
+
+				runtime_names.events.push((pallet, event_names));
+			}
+		}
+
+		Ok(runtime_names)
+	}
+
+	// The pallet, the name and the named arguments of the SCALE encoded `call`.
+	fn call(&self, call: &[u8]) -> (String, String, Value) {
+		let names = call.get(..2).and_then(|index| {
+			let (pallet, calls) = self.calls.get(index[0] as usize)?;
+
+			calls
+				.get(index[1] as usize)
+				.map(|(call, arguments)| (pallet, call, arguments))
+		});
+		let (pallet, call_name, arguments) = match names {
+			Some(names) => names,
+			None => return unknown(call),
+		};
+		let mut input = &call[2..];
+		let mut args = Map::new();
+
+		for (name, ty) in arguments {
+			match decode_argument(ty, &mut input) {
+				Some(value) => {
+					args.insert(name.to_owned(), value);
+				}
+				None => {
+					// The rest of the arguments can't be told apart.
+					args.insert(name.to_owned(), json!({ "scale": to_hex(input) }));
+
+					break;
+				}
+			}
+		}
+
+		(pallet.to_owned(), call_name.to_owned(), Value::Object(args))
+	}
+
+	// The signer and the call of the SCALE encoded `extrinsic`, `None` if its format or one of its
+	// signed extensions is unknown.
+	fn extrinsic<'a>(&self, extrinsic: &'a [u8]) -> Option<(Option<AccountId>, &'a [u8])> {
+		let mut input = extrinsic;
+
+		<Compact<u32>>::decode(&mut input).ok()?;
+
+		let version = u8::decode(&mut input).ok()?;
+
+		if version & 0b0111_1111 != 4 {
+			return None;
+		}
+
+		let signer = if version & 0b1000_0000 == 0 {
+			None
+		} else {
+			let signer = AccountId::decode(&mut input).ok()?;
+
+			MultiSignature::decode(&mut input).ok()?;
+
+			for signed_extension in &self.signed_extensions {
+				skip_signed_extension(signed_extension, &mut input)?;
+			}
+
+			Some(signer)
+		};
+
+		Some((signer, input))
+	}
+
+	// The records of the SCALE encoded `System::Events`. Decoded with the native runtime, an argument
+	// of unknown type only ends its own event, else the records end at the first such event.
+	fn events(&self, records: &[u8]) -> Vec<ExportedEvent> {
+		let mut input = records;
+		let count = match <Compact<u32>>::decode(&mut input) {
+			Ok(count) => count.0,
+			Err(_) => return vec![],
+		};
+		let mut events = vec![];
+
+		for i in 0..count {
+			let record_len = if self.native {
+				native_record_len(input)
+			} else {
+				None
+			};
+			let mut record = match record_len {
+				Some((len, _)) => &input[..len],
+				None => input,
+			};
+			let extrinsic_index = match Phase::decode(&mut record) {
+				Ok(Phase::ApplyExtrinsic(extrinsic_index)) => Some(extrinsic_index),
+				Ok(_) => None,
+				Err(_) => break,
+			};
+			let (pallet, event, args, complete) = match record_len {
+				Some((_, topics_len)) => self.event(&mut &record[..record.len() - topics_len]),
+				None => self.event(&mut record),
+			};
+
+			events.push(ExportedEvent {
+				index: i,
+				extrinsic_index,
+				pallet,
+				event,
+				args,
+			});
+
+			match record_len {
+				Some((len, _)) => input = &input[len..],
+				None => {
+					// The topics follow the event.
+					if !complete || <Vec<Hash>>::decode(&mut record).is_err() {
+						break;
+					}
+
+					input = record;
+				}
+			}
+		}
+
+		events
+	}
+
+	// The pallet, the name and the arguments of the SCALE encoded event at the start of `input`,
+	// and whether its end is known.
+	fn event(&self, input: &mut &[u8]) -> (String, String, Vec<Value>, bool) {
+		let names = input.get(..2).and_then(|index| {
+			let (pallet, events) = self.events.get(index[0] as usize)?;
+
+			events
+				.get(index[1] as usize)
+				.map(|(event, arguments)| (pallet, event, arguments))
+		});
+		let (pallet, event_name, arguments) = match names {
+			Some(names) => names,
+			None => {
+				let (pallet, event_name, args) = unknown(input);
+
+				return (pallet, event_name, vec![args], false);
+			}
+		};
+		let mut args = vec![];
+
+		*input = &input[2..];
+
+		for ty in arguments {
+			match decode_argument(ty, input) {
+				Some(value) => args.push(value),
+				None => {
+					args.push(json!({ "scale": to_hex(input) }));
+
+					return (pallet.to_owned(), event_name.to_owned(), args, false);
+				}
+			}
+		}
+
+		(pallet.to_owned(), event_name.to_owned(), args, true)
+	}
+}
+
+struct Exporter<C> {
+	client: Arc<C>,
+	sink: Sink,
+	/// The `System::LastRuntimeUpgrade` the names are read with, and the names.
+	runtime_names: Option<(Option<Vec<u8>>, RuntimeNames)>,
+}
+impl<C> Exporter<C> {
+	fn export_block<B>(&mut self, hash: Hash, finalized: bool) -> Result<(), String>
+	where
+		B: Backend<Block>,
+		C: BlockBackend<Block>
+			+ HeaderBackend<Block>
+			+ StorageProvider<Block, B>
+			+ ProvideRuntimeApi<Block>,
+		C::Api: Metadata<Block>,
+	{
+		let at = BlockId::Hash(hash);
+		let header = self
+			.client
+			.header(at)
+			.map_err(|e| e.to_string())?
+			.ok_or("Unknown block")?;
+		let body = self
+			.client
+			.block_body(&at)
+			.map_err(|e| e.to_string())?
+			.unwrap_or_default();
+		let records = self
+			.client
+			.storage(&at, &system_storage_key(b"Events"))
+			.map_err(|e| e.to_string())?
+			.map(|records| records.0)
+			.unwrap_or_default();
+		let runtime_names = self.runtime_names(&at)?;
+		let events = runtime_names.events(&records);
+		let mut extrinsics = vec![];
+
+		for (i, opaque_extrinsic) in body.iter().enumerate() {
+			let encoded_extrinsic = opaque_extrinsic.encode();
+			let (signer, (pallet, call, args)) = match runtime_names.extrinsic(&encoded_extrinsic) {
+				Some((signer, call)) => (signer, runtime_names.call(call)),
+				None => (None, unknown(&encoded_extrinsic)),
+			};
+			let success = events
+				.iter()
+				.filter(|event| event.extrinsic_index == Some(i as _) && event.pallet == "System")
+				.find_map(|event| match event.event.as_str() {
+					"ExtrinsicSuccess" => Some(true),
+					"ExtrinsicFailed" => Some(false),
+					_ => None,
+				});
+
+			extrinsics.push(ExportedExtrinsic {
+				index: i as _,
+				hash: BlakeTwo256::hash(&encoded_extrinsic),
+				signer: signer.map(|signer| signer.to_ss58check()),
+				pallet,
+				call,
+				args,
+				success,
+			});
+		}
+
+		self.sink.write_block(&ExportedBlock {
+			number: *header.number(),
+			hash,
+			parent_hash: *header.parent_hash(),
+			finalized,
+			extrinsics,
+			events,
+		})
+	}
+
+	// The names of the runtime of block `at`, read again on a runtime upgrade only.
+	fn runtime_names<B>(&mut self, at: &BlockId<Block>) -> Result<&RuntimeNames, String>
+	where
+		B: Backend<Block>,
+		C: StorageProvider<Block, B> + ProvideRuntimeApi<Block>,
+		C::Api: Metadata<Block>,
+	{
+		let last_runtime_upgrade = self
+			.client
+			.storage(at, &system_storage_key(b"LastRuntimeUpgrade"))
+			.map_err(|e| e.to_string())?
+			.map(|last_runtime_upgrade| last_runtime_upgrade.0);
+
+		if self.runtime_names.as_ref().map(|(upgrade, _)| upgrade) != Some(&last_runtime_upgrade) {
+			let metadata = self
+				.client
+				.runtime_api()
+				.metadata(at)
+				.map_err(|e| format!("Failed to read the metadata: {:?}", e))?;
+
+			self.runtime_names = Some((
+				last_runtime_upgrade,
+				RuntimeNames::from_metadata(&metadata)?,
+			));
+		}
+
+		Ok(&self
+			.runtime_names
+			.as_ref()
+			.expect("`runtime_names` is set above; qed")
+			.1)
+	}
+}
+
+enum Notification {
+	Imported(Hash),
+	Finalized(Hash),
+}
+
+/// Export each imported block, or each finalized one if `finalized_only`, and mark the finalized
+/// ones as such.
+pub async fn exporter_task<B, C>(client: Arc<C>, config: ExportConfig)
+where
+	B: Backend<Block>,
+	C: BlockchainEvents<Block>
+		+ BlockBackend<Block>
+		+ HeaderBackend<Block>
+		+ StorageProvider<Block, B>
+		+ ProvideRuntimeApi<Block>,
+	C::Api: Metadata<Block>,
+{
+	let sink = match Sink::open(&config) {
+		Ok(sink) => sink,
+		Err(e) => {
+			log::error!(
+				"Failed to open the block export `{}`: {}",
+				config.path.display(),
+				e
+			);
+
+			return;
+		}
+	};
+	let finalized_only = config.finalized_only;
+	let imported_blocks = client
+		.import_notification_stream()
+		.filter_map(move |notification| {
+			future::ready(if finalized_only {
+				None
+			} else {
+				Some(Notification::Imported(notification.hash))
+			})
+		});
+	let finalized_blocks = client
+		.finality_notification_stream()
+		.map(|notification| Notification::Finalized(notification.hash));
+	let mut notifications = stream::select(imported_blocks, finalized_blocks);
+	let mut last_finalized = client.info().finalized_number;
+	let mut exporter = Exporter {
+		client: client.clone(),
+		sink,
+		runtime_names: None,
+	};
+
+	log::info!("📦 Exporting blocks to `{}`", config.path.display());
+
+	while let Some(notification) = notifications.next().await {
+		let result = match notification {
+			Notification::Imported(hash) => exporter.export_block(hash, false),
+			Notification::Finalized(hash) => {
+				// A finality notification finalizes the blocks since the last one as well.
+				finalized_route(&*client, hash, last_finalized).and_then(|route| {
+					for (number, hash) in route {
+						if finalized_only {
+							exporter.export_block(hash, true)?;
+						} else {
+							exporter.sink.write_finalized(number, hash)?;
+						}
+
+						last_finalized = number;
+					}
+
+					Ok(())
+				})
+			}
+		};
+
+		if let Err(e) = result {
+			log::warn!("Failed to export a block: {}", e);
+		}
+	}
+}
+
+// The blocks from the one after `last_finalized` to `hash`, in ascending order.
+fn finalized_route<C>(
+	client: &C,
+	mut hash: Hash,
+	last_finalized: BlockNumber,
+) -> Result<Vec<(BlockNumber, Hash)>, String>
+where
+	C: HeaderBackend<Block>,
+{
+	let mut route = vec![];
+
+	loop {
+		let header = client
+			.header(BlockId::Hash(hash))
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Unknown finalized block {}", hash))?;
+
+		if *header.number() <= last_finalized {
+			break;
+		}
+
+		route.push((*header.number(), hash));
+		hash = *header.parent_hash();
+	}
+
+	route.reverse();
+
+	Ok(route)
+}
+
+// The length of the SCALE encoded record at the start of `input` and of its topics, decoded with
+// the events of the native runtime.
+fn native_record_len(input: &[u8]) -> Option<(usize, usize)> {
+	let mut rest = input;
+	let record = <EventRecord<crab_runtime::Event, Hash>>::decode(&mut rest).ok()?;
+
+	Some((input.len() - rest.len(), record.topics.encoded_size()))
+}
+
+// Decode an argument of type `ty`, as named in the metadata, `None` if the type is unknown, the
+// input being left as is.
+fn decode_argument(ty: &str, input: &mut &[u8]) -> Option<Value> {
+	let mut attempt = *input;
+	let value = decode_known_type(&normalize(ty), &mut attempt)?;
+
+	*input = attempt;
+
+	Some(value)
+}
+
+fn decode_known_type(ty: &str, input: &mut &[u8]) -> Option<Value> {
+	if let Some(ty) = ty
+		.strip_prefix("Compact<")
+		.and_then(|ty| ty.strip_suffix('>'))
+	{
+		let value = <Compact<u128>>::decode(input).ok()?.0;
+
+		return if BALANCE_TYPES.contains(&ty) {
+			Some(json!(value.to_string()))
+		} else if U32_TYPES.contains(&ty) || U64_TYPES.contains(&ty) {
+			Some(json!(value as u64))
+		} else {
+			None
+		};
+	}
+
+	let value = match ty {
+		"bool" => json!(bool::decode(input).ok()?),
+		"u8" => json!(u8::decode(input).ok()?),
+		"u16" => json!(u16::decode(input).ok()?),
+		"EthereumTransactionIndex" => {
+			let (hash, index) = <(Hash, u64)>::decode(input).ok()?;
+
+			json!([hash, index])
+		}
+		"U256" | "DepositId" => json!(U256::decode(input).ok()?.to_string()),
+		"DispatchInfo" => {
+			let (weight, class, pays_fee) = <(u64, u8, u8)>::decode(input).ok()?;
+
+			json!({
+				"weight": weight,
+				"class": ["Normal", "Operational", "Mandatory"].get(class as usize)?,
+				"paysFee": ["Yes", "No"].get(pays_fee as usize)?,
+			})
+		}
+		"DispatchError" => json!(format!("{:?}", DispatchError::decode(input).ok()?)),
+		_ if RELAY_AFFIRMATION_ID_TYPES.contains(&ty) => {
+			let (game_id, round, index) = <(u64, u32, u32)>::decode(input).ok()?;
+
+			json!({
+				"gameId": game_id,
+				"round": round,
+				"index": index,
+			})
+		}
+		_ if U32_TYPES.contains(&ty) => json!(u32::decode(input).ok()?),
+		_ if U64_TYPES.contains(&ty) => json!(u64::decode(input).ok()?),
+		// Kept as a string, beyond the precision of a JSON number.
+		_ if BALANCE_TYPES.contains(&ty) => json!(u128::decode(input).ok()?.to_string()),
+		_ if ACCOUNT_ID_TYPES.contains(&ty) => json!(AccountId::decode(input).ok()?.to_ss58check()),
+		_ if HASH_TYPES.contains(&ty) => json!(Hash::decode(input).ok()?),
+		_ if BYTES_TYPES.contains(&ty) => json!(to_hex(&<Vec<u8>>::decode(input).ok()?)),
+		_ if ETHEREUM_ADDRESS_TYPES.contains(&ty) => {
+			json!(to_hex(H160::decode(input).ok()?.as_bytes()))
+		}
+		_ => match ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+			Some(item_ty) => {
+				let len = <Compact<u32>>::decode(input).ok()?.0;
+
+				Value::Array(
+					(0..len)
+						.map(|_| decode_known_type(item_ty, input))
+						.collect::<Option<_>>()?,
+				)
+			}
+			None => return None,
+		},
+	};
+
+	Some(value)
+}
+
+// Skip the extra data of `signed_extension` in `input`, `None` if the signed extension is unknown.
+fn skip_signed_extension(signed_extension: &str, input: &mut &[u8]) -> Option<()> {
+	match signed_extension {
+		"CheckSpecVersion"
+		| "CheckTxVersion"
+		| "CheckGenesis"
+		| "CheckWeight"
+		| "CheckEthereumRelayHeaderHash" => (),
+		"CheckEra" | "CheckMortality" => {
+			Era::decode(input).ok()?;
+		}
+		"CheckNonce" | "ChargeTransactionPayment" => {
+			<Compact<u128>>::decode(input).ok()?;
+		}
+		_ => return None,
+	}
+
+	Some(())
+}
+
+// `("Unknown", "Unknown", {"scale": ..})` of an item missing in the metadata.
+fn unknown(encoded: &[u8]) -> (String, String, Value) {
+	(
+		"Unknown".into(),
+		"Unknown".into(),
+		json!({ "scale": to_hex(encoded) }),
+	)
+}
+
+fn decoded<B, O>(value: DecodeDifferent<B, O>) -> Result<O, String>
+where
+	B: 'static,
+	O: 'static,
+{
+	match value {
+		DecodeDifferent::Decoded(value) => Ok(value),
+		DecodeDifferent::Encode(_) => Err("Undecoded metadata".into()),
+	}
+}
+
+fn system_storage_key(item: &[u8]) -> StorageKey {
+	StorageKey(
+		twox_128(b"System")
+			.iter()
+			.chain(twox_128(item).iter())
+			.cloned()
+			.collect(),
+	)
+}
+
+fn open_jsonl(directory: &Path, first_block: BlockNumber) -> Result<File, String> {
+	let path = directory.join(format!("blocks-{:010}.jsonl", first_block));
+
+	OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(&path)
+		.map_err(|e| format!("Failed to open `{}`: {}", path.display(), e))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	format!("0x{}", HexDisplay::from(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+	// --- darwinia ---
+	use super::*;
+	use crab_runtime::Event;
+	use darwinia_runtime_common::{nft_bridge, sudo_retirement};
+
+	fn alice() -> AccountId {
+		AccountId::from([1; 32])
+	}
+
+	fn root_call_approval() -> sudo_retirement::RootCallApproval<BlockNumber> {
+		sudo_retirement::RootCallApproval {
+			council: true,
+			technical_committee: false,
+			expires_at: 100,
+		}
+	}
+
+	// An event with an argument of unknown type, `RootCallApproval`, between two known ones.
+	fn records() -> Vec<EventRecord<Event, Hash>> {
+		vec![
+			EventRecord {
+				phase: Phase::ApplyExtrinsic(1),
+				event: Event::sudo_retirement(sudo_retirement::RawEvent::RootCallApproved(
+					Hash::repeat_byte(1),
+					root_call_approval(),
+				)),
+				topics: vec![],
+			},
+			EventRecord {
+				phase: Phase::ApplyExtrinsic(1),
+				event: Event::nft_bridge(nft_bridge::RawEvent::Minted(
+					alice(),
+					H160::repeat_byte(0x22),
+					U256::from(7),
+				)),
+				topics: vec![Hash::repeat_byte(9)],
+			},
+			EventRecord {
+				phase: Phase::Finalization,
+				event: Event::frame_system(frame_system::RawEvent::NewAccount(alice())),
+				topics: vec![],
+			},
+		]
+	}
+
+	fn native_runtime_names() -> RuntimeNames {
+		RuntimeNames::from_metadata(&crab_runtime::Runtime::metadata().encode()).unwrap()
+	}
+
+	fn names_of(events: &[ExportedEvent]) -> Vec<(&str, &str)> {
+		events
+			.iter()
+			.map(|event| (event.pallet.as_str(), event.event.as_str()))
+			.collect()
+	}
+
+	#[test]
+	fn events_should_decode_the_native_event_records() {
+		let runtime_names = native_runtime_names();
+
+		assert!(runtime_names.native);
+
+		let events = runtime_names.events(&records().encode());
+
+		assert_eq!(
+			names_of(&events),
+			vec![
+				("SudoRetirement", "RootCallApproved"),
+				("NftBridge", "Minted"),
+				("System", "NewAccount"),
+			]
+		);
+		assert_eq!(
+			events[0].args,
+			vec![
+				json!(Hash::repeat_byte(1)),
+				json!({ "scale": to_hex(&root_call_approval().encode()) }),
+			]
+		);
+		assert_eq!(
+			events[1].args,
+			vec![
+				json!(alice().to_ss58check()),
+				json!(to_hex(&[0x22; 20])),
+				json!("7"),
+			]
+		);
+		assert_eq!(events[1].extrinsic_index, Some(1));
+		assert_eq!(events[2].args, vec![json!(alice().to_ss58check())]);
+		assert_eq!(events[2].extrinsic_index, None);
+		assert_eq!(events[2].index, 2);
+	}
+
+	#[test]
+	fn events_of_other_runtimes_should_end_at_an_unknown_argument() {
+		let mut runtime_names = native_runtime_names();
+
+		runtime_names.native = false;
+
+		let events = runtime_names.events(&records().encode());
+
+		assert_eq!(
+			names_of(&events),
+			vec![("SudoRetirement", "RootCallApproved")]
+		);
+
+		let mut known_records = records();
+
+		known_records.remove(0);
+
+		assert_eq!(
+			names_of(&runtime_names.events(&known_records.encode())),
+			vec![("NftBridge", "Minted"), ("System", "NewAccount")]
+		);
+	}
+
+	#[test]
+	fn decode_argument_should_decode_the_relay_types() {
+		let mut input = &(H160::repeat_byte(0x11), 5u64, (5u64, 2u32, 1u32)).encode()[..];
+
+		assert_eq!(
+			decode_argument("EthereumAddress", &mut input),
+			Some(json!(to_hex(&[0x11; 20])))
+		);
+		assert_eq!(
+			decode_argument("GameId<TcBlockNumber<T, I>>", &mut input),
+			Some(json!(5))
+		);
+		assert_eq!(
+			decode_argument("RelayAffirmationId<RelayHeaderId<T, I>>", &mut input),
+			Some(json!({ "gameId": 5, "round": 2, "index": 1 }))
+		);
+		assert!(input.is_empty());
+
+		let mut input = &vec![5u64, 6].encode()[..];
+
+		assert_eq!(
+			decode_argument("Vec<GameId>", &mut input),
+			Some(json!([5, 6]))
+		);
+
+		let encoded = vec![1u8].encode();
+		let mut input = &encoded[..];
+
+		assert_eq!(decode_argument("Vec<ProxyType>", &mut input), None);
+		assert_eq!(input, &encoded[..]);
+	}
+}
@@ -0,0 +1,270 @@
+//! `/health` and `/ready` HTTP endpoints for the node orchestration.
+//!
+//! `/health` answers as long as the node runs. `/ready` checks the node is in sync, connected
+//! and finalizing, and for an authority running a configured validator, that its keystore holds
+//! every session key the validator set on chain in `Session::NextKeys`. A failed check is
+//! answered with `503 Service Unavailable`, the body listing the failures.
+
+// --- std ---
+use std::{
+	future::Future,
+	marker::PhantomData,
+	net::{SocketAddr, TcpListener},
+	sync::Arc,
+};
+// --- crates ---
+use codec::Encode;
+use hyper::{
+	header::{HeaderValue, CONTENT_TYPE},
+	service::{make_service_fn, service_fn},
+	Body, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+// --- substrate ---
+use sc_client_api::{Backend, StorageProvider};
+use sc_keystore::KeyStorePtr;
+use sc_network::NetworkService;
+use sc_service::Error as ServiceError;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	crypto::{key_types, KeyTypeId},
+	storage::StorageKey,
+	traits::BareCryptoStore,
+	twox_128, twox_64,
+};
+use sp_runtime::generic::BlockId;
+// --- darwinia ---
+use darwinia_primitives::{AccountId, Block, BlockNumber, Hash};
+
+/// The session keys an authority must hold, in their order in the runtime's `SessionKeys`.
+const SESSION_KEY_TYPES: [KeyTypeId; 4] = [
+	key_types::BABE,
+	key_types::GRANDPA,
+	key_types::IM_ONLINE,
+	key_types::AUTHORITY_DISCOVERY,
+];
+
+/// The settings of the health endpoints.
+#[derive(Clone, Debug)]
+pub struct HealthConfig {
+	/// The address to listen on.
+	pub listen_addr: SocketAddr,
+	/// The peers below which the node is not ready.
+	pub min_peers: usize,
+	/// The blocks between the best and the finalized block above which the node is not ready.
+	pub max_finality_lag: BlockNumber,
+	/// The stash of the validator whose session keys an authority must hold, if any.
+	pub validator: Option<AccountId>,
+}
+
+/// The answer of `/ready`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Readiness {
+	is_syncing: bool,
+	peers: usize,
+	best_number: BlockNumber,
+	finalized_number: BlockNumber,
+	finality_lag: BlockNumber,
+	/// `None` if the node is not an authority, or runs no configured validator.
+	session_keys_registered: Option<bool>,
+	failures: Vec<String>,
+}
+
+struct HealthChecker<B, C> {
+	client: Arc<C>,
+	network: Arc<NetworkService<Block, Hash>>,
+	keystore: Option<KeyStorePtr>,
+	config: HealthConfig,
+	_marker: PhantomData<B>,
+}
+impl<B, C> HealthChecker<B, C>
+where
+	B: Backend<Block>,
+	C: HeaderBackend<Block> + StorageProvider<Block, B>,
+{
+	fn respond(&self, request: Request<Body>) -> Response<Body> {
+		match request.uri().path() {
+			"/health" => json_response(StatusCode::OK, &"ok"),
+			"/ready" => {
+				let readiness = self.readiness();
+				let status = if readiness.failures.is_empty() {
+					StatusCode::OK
+				} else {
+					StatusCode::SERVICE_UNAVAILABLE
+				};
+
+				json_response(status, &readiness)
+			}
+			_ => json_response(StatusCode::NOT_FOUND, &"not found"),
+		}
+	}
+
+	fn readiness(&self) -> Readiness {
+		let info = self.client.info();
+		let is_syncing = self.network.is_major_syncing();
+		let peers = self.network.num_connected();
+		let finality_lag = info.best_number.saturating_sub(info.finalized_number);
+		let session_keys_registered = match (&self.keystore, &self.config.validator) {
+			(Some(keystore), Some(validator)) => {
+				Some(self.session_keys_registered(keystore, validator, info.best_hash))
+			}
+			_ => None,
+		};
+		let mut failures = vec![];
+
+		if is_syncing {
+			failures.push("major syncing".into());
+		}
+		if peers < self.config.min_peers {
+			failures.push(format!(
+				"{} peers, below the minimum of {}",
+				peers, self.config.min_peers
+			));
+		}
+		if finality_lag > self.config.max_finality_lag {
+			failures.push(format!(
+				"finality lags {} blocks behind, above the maximum of {}",
+				finality_lag, self.config.max_finality_lag
+			));
+		}
+		if session_keys_registered == Some(false) {
+			failures.push("session keys of the validator are not all in the keystore".into());
+		}
+
+		Readiness {
+			is_syncing,
+			peers,
+			best_number: info.best_number,
+			finalized_number: info.finalized_number,
+			finality_lag,
+			session_keys_registered,
+			failures,
+		}
+	}
+
+	// Whether the keystore holds every key in `Session::NextKeys` of `validator` at block `at`.
+	fn session_keys_registered(
+		&self,
+		keystore: &KeyStorePtr,
+		validator: &AccountId,
+		at: Hash,
+	) -> bool {
+		let next_keys = match self
+			.client
+			.storage(&BlockId::Hash(at), &next_keys_storage_key(validator))
+			.ok()
+			.flatten()
+		{
+			Some(next_keys) => next_keys.0,
+			None => return false,
+		};
+
+		match session_keys(&next_keys) {
+			Some(session_keys) => keystore.read().has_keys(&session_keys),
+			None => false,
+		}
+	}
+}
+
+// `Session::NextKeys`, hashed with `twox_64_concat`.
+fn next_keys_storage_key(validator: &AccountId) -> StorageKey {
+	let key = validator.encode();
+
+	StorageKey(
+		twox_128(b"Session")
+			.iter()
+			.chain(twox_128(b"NextKeys").iter())
+			.chain(twox_64(&key).iter())
+			.chain(key.iter())
+			.cloned()
+			.collect(),
+	)
+}
+
+// The public keys of an encoded `SessionKeys`, with their types. All of them are 32 bytes.
+fn session_keys(encoded: &[u8]) -> Option<Vec<(Vec<u8>, KeyTypeId)>> {
+	if encoded.len() != 32 * SESSION_KEY_TYPES.len() {
+		return None;
+	}
+
+	Some(
+		encoded
+			.chunks(32)
+			.zip(SESSION_KEY_TYPES.iter())
+			.map(|(public, key_type)| (public.to_vec(), *key_type))
+			.collect(),
+	)
+}
+
+fn json_response(status: StatusCode, body: &impl Serialize) -> Response<Body> {
+	let mut response = Response::new(Body::from(serde_json::to_vec(body).unwrap_or_default()));
+
+	*response.status_mut() = status;
+	response
+		.headers_mut()
+		.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+	response
+}
+
+/// Serve the health endpoints, checking the session keys of `keystore` if it is `Some`.
+///
+/// The address is bound right away, so the node fails to start if it is taken.
+pub fn health_task<B, C>(
+	client: Arc<C>,
+	network: Arc<NetworkService<Block, Hash>>,
+	keystore: Option<KeyStorePtr>,
+	config: HealthConfig,
+) -> Result<impl Future<Output = ()>, ServiceError>
+where
+	B: 'static + Backend<Block>,
+	C: 'static + Send + Sync + HeaderBackend<Block> + StorageProvider<Block, B>,
+{
+	let listen_addr = config.listen_addr;
+	let listener = TcpListener::bind(&listen_addr).map_err(|e| {
+		ServiceError::Other(format!(
+			"Failed to serve the health endpoints on {}: {}",
+			listen_addr, e
+		))
+	})?;
+	let health_checker = Arc::new(HealthChecker {
+		client,
+		network,
+		keystore,
+		config,
+		_marker: PhantomData,
+	});
+
+	Ok(async move {
+		let make_service = make_service_fn(move |_| {
+			let health_checker = health_checker.clone();
+
+			async move {
+				Ok::<_, hyper::Error>(service_fn(move |request| {
+					let response = health_checker.respond(request);
+
+					async move { Ok::<_, hyper::Error>(response) }
+				}))
+			}
+		});
+		let server = match Server::from_tcp(listener) {
+			Ok(server) => server,
+			Err(e) => {
+				log::error!(
+					"Failed to serve the health endpoints on {}: {}",
+					listen_addr,
+					e
+				);
+
+				return;
+			}
+		};
+
+		log::info!("〽️ Health endpoints serving on http://{}", listen_addr);
+
+		if let Err(e) = server.serve(make_service).await {
+			log::error!("Health endpoints failed: {}", e);
+		}
+	})
+}
@@ -3,6 +3,11 @@
 // --- darwinia ---
 pub mod chain_spec;
 pub mod client;
+pub mod types;
+
+mod exporter;
+mod health;
+mod metrics;
 
 // --- crates ---
 pub use codec::Codec;
@@ -12,19 +17,22 @@ pub use sc_service::{
 	ChainSpec, Configuration, TFullBackend, TFullClient, TLightBackend, TLightClient,
 };
 // --- darwinia ---
-pub use chain_spec::CrabChainSpec;
+pub use chain_spec::{CrabChainSpec, GrandpaHardFork, GrandpaSettings};
 pub use client::DarwiniaClient;
 pub use crab_runtime;
 pub use darwinia_primitives::Block;
+pub use exporter::{ExportConfig, ExportFormat};
+pub use health::HealthConfig;
 
 // --- std ---
 use std::{sync::Arc, time::Duration};
 // --- crates ---
+use codec::Encode;
 use futures::stream::StreamExt;
 // --- substrate ---
 use sc_authority_discovery::Role as AuthorityDiscoveryRole;
 use sc_basic_authorship::ProposerFactory;
-use sc_client_api::{ExecutorProvider, RemoteBackend, StateBackendFor};
+use sc_client_api::{Backend, ExecutorProvider, RemoteBackend, StateBackendFor};
 use sc_consensus::LongestChain;
 use sc_consensus_babe::{BabeBlockImport, BabeLink, BabeParams, Config as BabeConfig};
 use sc_executor::native_executor_instance;
@@ -33,6 +41,7 @@ use sc_finality_grandpa::{
 	LinkHalf, SharedVoterState as GrandpaSharedVoterState,
 	VotingRulesBuilder as GrandpaVotingRulesBuilder,
 };
+use sc_keystore::KeyStorePtr;
 use sc_network::Event as NetworkEvent;
 use sc_service::{
 	config::{KeystoreConfig, PrometheusConfig},
@@ -41,19 +50,26 @@ use sc_service::{
 };
 use sc_transaction_pool::{BasicPool, FullPool};
 use sp_api::ConstructRuntimeApi;
+use sp_blockchain::HeaderBackend;
 use sp_consensus::{
 	import_queue::BasicQueue, CanAuthorWithNativeVersion, DefaultImportQueue, NeverCanAuthor,
 };
-use sp_core::traits::BareCryptoStorePtr;
+use sp_core::{
+	crypto::Ss58Codec,
+	offchain::OffchainStorage,
+	traits::{BareCryptoStore, BareCryptoStorePtr},
+};
 use sp_inherents::InherentDataProviders;
-use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::{generic::BlockId, traits::BlakeTwo256};
 use sp_trie::PrefixedMemoryDB;
 use substrate_prometheus_endpoint::Registry;
 // --- darwinia ---
-use darwinia_primitives::{AccountId, Balance, Hash, Nonce, Power};
+use darwinia_primitives::{fisherman, AccountId, Balance, BlockNumber, Hash, Nonce, Power};
 use darwinia_rpc::{
 	BabeDeps, DenyUnsafe, FullDeps, GrandpaDeps, LightDeps, RpcExtension, SubscriptionManager,
 };
+use darwinia_runtime_common::offchain_election;
+use metrics::{ChainMetrics, ElectionMetrics};
 
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
@@ -97,6 +113,11 @@ pub trait RuntimeApiCollection:
 	+ darwinia_balances_rpc_runtime_api::BalancesApi<Block, AccountId, Balance>
 	+ darwinia_header_mmr_rpc_runtime_api::HeaderMMRApi<Block, Hash>
 	+ darwinia_staking_rpc_runtime_api::StakingApi<Block, AccountId, Power>
+	+ darwinia_rpc_runtime_api::fee_payment::FeePaymentApi<Block, Balance>
+	+ darwinia_rpc_runtime_api::dex::DexApi<Block, Balance>
+	+ darwinia_rpc_runtime_api::crab_mapping::CrabMappingApi<Block, Balance>
+	+ darwinia_rpc_runtime_api::scheduler::SchedulerApi<Block, BlockNumber>
+	+ darwinia_rpc_runtime_api::metrics::MetricsApi<Block, Balance>
 where
 	<Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
@@ -116,7 +137,12 @@ where
 		+ pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance>
 		+ darwinia_balances_rpc_runtime_api::BalancesApi<Block, AccountId, Balance>
 		+ darwinia_header_mmr_rpc_runtime_api::HeaderMMRApi<Block, Hash>
-		+ darwinia_staking_rpc_runtime_api::StakingApi<Block, AccountId, Power>,
+		+ darwinia_staking_rpc_runtime_api::StakingApi<Block, AccountId, Power>
+		+ darwinia_rpc_runtime_api::fee_payment::FeePaymentApi<Block, Balance>
+		+ darwinia_rpc_runtime_api::dex::DexApi<Block, Balance>
+		+ darwinia_rpc_runtime_api::crab_mapping::CrabMappingApi<Block, Balance>
+		+ darwinia_rpc_runtime_api::scheduler::SchedulerApi<Block, BlockNumber>
+		+ darwinia_rpc_runtime_api::metrics::MetricsApi<Block, Balance>,
 	<Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
 }
@@ -124,6 +150,29 @@ where
 pub trait RuntimeExtrinsic: codec::Codec + Send + Sync + 'static {}
 impl<E> RuntimeExtrinsic for E where E: codec::Codec + Send + Sync + 'static {}
 
+/// Darwinia specific options of a full node.
+#[derive(Clone, Debug, Default)]
+pub struct FullNodeOptions {
+	/// Sign the equivocation reports with the fisherman key, generating one if there is none.
+	pub fisherman: bool,
+	/// Check the relayed Ethereum headers against this Ethereum JSON-RPC endpoint, if `Some`.
+	pub fisherman_ethereum_rpc: Option<String>,
+	/// The balancing iterations of the offchain election miner, the runtime's default if `None`.
+	pub election_max_iterations: Option<u32>,
+	/// Override the GRANDPA gossip duration of the chain spec, in milliseconds.
+	pub grandpa_gossip_duration_millis: Option<u64>,
+	/// Override the GRANDPA justification period of the chain spec.
+	pub grandpa_justification_period: Option<u32>,
+	/// Enable or disable the GRANDPA observer protocol, instead of the chain spec.
+	pub grandpa_observer_enabled: Option<bool>,
+	/// GRANDPA authority set hard forks, in addition to the ones of the chain spec.
+	pub grandpa_hard_forks: Vec<GrandpaHardFork>,
+	/// Serve the `/health` and `/ready` HTTP endpoints, if `Some`.
+	pub health: Option<HealthConfig>,
+	/// Export the blocks with their extrinsics and events, if `Some`.
+	pub exporter: Option<ExportConfig>,
+}
+
 /// Can be called for a `Configuration` to check if it is a configuration for the `Crab` network.
 pub trait IdentifyVariant {
 	/// Returns if this is a configuration for the `Crab` network.
@@ -151,9 +200,126 @@ fn set_prometheus_registry(config: &mut Configuration) -> Result<(), ServiceErro
 	Ok(())
 }
 
+// The GRANDPA settings of the chain spec, overridden by the options.
+#[cfg(feature = "full-node")]
+fn grandpa_settings(config: &Configuration, options: &FullNodeOptions) -> GrandpaSettings {
+	let mut grandpa_settings =
+		sc_chain_spec::get_extension::<GrandpaSettings>(config.chain_spec.extensions())
+			.cloned()
+			.unwrap_or_default();
+
+	if let Some(gossip_duration_millis) = options.grandpa_gossip_duration_millis {
+		grandpa_settings.gossip_duration_millis = gossip_duration_millis;
+	}
+	if let Some(justification_period) = options.grandpa_justification_period {
+		grandpa_settings.justification_period = justification_period;
+	}
+	if let Some(observer_enabled) = options.grandpa_observer_enabled {
+		grandpa_settings.observer_enabled = observer_enabled;
+	}
+
+	grandpa_settings
+}
+
+// The GRANDPA authority set hard forks of the chain spec, along with the ones of the options, which
+// take the place of the chain spec's for the same set.
+#[cfg(feature = "full-node")]
+fn grandpa_hard_forks(
+	config: &Configuration,
+	options: &FullNodeOptions,
+) -> Result<Vec<GrandpaHardFork>, ServiceError> {
+	let mut grandpa_hard_forks =
+		sc_chain_spec::get_extension::<Vec<GrandpaHardFork>>(config.chain_spec.extensions())
+			.cloned()
+			.unwrap_or_default();
+
+	for hard_fork in &options.grandpa_hard_forks {
+		grandpa_hard_forks
+			.retain(|chain_spec_hard_fork| chain_spec_hard_fork.set_id != hard_fork.set_id);
+	}
+
+	grandpa_hard_forks.extend(options.grandpa_hard_forks.iter().cloned());
+	grandpa_hard_forks.sort_by_key(|hard_fork| hard_fork.set_id);
+
+	for (i, hard_fork) in grandpa_hard_forks.iter().enumerate() {
+		hard_fork.validate().map_err(ServiceError::Other)?;
+
+		if i > 0 && grandpa_hard_forks[i - 1].set_id == hard_fork.set_id {
+			return Err(ServiceError::Other(format!(
+				"GRANDPA hard fork of set {} is duplicated",
+				hard_fork.set_id
+			)));
+		}
+
+		log::warn!(
+			"🔀 Forcing GRANDPA authority set {} at block #{} ({})",
+			hard_fork.set_id,
+			hard_fork.block_number,
+			hard_fork.block_hash
+		);
+	}
+
+	Ok(grandpa_hard_forks)
+}
+
+// Make sure the keystore holds a fisherman key, so that the equivocation reports are signed with it.
+#[cfg(feature = "full-node")]
+fn ensure_fisherman_key(keystore: &KeyStorePtr) -> Result<(), ServiceError> {
+	let fisherman = match keystore
+		.read()
+		.sr25519_public_keys(fisherman::KEY_TYPE)
+		.into_iter()
+		.next()
+	{
+		Some(fisherman) => fisherman,
+		None => keystore
+			.write()
+			.sr25519_generate_new(fisherman::KEY_TYPE, None)
+			.map_err(|e| {
+				ServiceError::Other(format!("Failed to generate fisherman key: {:?}", e))
+			})?,
+	};
+
+	log::info!(
+		"🎣 Reporting equivocations as fisherman {}",
+		fisherman.to_ss58check()
+	);
+
+	Ok(())
+}
+
+// Hand the balancing iterations over to the staking offchain worker, an empty value stands for the
+// runtime's default.
+#[cfg(feature = "full-node")]
+fn set_election_max_iterations(backend: &FullBackend, election_max_iterations: Option<u32>) {
+	if let Some(mut offchain_storage) = backend.offchain_storage() {
+		offchain_storage.set(
+			sp_offchain::STORAGE_PREFIX,
+			offchain_election::MAX_ITERATIONS_KEY,
+			&election_max_iterations
+				.map(|max_iterations| max_iterations.encode())
+				.unwrap_or_default(),
+		);
+	}
+}
+
+// Hand the Ethereum RPC endpoint over to the fisherman offchain worker, an empty value disables the
+// relay checks.
+#[cfg(feature = "full-node")]
+fn set_fisherman_ethereum_rpc(backend: &FullBackend, fisherman_ethereum_rpc: Option<&str>) {
+	if let Some(mut offchain_storage) = backend.offchain_storage() {
+		offchain_storage.set(
+			sp_offchain::STORAGE_PREFIX,
+			darwinia_runtime_common::fisherman::ETHEREUM_RPC_KEY,
+			fisherman_ethereum_rpc.unwrap_or_default().as_bytes(),
+		);
+	}
+}
+
 #[cfg(feature = "full-node")]
 fn new_partial<RuntimeApi, Executor>(
 	config: &mut Configuration,
+	grandpa_hard_forks: Vec<GrandpaHardFork>,
 ) -> Result<
 	PartialComponents<
 		FullClient<RuntimeApi, Executor>,
@@ -197,7 +363,47 @@ where
 		task_manager.spawn_handle(),
 		client.clone(),
 	);
-	let grandpa_hard_forks = vec![];
+	let grandpa_hard_forks = grandpa_hard_forks
+		.into_iter()
+		.map(|hard_fork| {
+			match client.header(BlockId::Hash(hard_fork.block_hash))? {
+				Some(header) if header.number != hard_fork.block_number => {
+					return Err(ServiceError::Other(format!(
+						"GRANDPA hard fork of set {} is at block #{}, but block {} is #{}",
+						hard_fork.set_id,
+						hard_fork.block_number,
+						hard_fork.block_hash,
+						header.number
+					)));
+				}
+				Some(_) => (),
+				// Not synced yet, on another fork, or a typo, which can't be told apart.
+				None => match client.hash(hard_fork.block_number)? {
+					Some(hash) => log::warn!(
+						"GRANDPA hard fork of set {} is at block {}, but block #{} is {}, it only \
+						applies if block {} gets imported",
+						hard_fork.set_id,
+						hard_fork.block_hash,
+						hard_fork.block_number,
+						hash,
+						hard_fork.block_hash
+					),
+					None => log::warn!(
+						"GRANDPA hard fork of set {} is at the unknown block {}, it only applies \
+						once this block gets imported",
+						hard_fork.set_id,
+						hard_fork.block_hash
+					),
+				},
+			}
+
+			Ok((
+				hard_fork.set_id,
+				(hard_fork.block_hash, hard_fork.block_number),
+				hard_fork.authorities,
+			))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
 	let (grandpa_block_import, grandpa_link) =
 		sc_finality_grandpa::block_import_with_authority_set_hard_forks(
 			client.clone(),
@@ -275,6 +481,7 @@ where
 #[cfg(feature = "full-node")]
 fn new_full<RuntimeApi, Executor>(
 	mut config: Configuration,
+	options: FullNodeOptions,
 ) -> Result<(TaskManager, Arc<FullClient<RuntimeApi, Executor>>), ServiceError>
 where
 	Executor: 'static + NativeExecutionDispatch,
@@ -288,6 +495,8 @@ where
 	let force_authoring = config.force_authoring;
 	let disable_grandpa = config.disable_grandpa;
 	let name = config.network.node_name.clone();
+	let grandpa_settings = grandpa_settings(&config, &options);
+	let grandpa_hard_forks = grandpa_hard_forks(&config, &options)?;
 	let PartialComponents {
 		client,
 		backend,
@@ -298,7 +507,12 @@ where
 		transaction_pool,
 		inherent_data_providers,
 		other: (rpc_extensions_builder, import_setup, rpc_setup),
-	} = new_partial::<RuntimeApi, Executor>(&mut config)?;
+	} = new_partial::<RuntimeApi, Executor>(&mut config, grandpa_hard_forks)?;
+
+	if options.fisherman {
+		ensure_fisherman_key(&keystore)?;
+	}
+
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let finality_proof_provider =
 		GrandpaFinalityProofProvider::new_for_service(backend.clone(), client.clone());
@@ -316,6 +530,9 @@ where
 		})?;
 
 	if config.offchain_worker.enabled {
+		set_election_max_iterations(&backend, options.election_max_iterations);
+		set_fisherman_ethereum_rpc(&backend, options.fisherman_ethereum_rpc.as_deref());
+
 		sc_service::build_offchain_workers(
 			&config,
 			backend.clone(),
@@ -325,6 +542,44 @@ where
 		);
 	}
 
+	if let Some(registry) = prometheus_registry.as_ref() {
+		let election_metrics = ElectionMetrics::register(registry)?;
+
+		task_manager.spawn_handle().spawn(
+			"election-metrics",
+			metrics::election_metrics_task(client.clone(), election_metrics),
+		);
+
+		let chain_metrics = ChainMetrics::register(registry)?;
+
+		task_manager.spawn_handle().spawn(
+			"chain-metrics",
+			metrics::chain_metrics_task(client.clone(), chain_metrics),
+		);
+	}
+
+	if let Some(health_config) = options.health.clone() {
+		task_manager.spawn_handle().spawn(
+			"health",
+			health::health_task(
+				client.clone(),
+				network.clone(),
+				if is_authority {
+					Some(keystore.clone())
+				} else {
+					None
+				},
+				health_config,
+			)?,
+		);
+	}
+	if let Some(export_config) = options.exporter.clone() {
+		task_manager.spawn_handle().spawn_blocking(
+			"block-exporter",
+			exporter::exporter_task(client.clone(), export_config),
+		);
+	}
+
 	let telemetry_connection_sinks = TelemetryConnectionSinks::default();
 
 	sc_service::spawn_tasks(SpawnTasksParams {
@@ -411,11 +666,10 @@ where
 		None
 	};
 	let grandpa_config = GrandpaConfig {
-		// FIXME substrate#1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(1000),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(grandpa_settings.gossip_duration_millis),
+		justification_period: grandpa_settings.justification_period,
 		name: Some(name),
-		observer_enabled: false,
+		observer_enabled: grandpa_settings.observer_enabled,
 		keystore,
 		is_authority: role.is_network_authority(),
 	};
@@ -572,13 +826,14 @@ where
 {
 	config.keystore = KeystoreConfig::InMemory;
 
+	let grandpa_hard_forks = grandpa_hard_forks(&config, &FullNodeOptions::default())?;
 	let PartialComponents {
 		client,
 		backend,
 		import_queue,
 		task_manager,
 		..
-	} = new_partial::<Runtime, Dispatch>(&mut config)?;
+	} = new_partial::<Runtime, Dispatch>(&mut config, grandpa_hard_forks)?;
 
 	Ok((client, backend, import_queue, task_manager))
 }
@@ -587,6 +842,7 @@ where
 #[cfg(feature = "full-node")]
 pub fn crab_new_full(
 	config: Configuration,
+	options: FullNodeOptions,
 ) -> Result<
 	(
 		TaskManager,
@@ -594,7 +850,7 @@ pub fn crab_new_full(
 	),
 	ServiceError,
 > {
-	let (components, client) = new_full::<crab_runtime::RuntimeApi, CrabExecutor>(config)?;
+	let (components, client) = new_full::<crab_runtime::RuntimeApi, CrabExecutor>(config, options)?;
 
 	Ok((components, client))
 }
@@ -610,6 +866,7 @@ pub fn crab_new_light(config: Configuration) -> Result<TaskManager, ServiceError
 #[cfg(feature = "full-node")]
 pub fn darwinia_new_full(
 	config: Configuration,
+	options: FullNodeOptions,
 ) -> Result<
 	(
 		TaskManager,
@@ -617,7 +874,7 @@ pub fn darwinia_new_full(
 	),
 	ServiceError,
 > {
-	let (components, client) = new_full::<crab_runtime::RuntimeApi, CrabExecutor>(config)?;
+	let (components, client) = new_full::<crab_runtime::RuntimeApi, CrabExecutor>(config, options)?;
 
 	Ok((components, client))
 }
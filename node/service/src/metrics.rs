@@ -0,0 +1,176 @@
+//! Darwinia specific Prometheus metrics.
+
+// --- std ---
+use std::sync::Arc;
+// --- crates ---
+use codec::Decode;
+use futures::{
+	future,
+	stream::{self, StreamExt},
+};
+// --- substrate ---
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::generic::BlockId;
+use substrate_prometheus_endpoint::{register, GaugeVec, Opts, PrometheusError, Registry, F64};
+// --- darwinia ---
+use darwinia_primitives::{Balance, Block};
+use darwinia_rpc_runtime_api::metrics::{ChainMetrics as RuntimeChainMetrics, MetricsApi};
+
+/// `[minimal stake, sum of stakes, sum of squared stakes]` of an election solution.
+type ElectionScore = [u128; 3];
+
+const ELECTION_SCORE_COMPONENTS: [&str; 3] = ["minimal_stake", "sum_stake", "sum_stake_squared"];
+
+/// The metrics of the offchain phragmen election.
+pub struct ElectionMetrics {
+	queued_score: GaugeVec<F64>,
+}
+impl ElectionMetrics {
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			queued_score: register(
+				GaugeVec::new(
+					Opts::new(
+						"staking_election_queued_score",
+						"Score of the election solution queued for the next era, zero if none",
+					),
+					&["component"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Publish the score of the election solution queued in `DarwiniaStaking` at each new best block.
+pub async fn election_metrics_task<B, C>(client: Arc<C>, metrics: ElectionMetrics)
+where
+	B: Backend<Block>,
+	C: BlockchainEvents<Block> + StorageProvider<Block, B>,
+{
+	let queued_score_key = StorageKey(
+		twox_128(b"DarwiniaStaking")
+			.iter()
+			.chain(twox_128(b"QueuedScore").iter())
+			.cloned()
+			.collect(),
+	);
+	let mut import_notifications = client.import_notification_stream();
+
+	while let Some(notification) = import_notifications.next().await {
+		if !notification.is_new_best {
+			continue;
+		}
+
+		let queued_score = client
+			.storage(&BlockId::Hash(notification.hash), &queued_score_key)
+			.ok()
+			.flatten()
+			.and_then(|queued_score| ElectionScore::decode(&mut &queued_score.0[..]).ok())
+			.unwrap_or_default();
+
+		for (component, value) in ELECTION_SCORE_COMPONENTS.iter().zip(queued_score.iter()) {
+			metrics
+				.queued_score
+				.with_label_values(&[component])
+				.set(*value as f64);
+		}
+	}
+}
+
+/// The chain level metrics, of the best and of the finalized block.
+pub struct ChainMetrics {
+	ethereum_confirmed_block: GaugeVec<F64>,
+	relayer_games: GaugeVec<F64>,
+	staking_era: GaugeVec<F64>,
+	validators: GaugeVec<F64>,
+	mmr_leaves: GaugeVec<F64>,
+	treasury_balance: GaugeVec<F64>,
+}
+impl ChainMetrics {
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		let gauge = |name: &str, help: &str, labels: &[&str]| {
+			register(GaugeVec::new(Opts::new(name, help), labels)?, registry)
+		};
+
+		Ok(Self {
+			ethereum_confirmed_block: gauge(
+				"ethereum_relay_confirmed_block",
+				"Last Ethereum block confirmed by the relay",
+				&["block"],
+			)?,
+			relayer_games: gauge(
+				"ethereum_relayer_games",
+				"Relayer games in progress",
+				&["block"],
+			)?,
+			staking_era: gauge("staking_era", "Current staking era", &["block"])?,
+			validators: gauge(
+				"session_validators",
+				"Validators of the current session",
+				&["block"],
+			)?,
+			mmr_leaves: gauge("header_mmr_leaves", "Leaves of the Header MMR", &["block"])?,
+			treasury_balance: gauge(
+				"treasury_balance",
+				"Balance of the treasury, in the smallest unit",
+				&["block", "currency"],
+			)?,
+		})
+	}
+
+	fn set(&self, block: &str, chain_metrics: RuntimeChainMetrics<Balance>) {
+		self.ethereum_confirmed_block
+			.with_label_values(&[block])
+			.set(chain_metrics.ethereum_confirmed_block as f64);
+		self.relayer_games
+			.with_label_values(&[block])
+			.set(chain_metrics.relayer_games as f64);
+		self.staking_era
+			.with_label_values(&[block])
+			.set(chain_metrics.staking_era as f64);
+		self.validators
+			.with_label_values(&[block])
+			.set(chain_metrics.validators as f64);
+		self.mmr_leaves
+			.with_label_values(&[block])
+			.set(chain_metrics.mmr_leaves as f64);
+		self.treasury_balance
+			.with_label_values(&[block, "ring"])
+			.set(chain_metrics.treasury_ring as f64);
+		self.treasury_balance
+			.with_label_values(&[block, "kton"])
+			.set(chain_metrics.treasury_kton as f64);
+	}
+}
+
+/// Publish the chain level metrics read through the runtime API at each new best and finalized
+/// block.
+pub async fn chain_metrics_task<C>(client: Arc<C>, metrics: ChainMetrics)
+where
+	C: BlockchainEvents<Block> + ProvideRuntimeApi<Block>,
+	C::Api: MetricsApi<Block, Balance>,
+{
+	let best_blocks = client
+		.import_notification_stream()
+		.filter_map(|notification| {
+			future::ready(if notification.is_new_best {
+				Some(("best", notification.hash))
+			} else {
+				None
+			})
+		});
+	let finalized_blocks = client
+		.finality_notification_stream()
+		.map(|notification| ("finalized", notification.hash));
+	let mut blocks = stream::select(best_blocks, finalized_blocks);
+
+	while let Some((block, hash)) = blocks.next().await {
+		match client.runtime_api().chain_metrics(&BlockId::Hash(hash)) {
+			Ok(chain_metrics) => metrics.set(block, chain_metrics),
+			Err(e) => log::debug!("Failed to read the chain metrics of {}: {:?}", hash, e),
+		}
+	}
+}
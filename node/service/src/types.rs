@@ -0,0 +1,80 @@
+//! The names of the argument types in the metadata, shared by the block exporter decoding the
+//! calls and events and by the `tx` command encoding the calls.
+//!
+//! A type is only known by its exact name, without spaces, so that a type sharing a suffix with a
+//! known one, e.g. `Vec<Balance>`, is never read as it.
+
+/// The names of the types encoded as a `u32`.
+pub const U32_TYPES: &[&str] = &[
+	"u32",
+	"BlockNumber",
+	"T::BlockNumber",
+	"T::Index",
+	"Nonce",
+	"SessionIndex",
+	"EraIndex",
+	"ReferendumIndex",
+	"PropIndex",
+	"ProposalIndex",
+	"BountyIndex",
+	"RegistrarIndex",
+	"MemberCount",
+	"AuthorityIndex",
+];
+/// The names of the types encoded as a `u64`.
+pub const U64_TYPES: &[&str] = &[
+	"u64",
+	"Moment",
+	"T::Moment",
+	"Weight",
+	"TsInMs",
+	"EthereumBlockNumber",
+	"TcBlockNumber<T,I>",
+	"GameId",
+	"GameId<TcBlockNumber<T,I>>",
+	"RelayHeaderId",
+	"RelayHeaderId<T,I>",
+];
+/// The names of the balance types, encoded as a `u128`.
+pub const BALANCE_TYPES: &[&str] = &[
+	"u128",
+	"Balance",
+	"T::Balance",
+	"BalanceOf<T>",
+	"BalanceOf<T,I>",
+	"RingBalance",
+	"RingBalance<T>",
+	"RingBalance<T,I>",
+	"KtonBalance",
+	"KtonBalance<T>",
+	"KtonBalance<T,I>",
+];
+/// The names of the account id types.
+pub const ACCOUNT_ID_TYPES: &[&str] = &[
+	"AccountId",
+	"T::AccountId",
+	"Address",
+	"<T::LookupasStaticLookup>::Source",
+];
+/// The names of the hash types.
+pub const HASH_TYPES: &[&str] = &["H256", "Hash", "T::Hash"];
+/// The names of the Ethereum address types, encoded as a `H160`.
+pub const ETHEREUM_ADDRESS_TYPES: &[&str] = &["H160", "EthereumAddress"];
+/// The names of the ids of the relayer game affirmations, encoded as the game id, a `u64`, the
+/// round and the index of the affirmation in the round, two `u32`.
+pub const RELAY_AFFIRMATION_ID_TYPES: &[&str] = &[
+	"RelayAffirmationId",
+	"RelayAffirmationId<RelayHeaderId<T,I>>",
+	"RelayAffirmationId<GameId<TcBlockNumber<T,I>>>",
+];
+/// The names of the byte string types.
+pub const BYTES_TYPES: &[&str] = &["Vec<u8>", "Bytes"];
+/// The names of the call types.
+pub const CALL_TYPES: &[&str] = &["<TasTrait>::Call", "Box<<TasTrait>::Call>"];
+/// The names of the call list types.
+pub const CALLS_TYPES: &[&str] = &["Vec<<TasTrait>::Call>"];
+
+/// The name of `ty` in the tables, without its spaces.
+pub fn normalize(ty: &str) -> String {
+	ty.replace(' ', "")
+}
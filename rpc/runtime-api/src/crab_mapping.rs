@@ -0,0 +1,63 @@
+// --- crates ---
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+// --- substrate ---
+use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
+
+/// The CRING which may still be mapped to the mainnet RING.
+#[derive(Debug, Default, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MappingAllowance<Balance> {
+	/// The CRING the mainnet honours in total.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub total: Balance,
+	/// The CRING already mapped.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub mapped: Balance,
+	/// The CRING which may still be mapped.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub remaining: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to read the allowance of mapping CRING to the mainnet RING.
+	pub trait CrabMappingApi<Balance>
+	where
+		Balance: Codec + MaybeDisplay + MaybeFromStr,
+	{
+		/// The CRING which may still be mapped.
+		fn mapping_allowance() -> MappingAllowance<Balance>;
+	}
+}
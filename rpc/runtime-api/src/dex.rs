@@ -0,0 +1,110 @@
+// --- crates ---
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+// --- substrate ---
+use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
+
+/// An asset traded on the DEX.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum DexAsset {
+	/// RING, the `Balances` instance.
+	Ring,
+	/// KTON, the `Kton` instance.
+	Kton,
+}
+
+/// The result of swapping an amount of an asset for the other one.
+#[derive(Debug, Default, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SwapQuote<Balance> {
+	/// The amount received.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub amount_out: Balance,
+	/// The fee paid to the treasury, in the asset swapped in.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub fee: Balance,
+}
+
+/// The reserves of the RING/KTON pool.
+#[derive(Debug, Default, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PoolReserves<Balance> {
+	/// The RING in the pool.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub ring: Balance,
+	/// The KTON in the pool.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub kton: Balance,
+	/// The liquidity shares issued.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub total_shares: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to quote the swaps and read the pool of the DEX.
+	pub trait DexApi<Balance>
+	where
+		Balance: Codec + MaybeDisplay + MaybeFromStr,
+	{
+		/// Quote swapping `amount_in` of `asset_in`, `None` if the pool can't serve it.
+		fn quote_swap(asset_in: DexAsset, amount_in: Balance) -> Option<SwapQuote<Balance>>;
+
+		/// The reserves of the RING/KTON pool.
+		fn pool_reserves() -> PoolReserves<Balance>;
+	}
+}
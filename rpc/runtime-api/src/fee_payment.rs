@@ -0,0 +1,51 @@
+// --- crates ---
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+// --- substrate ---
+use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
+
+/// The currency a transaction fee is paid in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum FeeCurrency {
+	/// RING, the `Balances` instance.
+	Ring,
+	/// KTON, the `Kton` instance, when the RING balance is too low to cover the fee.
+	Kton,
+}
+
+/// The fee of an extrinsic, in the currency its sender pays it in.
+#[derive(Debug, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RuntimeDispatchInfo<Balance> {
+	/// The currency the fee is paid in.
+	pub currency: FeeCurrency,
+	/// The partial fee, see `pallet_transaction_payment::RuntimeDispatchInfo`, in `currency`.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			bound(
+				serialize = "Balance: std::fmt::Display",
+				deserialize = "Balance: std::str::FromStr"
+			),
+			serialize_with = "crate::serialize_as_string",
+			deserialize_with = "crate::deserialize_from_string"
+		)
+	)]
+	pub partial_fee: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the transaction fees, in RING or in KTON.
+	pub trait FeePaymentApi<Balance>
+	where
+		Balance: Codec + MaybeDisplay + MaybeFromStr,
+	{
+		/// The fee of `uxt`, in KTON if its sender can't pay it in RING and paying the fees in
+		/// KTON is enabled, in RING otherwise.
+		fn query_info(uxt: Block::Extrinsic, len: u32) -> RuntimeDispatchInfo<Balance>;
+	}
+}
@@ -0,0 +1,56 @@
+//! Runtime APIs of the Darwinia node specific modules.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+/// Runtime API of mapping CRING to the mainnet RING.
+pub mod crab_mapping;
+/// Runtime API of the RING/KTON DEX.
+pub mod dex;
+/// Runtime API of paying the transaction fees in KTON.
+pub mod fee_payment;
+/// Runtime API of the chain level metrics.
+pub mod metrics;
+/// Runtime API of the scheduler agenda.
+pub mod scheduler;
+
+#[cfg(feature = "std")]
+fn serialize_as_string<S, T>(t: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: serde::Serializer,
+	T: std::fmt::Display,
+{
+	serializer.serialize_str(&t.to_string())
+}
+
+#[cfg(feature = "std")]
+fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+	D: serde::Deserializer<'de>,
+	T: std::str::FromStr,
+{
+	let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+	s.parse::<T>()
+		.map_err(|_| serde::de::Error::custom("Parse from string failed"))
+}
+
+#[cfg(feature = "std")]
+fn serialize_option_bytes<S>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: serde::Serializer,
+{
+	match bytes {
+		Some(bytes) => sp_core::bytes::serialize(bytes, serializer),
+		None => serializer.serialize_none(),
+	}
+}
+
+#[cfg(feature = "std")]
+fn deserialize_option_bytes<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	<Option<sp_core::Bytes> as serde::Deserialize>::deserialize(deserializer)
+		.map(|bytes| bytes.map(|bytes| bytes.0))
+}
@@ -0,0 +1,32 @@
+// --- crates ---
+use codec::{Codec, Decode, Encode};
+
+/// The chain level metrics of a block, published by the node.
+#[derive(Debug, Default, Eq, PartialEq, Encode, Decode)]
+pub struct ChainMetrics<Balance> {
+	/// The last Ethereum block confirmed by `EthereumRelay`.
+	pub ethereum_confirmed_block: u64,
+	/// The relayer games in progress.
+	pub relayer_games: u32,
+	/// The current staking era.
+	pub staking_era: u32,
+	/// The validators of the current session.
+	pub validators: u32,
+	/// The leaves of the Header MMR.
+	pub mmr_leaves: u64,
+	/// The RING of the treasury.
+	pub treasury_ring: Balance,
+	/// The KTON of the treasury.
+	pub treasury_kton: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to read the chain level metrics.
+	pub trait MetricsApi<Balance>
+	where
+		Balance: Codec,
+	{
+		/// The chain level metrics of the block.
+		fn chain_metrics() -> ChainMetrics<Balance>;
+	}
+}
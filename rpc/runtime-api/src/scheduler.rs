@@ -0,0 +1,44 @@
+// --- crates ---
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+// --- substrate ---
+use sp_std::prelude::*;
+
+/// A task in the agenda of the scheduler.
+#[derive(Debug, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ScheduledTask<BlockNumber> {
+	/// The block the task is dispatched at.
+	pub when: BlockNumber,
+	/// The index of the task in the agenda of `when`.
+	pub index: u32,
+	/// The name of the task, if it was scheduled with one.
+	#[cfg_attr(
+		feature = "std",
+		serde(
+			serialize_with = "crate::serialize_option_bytes",
+			deserialize_with = "crate::deserialize_option_bytes"
+		)
+	)]
+	pub id: Option<Vec<u8>>,
+	/// The priority of the task, the lower the sooner in the block.
+	pub priority: u8,
+	/// The period and the remaining count of the dispatches, if the task is periodic.
+	pub maybe_periodic: Option<(BlockNumber, u32)>,
+	/// The SCALE encoded call.
+	#[cfg_attr(feature = "std", serde(with = "sp_core::bytes"))]
+	pub call: Vec<u8>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to read the agenda of the scheduler.
+	pub trait SchedulerApi<BlockNumber>
+	where
+		BlockNumber: Codec,
+	{
+		/// The tasks dispatched in the `blocks` blocks following the current one, the soonest first.
+		fn agenda(blocks: BlockNumber) -> Vec<ScheduledTask<BlockNumber>>;
+	}
+}
@@ -0,0 +1,66 @@
+//! RPC interface for mapping CRING to the mainnet RING.
+
+pub use darwinia_rpc_runtime_api::crab_mapping::CrabMappingApi as CrabMappingRuntimeApi;
+
+// --- std ---
+use std::{marker::PhantomData, sync::Arc};
+// --- crates ---
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+// --- substrate ---
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
+};
+// --- darwinia ---
+use darwinia_rpc_runtime_api::crab_mapping::MappingAllowance;
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// CRING mapping RPC methods.
+#[rpc]
+pub trait CrabMappingApi<BlockHash, AllowanceResponse> {
+	/// The CRING which may still be mapped to the mainnet RING.
+	#[rpc(name = "crabMapping_mappingAllowance")]
+	fn mapping_allowance(&self, at: Option<BlockHash>) -> Result<AllowanceResponse>;
+}
+
+/// A struct that implements the [`CrabMappingApi`].
+pub struct CrabMapping<Client, Block> {
+	client: Arc<Client>,
+	_marker: PhantomData<Block>,
+}
+impl<Client, Block> CrabMapping<Client, Block> {
+	/// Create new `CrabMapping` with the given reference to the client.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+impl<Client, Block, Balance> CrabMappingApi<<Block as BlockT>::Hash, MappingAllowance<Balance>>
+	for CrabMapping<Client, Block>
+where
+	Client: 'static + Send + Sync + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	Client::Api: CrabMappingRuntimeApi<Block, Balance>,
+	Block: BlockT,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	fn mapping_allowance(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<MappingAllowance<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.mapping_allowance(&at).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query the mapping allowance.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
@@ -0,0 +1,95 @@
+//! RPC interface for the RING/KTON DEX.
+
+pub use darwinia_rpc_runtime_api::dex::DexApi as DexRuntimeApi;
+
+// --- std ---
+use std::{marker::PhantomData, sync::Arc};
+// --- crates ---
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+// --- substrate ---
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
+};
+// --- darwinia ---
+use darwinia_rpc_runtime_api::dex::{DexAsset, PoolReserves, SwapQuote};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// DEX RPC methods.
+#[rpc]
+pub trait DexApi<BlockHash, QuoteResponse, ReservesResponse> {
+	/// Quote swapping `amount_in`, a decimal string, of `asset_in`, `null` if the pool can't
+	/// serve it.
+	#[rpc(name = "dex_quoteSwap")]
+	fn quote_swap(
+		&self,
+		asset_in: DexAsset,
+		amount_in: String,
+		at: Option<BlockHash>,
+	) -> Result<QuoteResponse>;
+
+	/// The reserves of the RING/KTON pool.
+	#[rpc(name = "dex_poolReserves")]
+	fn pool_reserves(&self, at: Option<BlockHash>) -> Result<ReservesResponse>;
+}
+
+/// A struct that implements the [`DexApi`].
+pub struct Dex<Client, Block> {
+	client: Arc<Client>,
+	_marker: PhantomData<Block>,
+}
+impl<Client, Block> Dex<Client, Block> {
+	/// Create new `Dex` with the given reference to the client.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+impl<Client, Block, Balance>
+	DexApi<<Block as BlockT>::Hash, Option<SwapQuote<Balance>>, PoolReserves<Balance>>
+	for Dex<Client, Block>
+where
+	Client: 'static + Send + Sync + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	Client::Api: DexRuntimeApi<Block, Balance>,
+	Block: BlockT,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	fn quote_swap(
+		&self,
+		asset_in: DexAsset,
+		amount_in: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<SwapQuote<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let amount_in = amount_in.parse::<Balance>().map_err(|_| Error {
+			code: ErrorCode::InvalidParams,
+			message: "Unable to parse the amount in.".into(),
+			data: Some(amount_in.clone().into()),
+		})?;
+
+		api.quote_swap(&at, asset_in, amount_in).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to quote the swap.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn pool_reserves(&self, at: Option<<Block as BlockT>::Hash>) -> Result<PoolReserves<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.pool_reserves(&at).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query the pool reserves.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
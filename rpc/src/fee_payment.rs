@@ -0,0 +1,75 @@
+//! RPC interface for paying the transaction fees in RING or in KTON.
+
+pub use darwinia_rpc_runtime_api::fee_payment::FeePaymentApi as FeePaymentRuntimeApi;
+
+// --- std ---
+use std::{marker::PhantomData, sync::Arc};
+// --- crates ---
+use codec::{Codec, Decode};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+// --- substrate ---
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
+};
+// --- darwinia ---
+use darwinia_rpc_runtime_api::fee_payment::RuntimeDispatchInfo;
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// Fee payment RPC methods.
+#[rpc]
+pub trait FeePaymentApi<BlockHash, Response> {
+	/// Query the fee of an encoded extrinsic, along with the currency its sender pays it in.
+	#[rpc(name = "feePayment_queryInfo")]
+	fn query_info(&self, encoded_xt: Bytes, at: Option<BlockHash>) -> Result<Response>;
+}
+
+/// A struct that implements the [`FeePaymentApi`].
+pub struct FeePayment<Client, Block> {
+	client: Arc<Client>,
+	_marker: PhantomData<Block>,
+}
+impl<Client, Block> FeePayment<Client, Block> {
+	/// Create new `FeePayment` with the given reference to the client.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+impl<Client, Block, Balance> FeePaymentApi<<Block as BlockT>::Hash, RuntimeDispatchInfo<Balance>>
+	for FeePayment<Client, Block>
+where
+	Client: 'static + Send + Sync + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	Client::Api: FeePaymentRuntimeApi<Block, Balance>,
+	Block: BlockT,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	fn query_info(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RuntimeDispatchInfo<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let encoded_len = encoded_xt.len() as u32;
+		let uxt: <Block as BlockT>::Extrinsic =
+			Decode::decode(&mut &*encoded_xt).map_err(|e| Error {
+				code: ErrorCode::ServerError(RUNTIME_ERROR),
+				message: "Unable to query fee info.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})?;
+
+		api.query_info(&at, uxt, encoded_len).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query fee info.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
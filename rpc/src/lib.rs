@@ -2,6 +2,11 @@
 
 #![warn(missing_docs)]
 
+pub mod crab_mapping;
+pub mod dex;
+pub mod fee_payment;
+pub mod scheduler;
+
 // --- crates ---
 pub use jsonrpc_pubsub::manager::SubscriptionManager;
 // --- substrate ---
@@ -82,6 +87,10 @@ where
 	C::Api: darwinia_balances_rpc::BalancesRuntimeApi<Block, AccountId, Balance>,
 	C::Api: darwinia_header_mmr_rpc::HeaderMMRRuntimeApi<Block, Hash>,
 	C::Api: darwinia_staking_rpc::StakingRuntimeApi<Block, AccountId, Power>,
+	C::Api: fee_payment::FeePaymentRuntimeApi<Block, Balance>,
+	C::Api: dex::DexRuntimeApi<Block, Balance>,
+	C::Api: crab_mapping::CrabMappingRuntimeApi<Block, Balance>,
+	C::Api: scheduler::SchedulerRuntimeApi<Block, BlockNumber>,
 	P: 'static + sp_transaction_pool::TransactionPool,
 	SC: 'static + sp_consensus::SelectChain<Block>,
 {
@@ -91,9 +100,13 @@ where
 	use sc_finality_grandpa_rpc::{GrandpaApi, GrandpaRpcHandler};
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	// --- darwinia ---
+	use crab_mapping::{CrabMapping, CrabMappingApi};
 	use darwinia_balances_rpc::{Balances, BalancesApi};
 	use darwinia_header_mmr_rpc::{HeaderMMR, HeaderMMRApi};
 	use darwinia_staking_rpc::{Staking, StakingApi};
+	use dex::{Dex, DexApi};
+	use fee_payment::{FeePayment, FeePaymentApi};
+	use scheduler::{Scheduler, SchedulerApi};
 
 	let FullDeps {
 		client,
@@ -144,7 +157,13 @@ where
 	}
 	io.extend_with(BalancesApi::to_delegate(Balances::new(client.clone())));
 	io.extend_with(HeaderMMRApi::to_delegate(HeaderMMR::new(client.clone())));
-	io.extend_with(StakingApi::to_delegate(Staking::new(client)));
+	io.extend_with(StakingApi::to_delegate(Staking::new(client.clone())));
+	io.extend_with(FeePaymentApi::to_delegate(FeePayment::new(client.clone())));
+	io.extend_with(DexApi::to_delegate(Dex::new(client.clone())));
+	io.extend_with(CrabMappingApi::to_delegate(CrabMapping::new(
+		client.clone(),
+	)));
+	io.extend_with(SchedulerApi::to_delegate(Scheduler::new(client)));
 
 	io
 }
@@ -0,0 +1,65 @@
+//! RPC interface for the agenda of the scheduler.
+
+pub use darwinia_rpc_runtime_api::scheduler::SchedulerApi as SchedulerRuntimeApi;
+
+// --- std ---
+use std::{marker::PhantomData, sync::Arc};
+// --- crates ---
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+// --- substrate ---
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+// --- darwinia ---
+use darwinia_rpc_runtime_api::scheduler::ScheduledTask;
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// Scheduler RPC methods.
+#[rpc]
+pub trait SchedulerApi<BlockHash, BlockNumber, AgendaResponse> {
+	/// The tasks dispatched in the `blocks` blocks following `at`, the soonest first.
+	#[rpc(name = "scheduler_agenda")]
+	fn agenda(&self, blocks: BlockNumber, at: Option<BlockHash>) -> Result<AgendaResponse>;
+}
+
+/// A struct that implements the [`SchedulerApi`].
+pub struct Scheduler<Client, Block> {
+	client: Arc<Client>,
+	_marker: PhantomData<Block>,
+}
+impl<Client, Block> Scheduler<Client, Block> {
+	/// Create new `Scheduler` with the given reference to the client.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+impl<Client, Block, BlockNumber>
+	SchedulerApi<<Block as BlockT>::Hash, BlockNumber, Vec<ScheduledTask<BlockNumber>>>
+	for Scheduler<Client, Block>
+where
+	Client: 'static + Send + Sync + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	Client::Api: SchedulerRuntimeApi<Block, BlockNumber>,
+	Block: BlockT,
+	BlockNumber: Codec,
+{
+	fn agenda(
+		&self,
+		blocks: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<ScheduledTask<BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.agenda(&at, blocks).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query the agenda.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
@@ -0,0 +1,1202 @@
+//! Treasury bounties, paid in RING or KTON from the treasury pot.
+//!
+//! A bounty is proposed with a RING bond and, once approved, funded from the treasury pot at the
+//! next `SpendPeriod`, after the treasury spent on its approved proposals and burned its share.
+//! Approved bounties the pot can't afford yet wait for the following spend periods. A curator is
+//! then proposed, who must accept the role with a deposit. The curator may pay out parts of the
+//! bounty as child payouts, and finally awards the rest to a beneficiary, who can claim it after
+//! `BountyDepositPayoutDelay`. A curator who does not extend the bounty within
+//! `BountyUpdatePeriod` can be unassigned by anyone, losing the deposit.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, EnsureOrigin, ExistenceRequirement, Get, OnUnbalanced, ReservableCurrency},
+	transactional,
+	weights::Weight,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, Zero},
+	DispatchError, ModuleId, Permill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub type RingBalance<T> =
+	<<T as Trait>::RingCurrency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type RingNegativeImbalance<T> = <<T as Trait>::RingCurrency as Currency<
+	<T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+pub type KtonNegativeImbalance<T> = <<T as Trait>::KtonCurrency as Currency<
+	<T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+
+/// An index of a bounty. Just a `u32`.
+pub type BountyIndex = u32;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The treasury's module id, the bounties are funded from its pot.
+	type TreasuryModuleId: Get<ModuleId>;
+
+	type RingCurrency: ReservableCurrency<Self::AccountId>;
+	type KtonCurrency: ReservableCurrency<Self::AccountId, Balance = RingBalance<Self>>;
+
+	/// Origin from which the bounties and their curators are approved.
+	type ApproveOrigin: EnsureOrigin<Self::Origin>;
+	/// Origin from which the bounties are closed and the curators are unassigned.
+	type RejectOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The amount held on deposit for placing a bounty proposal.
+	type BountyDepositBase: Get<RingBalance<Self>>;
+	/// The amount held on deposit per byte within the bounty description.
+	type DataDepositPerByte: Get<RingBalance<Self>>;
+	/// Maximum acceptable description length.
+	type MaximumReasonLength: Get<u32>;
+	/// Minimum value for a RING bounty.
+	type RingBountyValueMinimum: Get<RingBalance<Self>>;
+	/// Minimum value for a KTON bounty.
+	type KtonBountyValueMinimum: Get<RingBalance<Self>>;
+	/// Percentage of the curator fee that will be reserved upfront as deposit for the curator.
+	type BountyCuratorDeposit: Get<Permill>;
+	/// The delay period for which a bounty beneficiary need to wait before claim the payout.
+	type BountyDepositPayoutDelay: Get<Self::BlockNumber>;
+	/// Bounty duration in blocks, the curator must extend the bounty within this period.
+	type BountyUpdatePeriod: Get<Self::BlockNumber>;
+	/// The spend period of the treasury, the approved bounties are funded along with it.
+	type SpendPeriod: Get<Self::BlockNumber>;
+
+	/// Handler for the unbalanced reduction when slashing a bond or a RING curator deposit.
+	type OnSlash: OnUnbalanced<RingNegativeImbalance<Self>>;
+	/// Handler for the unbalanced reduction when slashing a KTON curator deposit.
+	type OnKtonSlash: OnUnbalanced<KtonNegativeImbalance<Self>>;
+}
+
+/// The currency a bounty is paid in.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum BountyAsset {
+	Ring,
+	Kton,
+}
+
+/// A bounty proposal.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct Bounty<AccountId, Balance, BlockNumber> {
+	/// The account proposing it.
+	pub proposer: AccountId,
+	/// The currency of the bounty.
+	pub asset: BountyAsset,
+	/// The (total) amount that should be paid if the bounty is rewarded, less the child payouts.
+	pub value: Balance,
+	/// The curator fee. Included in value.
+	pub fee: Balance,
+	/// The deposit of the curator, in the currency of the bounty.
+	pub curator_deposit: Balance,
+	/// The RING bond of the proposer, returned once the bounty is approved.
+	pub bond: Balance,
+	/// The status of this bounty.
+	pub status: BountyStatus<AccountId, BlockNumber>,
+}
+
+/// The status of a bounty.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum BountyStatus<AccountId, BlockNumber> {
+	/// The bounty is proposed and waiting for approval.
+	Proposed,
+	/// The bounty is approved and waiting to be funded at a spend period.
+	Approved,
+	/// The bounty is funded and waiting for a curator assignment.
+	Funded,
+	/// A curator has been proposed and is waiting for the acceptance from the curator.
+	CuratorProposed { curator: AccountId },
+	/// The bounty is active and waiting to be awarded.
+	Active {
+		curator: AccountId,
+		/// The bounty expires, and the curator can be unassigned by anyone, after this block.
+		update_due: BlockNumber,
+	},
+	/// The bounty is awarded and waiting to released after a delay.
+	PendingPayout {
+		curator: AccountId,
+		beneficiary: AccountId,
+		unlock_at: BlockNumber,
+	},
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = RingBalance<T>,
+	{
+		/// New bounty proposal. [index]
+		BountyProposed(BountyIndex),
+		/// A bounty proposal was rejected, the bond was slashed. [index, bond]
+		BountyRejected(BountyIndex, Balance),
+		/// A bounty proposal was approved, to be funded at the next spend period. [index]
+		BountyApproved(BountyIndex),
+		/// A bounty proposal was approved and funded from the treasury pot. [index]
+		BountyFunded(BountyIndex),
+		/// A curator was proposed for a bounty. [index, curator]
+		CuratorProposed(BountyIndex, AccountId),
+		/// The curator of a bounty was unassigned. [index]
+		CuratorUnassigned(BountyIndex),
+		/// A curator accepted a bounty. [index, curator]
+		CuratorAccepted(BountyIndex, AccountId),
+		/// A bounty expiry was extended. [index]
+		BountyExtended(BountyIndex),
+		/// A part of a bounty was paid out. [index, beneficiary, value]
+		ChildPaid(BountyIndex, AccountId, Balance),
+		/// A bounty was awarded to a beneficiary. [index, beneficiary]
+		BountyAwarded(BountyIndex, AccountId),
+		/// A bounty was claimed. [index, payout, beneficiary]
+		BountyClaimed(BountyIndex, Balance, AccountId),
+		/// A bounty was closed, the remaining funds went back to the treasury pot. [index]
+		BountyCanceled(BountyIndex),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Proposer's Balance - TOO LOW
+		InsufficientProposersBalance,
+		/// Bounty Index - INVALID
+		InvalidIndex,
+		/// Reason - TOO BIG
+		ReasonTooBig,
+		/// Bounty Status - UNEXPECTED
+		UnexpectedStatus,
+		/// Curator - REQUIRED
+		RequireCurator,
+		/// Bounty Value - TOO LOW
+		InvalidValue,
+		/// Curator Fee - TOO HIGH
+		InvalidFee,
+		/// Bounty - PENDING PAYOUT
+		PendingPayout,
+		/// Bounty - NOT EXPIRED
+		Premature,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaBounties {
+		/// Number of bounty proposals that have been made.
+		pub BountyCount get(fn bounty_count): BountyIndex;
+
+		/// Bounties that have been made.
+		pub Bounties get(fn bounties):
+			map hasher(twox_64_concat) BountyIndex
+			=> Option<Bounty<T::AccountId, RingBalance<T>, T::BlockNumber>>;
+
+		/// The description of each bounty.
+		pub BountyDescriptions get(fn bounty_descriptions):
+			map hasher(twox_64_concat) BountyIndex => Option<Vec<u8>>;
+
+		/// The approved bounties waiting to be funded.
+		pub BountyApprovals get(fn bounty_approvals): Vec<BountyIndex>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		const BountyDepositBase: RingBalance<T> = T::BountyDepositBase::get();
+
+		const DataDepositPerByte: RingBalance<T> = T::DataDepositPerByte::get();
+
+		const MaximumReasonLength: u32 = T::MaximumReasonLength::get();
+
+		const RingBountyValueMinimum: RingBalance<T> = T::RingBountyValueMinimum::get();
+
+		const KtonBountyValueMinimum: RingBalance<T> = T::KtonBountyValueMinimum::get();
+
+		const BountyCuratorDeposit: Permill = T::BountyCuratorDeposit::get();
+
+		const BountyDepositPayoutDelay: T::BlockNumber = T::BountyDepositPayoutDelay::get();
+
+		const BountyUpdatePeriod: T::BlockNumber = T::BountyUpdatePeriod::get();
+
+		fn deposit_event() = default;
+
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			if (n % T::SpendPeriod::get()).is_zero() {
+				Self::fund_approved_bounties()
+			} else {
+				0
+			}
+		}
+
+		/// Propose a new bounty.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// A RING bond of `BountyDepositBase` plus `DataDepositPerByte` for each byte of
+		/// `description` is reserved. It is returned once the bounty is approved and slashed if
+		/// the bounty is rejected.
+		///
+		/// - `asset`: The currency the bounty is paid in.
+		/// - `value`: The total payment amount of this bounty, curator fee included.
+		/// - `description`: The description of this bounty.
+		#[weight = T::DbWeight::get().reads_writes(1, 3)]
+		pub fn propose_bounty(
+			origin,
+			asset: BountyAsset,
+			#[compact] value: RingBalance<T>,
+			description: Vec<u8>,
+		) {
+			let proposer = ensure_signed(origin)?;
+
+			ensure!(
+				description.len() <= T::MaximumReasonLength::get() as usize,
+				<Error<T>>::ReasonTooBig
+			);
+			ensure!(
+				value >= match asset {
+					BountyAsset::Ring => T::RingBountyValueMinimum::get(),
+					BountyAsset::Kton => T::KtonBountyValueMinimum::get(),
+				},
+				<Error<T>>::InvalidValue
+			);
+
+			let index = Self::bounty_count();
+			let bond = T::BountyDepositBase::get()
+				+ T::DataDepositPerByte::get() * (description.len() as u32).into();
+
+			T::RingCurrency::reserve(&proposer, bond)
+				.map_err(|_| <Error<T>>::InsufficientProposersBalance)?;
+
+			<BountyCount>::put(index + 1);
+			<Bounties<T>>::insert(index, Bounty {
+				proposer,
+				asset,
+				value,
+				fee: Zero::zero(),
+				curator_deposit: Zero::zero(),
+				bond,
+				status: BountyStatus::Proposed,
+			});
+			<BountyDescriptions>::insert(index, description);
+
+			Self::deposit_event(RawEvent::BountyProposed(index));
+		}
+
+		/// Approve a bounty proposal, to be funded from the treasury pot at the next spend period.
+		///
+		/// The dispatch origin for this call must be `ApproveOrigin`.
+		#[weight = T::DbWeight::get().reads_writes(2, 2)]
+		pub fn approve_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::ApproveOrigin::ensure_origin(origin)?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				ensure!(bounty.status == BountyStatus::Proposed, <Error<T>>::UnexpectedStatus);
+
+				T::RingCurrency::unreserve(&bounty.proposer, bounty.bond);
+
+				bounty.status = BountyStatus::Approved;
+
+				<BountyApprovals>::append(bounty_id);
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::BountyApproved(bounty_id));
+		}
+
+		/// Assign a curator to a funded bounty.
+		///
+		/// The dispatch origin for this call must be `ApproveOrigin`.
+		///
+		/// - `fee`: The curator fee, paid out of the bounty value once it is claimed.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn propose_curator(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			curator: T::AccountId,
+			#[compact] fee: RingBalance<T>,
+		) {
+			T::ApproveOrigin::ensure_origin(origin)?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				ensure!(bounty.status == BountyStatus::Funded, <Error<T>>::UnexpectedStatus);
+				ensure!(fee < bounty.value, <Error<T>>::InvalidFee);
+
+				bounty.status = BountyStatus::CuratorProposed { curator: curator.clone() };
+				bounty.fee = fee;
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::CuratorProposed(bounty_id, curator));
+		}
+
+		/// Unassign the curator of a bounty.
+		///
+		/// The dispatch origin for this call must be either `RejectOrigin`, or _Signed_ by:
+		/// - the curator, who resigns and gets the deposit back;
+		/// - anyone, once the bounty expired, in which case the curator deposit is slashed.
+		///
+		/// The deposit is also slashed if `RejectOrigin` unassigns an active curator.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn unassign_curator(origin, #[compact] bounty_id: BountyIndex) {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::Proposed | BountyStatus::Approved | BountyStatus::Funded => {
+						return Err(<Error<T>>::UnexpectedStatus.into());
+					}
+					BountyStatus::CuratorProposed { ref curator } => {
+						ensure!(
+							maybe_sender.map_or(true, |sender| &sender == curator),
+							DispatchError::BadOrigin
+						);
+					}
+					BountyStatus::Active { ref curator, update_due } => match maybe_sender {
+						Some(ref sender) if sender == curator => {
+							Self::unreserve(bounty.asset, curator, bounty.curator_deposit);
+						}
+						Some(_) => {
+							ensure!(
+								update_due < <frame_system::Module<T>>::block_number(),
+								<Error<T>>::Premature
+							);
+
+							Self::slash_curator(bounty.asset, curator, bounty.curator_deposit);
+						}
+						None => Self::slash_curator(bounty.asset, curator, bounty.curator_deposit),
+					},
+					BountyStatus::PendingPayout { ref curator, .. } => {
+						ensure!(maybe_sender.is_none(), <Error<T>>::PendingPayout);
+
+						Self::slash_curator(bounty.asset, curator, bounty.curator_deposit);
+					}
+				}
+
+				bounty.status = BountyStatus::Funded;
+				bounty.curator_deposit = Zero::zero();
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::CuratorUnassigned(bounty_id));
+		}
+
+		/// Accept the curator role for a bounty.
+		///
+		/// The dispatch origin for this call must be the proposed curator.
+		///
+		/// A deposit of `BountyCuratorDeposit` of the fee is reserved, in the currency of the
+		/// bounty.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn accept_curator(origin, #[compact] bounty_id: BountyIndex) {
+			let signer = ensure_signed(origin)?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::CuratorProposed { ref curator } => {
+						ensure!(&signer == curator, <Error<T>>::RequireCurator);
+
+						let deposit = T::BountyCuratorDeposit::get() * bounty.fee;
+
+						Self::reserve(bounty.asset, curator, deposit)?;
+
+						bounty.curator_deposit = deposit;
+						bounty.status = BountyStatus::Active {
+							curator: signer.clone(),
+							update_due: Self::update_due(),
+						};
+
+						Ok(())
+					}
+					_ => Err(<Error<T>>::UnexpectedStatus.into()),
+				}
+			})?;
+
+			Self::deposit_event(RawEvent::CuratorAccepted(bounty_id, signer));
+		}
+
+		/// Extend the expiry of an active bounty by `BountyUpdatePeriod`.
+		///
+		/// The dispatch origin for this call must be the curator of this bounty.
+		///
+		/// - `_remark`: Additional information.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn extend_bounty_expiry(origin, #[compact] bounty_id: BountyIndex, _remark: Vec<u8>) {
+			let signer = ensure_signed(origin)?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::Active { ref curator, ref mut update_due } => {
+						ensure!(&signer == curator, <Error<T>>::RequireCurator);
+
+						*update_due = Self::update_due().max(*update_due);
+
+						Ok(())
+					}
+					_ => Err(<Error<T>>::UnexpectedStatus.into()),
+				}
+			})?;
+
+			Self::deposit_event(RawEvent::BountyExtended(bounty_id));
+		}
+
+		/// Pay out a part of an active bounty right away, for a finished stage of the work.
+		///
+		/// The dispatch origin for this call must be the curator of this bounty.
+		///
+		/// - `value`: The child payout, which can not touch the curator fee.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn pay_child(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			beneficiary: T::AccountId,
+			#[compact] value: RingBalance<T>,
+		) {
+			let signer = ensure_signed(origin)?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::Active { ref curator, .. } => {
+						ensure!(&signer == curator, <Error<T>>::RequireCurator);
+						ensure!(
+							!value.is_zero() && value <= bounty.value.saturating_sub(bounty.fee),
+							<Error<T>>::InvalidValue
+						);
+
+						Self::transfer(
+							bounty.asset,
+							&Self::bounty_account_id(bounty_id),
+							&beneficiary,
+							value,
+							ExistenceRequirement::AllowDeath,
+						)?;
+
+						bounty.value -= value;
+
+						Ok(())
+					}
+					_ => Err(<Error<T>>::UnexpectedStatus.into()),
+				}
+			})?;
+
+			Self::deposit_event(RawEvent::ChildPaid(bounty_id, beneficiary, value));
+		}
+
+		/// Award an active bounty to a beneficiary, who can claim it after
+		/// `BountyDepositPayoutDelay`.
+		///
+		/// The dispatch origin for this call must be the curator of this bounty.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		#[transactional]
+		pub fn award_bounty(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			beneficiary: T::AccountId,
+		) {
+			let signer = ensure_signed(origin)?;
+
+			<Bounties<T>>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(<Error<T>>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::Active { ref curator, .. } => {
+						ensure!(&signer == curator, <Error<T>>::RequireCurator);
+
+						bounty.status = BountyStatus::PendingPayout {
+							curator: signer,
+							beneficiary: beneficiary.clone(),
+							unlock_at: <frame_system::Module<T>>::block_number()
+								+ T::BountyDepositPayoutDelay::get(),
+						};
+
+						Ok(())
+					}
+					_ => Err(<Error<T>>::UnexpectedStatus.into()),
+				}
+			})?;
+
+			Self::deposit_event(RawEvent::BountyAwarded(bounty_id, beneficiary));
+		}
+
+		/// Claim an awarded bounty, paying the curator fee and the rest to the beneficiary.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[weight = T::DbWeight::get().reads_writes(1, 3)]
+		#[transactional]
+		pub fn claim_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			ensure_signed(origin)?;
+
+			let bounty = Self::bounties(bounty_id).ok_or(<Error<T>>::InvalidIndex)?;
+
+			if let BountyStatus::PendingPayout { curator, beneficiary, unlock_at } = bounty.status {
+				ensure!(
+					<frame_system::Module<T>>::block_number() >= unlock_at,
+					<Error<T>>::Premature
+				);
+
+				let bounty_account = Self::bounty_account_id(bounty_id);
+				let balance = Self::free_balance(bounty.asset, &bounty_account);
+				let fee = bounty.fee.min(balance);
+				let payout = balance.saturating_sub(fee);
+
+				Self::unreserve(bounty.asset, &curator, bounty.curator_deposit);
+
+				Self::transfer(
+					bounty.asset,
+					&bounty_account,
+					&curator,
+					fee,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				Self::transfer(
+					bounty.asset,
+					&bounty_account,
+					&beneficiary,
+					payout,
+					ExistenceRequirement::AllowDeath,
+				)?;
+
+				<Bounties<T>>::remove(bounty_id);
+				<BountyDescriptions>::remove(bounty_id);
+
+				Self::deposit_event(RawEvent::BountyClaimed(bounty_id, payout, beneficiary));
+			} else {
+				return Err(<Error<T>>::UnexpectedStatus.into());
+			}
+		}
+
+		/// Cancel a proposed or active bounty.
+		///
+		/// The dispatch origin for this call must be `RejectOrigin`.
+		///
+		/// The bond of a proposed bounty is slashed. An approved bounty is dropped from the
+		/// bounties to fund. The funds of a funded bounty go back to the treasury pot, and the
+		/// deposit of an active curator is returned.
+		#[weight = T::DbWeight::get().reads_writes(1, 3)]
+		#[transactional]
+		pub fn close_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::RejectOrigin::ensure_origin(origin)?;
+
+			let bounty = Self::bounties(bounty_id).ok_or(<Error<T>>::InvalidIndex)?;
+
+			match bounty.status {
+				BountyStatus::Proposed => {
+					let (imbalance, _) = T::RingCurrency::slash_reserved(&bounty.proposer, bounty.bond);
+
+					T::OnSlash::on_unbalanced(imbalance);
+
+					<Bounties<T>>::remove(bounty_id);
+					<BountyDescriptions>::remove(bounty_id);
+
+					Self::deposit_event(RawEvent::BountyRejected(bounty_id, bounty.bond));
+
+					return Ok(());
+				}
+				BountyStatus::Approved => {
+					<BountyApprovals>::mutate(|bounty_ids| bounty_ids.retain(|id| *id != bounty_id));
+					<Bounties<T>>::remove(bounty_id);
+					<BountyDescriptions>::remove(bounty_id);
+
+					Self::deposit_event(RawEvent::BountyCanceled(bounty_id));
+
+					return Ok(());
+				}
+				BountyStatus::Funded | BountyStatus::CuratorProposed { .. } => (),
+				BountyStatus::Active { ref curator, .. } => {
+					Self::unreserve(bounty.asset, curator, bounty.curator_deposit);
+				}
+				BountyStatus::PendingPayout { .. } => return Err(<Error<T>>::PendingPayout.into()),
+			}
+
+			let bounty_account = Self::bounty_account_id(bounty_id);
+
+			Self::transfer(
+				bounty.asset,
+				&bounty_account,
+				&Self::account_id(),
+				Self::free_balance(bounty.asset, &bounty_account),
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			<Bounties<T>>::remove(bounty_id);
+			<BountyDescriptions>::remove(bounty_id);
+
+			Self::deposit_event(RawEvent::BountyCanceled(bounty_id));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account ID of the treasury pot.
+	pub fn account_id() -> T::AccountId {
+		T::TreasuryModuleId::get().into_account()
+	}
+
+	/// The account ID of a bounty account.
+	pub fn bounty_account_id(id: BountyIndex) -> T::AccountId {
+		T::TreasuryModuleId::get().into_sub_account(("bt", id))
+	}
+
+	/// Fund the approved bounties from the treasury pot, the ones it can't afford wait for the
+	/// next spend period.
+	fn fund_approved_bounties() -> Weight {
+		let treasury = Self::account_id();
+		let approvals = <BountyApprovals>::take();
+		let weight = T::DbWeight::get().reads_writes(
+			1 + 3 * approvals.len() as Weight,
+			1 + 3 * approvals.len() as Weight,
+		);
+		let unfunded = approvals
+			.into_iter()
+			.filter(|&bounty_id| {
+				<Bounties<T>>::mutate(bounty_id, |maybe_bounty| match maybe_bounty {
+					Some(bounty) if bounty.status == BountyStatus::Approved => {
+						if Self::transfer(
+							bounty.asset,
+							&treasury,
+							&Self::bounty_account_id(bounty_id),
+							bounty.value,
+							ExistenceRequirement::KeepAlive,
+						)
+						.is_err()
+						{
+							return true;
+						}
+
+						bounty.status = BountyStatus::Funded;
+
+						Self::deposit_event(RawEvent::BountyFunded(bounty_id));
+
+						false
+					}
+					_ => false,
+				})
+			})
+			.collect::<Vec<_>>();
+
+		<BountyApprovals>::put(unfunded);
+
+		weight
+	}
+
+	fn update_due() -> T::BlockNumber {
+		<frame_system::Module<T>>::block_number().saturating_add(T::BountyUpdatePeriod::get())
+	}
+
+	fn slash_curator(asset: BountyAsset, curator: &T::AccountId, deposit: RingBalance<T>) {
+		match asset {
+			BountyAsset::Ring => {
+				let (imbalance, _) = T::RingCurrency::slash_reserved(curator, deposit);
+
+				T::OnSlash::on_unbalanced(imbalance);
+			}
+			BountyAsset::Kton => {
+				let (imbalance, _) = T::KtonCurrency::slash_reserved(curator, deposit);
+
+				T::OnKtonSlash::on_unbalanced(imbalance);
+			}
+		}
+	}
+
+	fn reserve(asset: BountyAsset, who: &T::AccountId, value: RingBalance<T>) -> DispatchResult {
+		match asset {
+			BountyAsset::Ring => T::RingCurrency::reserve(who, value),
+			BountyAsset::Kton => T::KtonCurrency::reserve(who, value),
+		}
+	}
+
+	fn unreserve(asset: BountyAsset, who: &T::AccountId, value: RingBalance<T>) {
+		match asset {
+			BountyAsset::Ring => T::RingCurrency::unreserve(who, value),
+			BountyAsset::Kton => T::KtonCurrency::unreserve(who, value),
+		};
+	}
+
+	fn free_balance(asset: BountyAsset, who: &T::AccountId) -> RingBalance<T> {
+		match asset {
+			BountyAsset::Ring => T::RingCurrency::free_balance(who),
+			BountyAsset::Kton => T::KtonCurrency::free_balance(who),
+		}
+	}
+
+	fn transfer(
+		asset: BountyAsset,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: RingBalance<T>,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+
+		match asset {
+			BountyAsset::Ring => {
+				T::RingCurrency::transfer(source, dest, value, existence_requirement)
+			}
+			BountyAsset::Kton => {
+				T::KtonCurrency::transfer(source, dest, value, existence_requirement)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_origin, parameter_types, traits::OnInitialize,
+		weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type Bounties = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		// Wide enough for the treasury and the bounty accounts not to collide.
+		type AccountId = u128;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 10;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const TreasuryModuleId: ModuleId = ModuleId(*b"da/trsry");
+		pub const BountyDepositBase: Balance = 80;
+		pub const DataDepositPerByte: Balance = 1;
+		pub const MaximumReasonLength: u32 = 16384;
+		pub const RingBountyValueMinimum: Balance = 100;
+		pub const KtonBountyValueMinimum: Balance = 20;
+		pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+		pub const BountyDepositPayoutDelay: u64 = 3;
+		pub const BountyUpdatePeriod: u64 = 20;
+		pub const SpendPeriod: u64 = 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type TreasuryModuleId = TreasuryModuleId;
+		type RingCurrency = Ring;
+		type KtonCurrency = Kton;
+		type ApproveOrigin = EnsureRoot<u128>;
+		type RejectOrigin = EnsureRoot<u128>;
+		type BountyDepositBase = BountyDepositBase;
+		type DataDepositPerByte = DataDepositPerByte;
+		type MaximumReasonLength = MaximumReasonLength;
+		type RingBountyValueMinimum = RingBountyValueMinimum;
+		type KtonBountyValueMinimum = KtonBountyValueMinimum;
+		type BountyCuratorDeposit = BountyCuratorDeposit;
+		type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+		type BountyUpdatePeriod = BountyUpdatePeriod;
+		type SpendPeriod = SpendPeriod;
+		type OnSlash = ();
+		type OnKtonSlash = ();
+	}
+
+	const PROPOSER: u128 = 1;
+	const CURATOR: u128 = 2;
+	const BENEFICIARY: u128 = 3;
+	const CHILD_BENEFICIARY: u128 = 4;
+
+	/// The proposer and the curator have 1_000 RING and KTON, the treasury pot 1_000 RING and 100
+	/// KTON.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+		let treasury = Bounties::account_id();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(PROPOSER, 1_000), (CURATOR, 1_000), (treasury, 1_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		darwinia_balances::GenesisConfig::<Test, KtonInstance> {
+			balances: vec![(PROPOSER, 1_000), (CURATOR, 1_000), (treasury, 100)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	fn status(bounty_id: BountyIndex) -> Option<BountyStatus<u128, u64>> {
+		Bounties::bounties(bounty_id).map(|bounty| bounty.status)
+	}
+
+	/// Propose and approve a bounty, funded at block 2.
+	fn fund_bounty(asset: BountyAsset, value: Balance) -> BountyIndex {
+		let bounty_id = Bounties::bounty_count();
+
+		assert_ok!(Bounties::propose_bounty(
+			Origin::signed(PROPOSER),
+			asset,
+			value,
+			b"bounty".to_vec()
+		));
+		assert_ok!(Bounties::approve_bounty(Origin::root(), bounty_id));
+
+		Bounties::on_initialize(2);
+
+		assert_eq!(status(bounty_id), Some(BountyStatus::Funded));
+
+		bounty_id
+	}
+
+	#[test]
+	fn propose_bounty_should_check_the_minimum_of_its_asset() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Bounties::propose_bounty(Origin::signed(PROPOSER), BountyAsset::Ring, 99, vec![]),
+				<Error<Test>>::InvalidValue
+			);
+			assert_noop!(
+				Bounties::propose_bounty(Origin::signed(PROPOSER), BountyAsset::Kton, 19, vec![]),
+				<Error<Test>>::InvalidValue
+			);
+			assert_ok!(Bounties::propose_bounty(
+				Origin::signed(PROPOSER),
+				BountyAsset::Kton,
+				20,
+				vec![]
+			));
+			assert_ok!(Bounties::propose_bounty(
+				Origin::signed(PROPOSER),
+				BountyAsset::Ring,
+				100,
+				b"bounty".to_vec()
+			));
+
+			// The RING bond is reserved whichever the asset, along with the description deposit.
+			assert_eq!(Ring::reserved_balance(&PROPOSER), 80 + 80 + 6);
+			assert_eq!(Kton::reserved_balance(&PROPOSER), 0);
+			assert_eq!(status(1), Some(BountyStatus::Proposed));
+		});
+	}
+
+	#[test]
+	fn approved_bounties_should_be_funded_at_the_spend_period() {
+		new_test_ext().execute_with(|| {
+			let treasury = Bounties::account_id();
+
+			assert_ok!(Bounties::propose_bounty(
+				Origin::signed(PROPOSER),
+				BountyAsset::Ring,
+				500,
+				vec![]
+			));
+			assert_ok!(Bounties::propose_bounty(
+				Origin::signed(PROPOSER),
+				BountyAsset::Ring,
+				600,
+				vec![]
+			));
+			assert_ok!(Bounties::propose_bounty(
+				Origin::signed(PROPOSER),
+				BountyAsset::Kton,
+				50,
+				vec![]
+			));
+			assert_ok!(Bounties::approve_bounty(Origin::root(), 0));
+			assert_ok!(Bounties::approve_bounty(Origin::root(), 1));
+			assert_ok!(Bounties::approve_bounty(Origin::root(), 2));
+			// The bonds are returned on approval.
+			assert_eq!(Ring::reserved_balance(&PROPOSER), 0);
+
+			// Not a spend period.
+			Bounties::on_initialize(1);
+
+			assert_eq!(status(0), Some(BountyStatus::Approved));
+			assert_eq!(Bounties::bounty_approvals(), vec![0, 1, 2]);
+
+			// The pot can't afford the second bounty after the first one.
+			Bounties::on_initialize(2);
+
+			assert_eq!(status(0), Some(BountyStatus::Funded));
+			assert_eq!(status(1), Some(BountyStatus::Approved));
+			assert_eq!(status(2), Some(BountyStatus::Funded));
+			assert_eq!(Bounties::bounty_approvals(), vec![1]);
+			assert_eq!(Ring::free_balance(&treasury), 500);
+			assert_eq!(Ring::free_balance(&Bounties::bounty_account_id(0)), 500);
+			assert_eq!(Kton::free_balance(&treasury), 50);
+			assert_eq!(Kton::free_balance(&Bounties::bounty_account_id(2)), 50);
+
+			// It waits for the following spend periods.
+			Ring::make_free_balance_be(&treasury, 1_000);
+			Bounties::on_initialize(3);
+
+			assert_eq!(status(1), Some(BountyStatus::Approved));
+
+			Bounties::on_initialize(4);
+
+			assert_eq!(status(1), Some(BountyStatus::Funded));
+			assert!(Bounties::bounty_approvals().is_empty());
+			assert_eq!(Ring::free_balance(&treasury), 400);
+		});
+	}
+
+	#[test]
+	fn bounty_should_pay_the_child_the_curator_and_the_beneficiary() {
+		new_test_ext().execute_with(|| {
+			let bounty_id = fund_bounty(BountyAsset::Ring, 500);
+
+			assert_noop!(
+				Bounties::propose_curator(Origin::root(), bounty_id, CURATOR, 500),
+				<Error<Test>>::InvalidFee
+			);
+			assert_ok!(Bounties::propose_curator(
+				Origin::root(),
+				bounty_id,
+				CURATOR,
+				100
+			));
+			assert_noop!(
+				Bounties::accept_curator(Origin::signed(PROPOSER), bounty_id),
+				<Error<Test>>::RequireCurator
+			);
+			assert_ok!(Bounties::accept_curator(Origin::signed(CURATOR), bounty_id));
+			assert_eq!(Ring::reserved_balance(&CURATOR), 50);
+
+			// The child payouts can't touch the curator fee.
+			assert_noop!(
+				Bounties::pay_child(Origin::signed(CURATOR), bounty_id, CHILD_BENEFICIARY, 401),
+				<Error<Test>>::InvalidValue
+			);
+			assert_ok!(Bounties::pay_child(
+				Origin::signed(CURATOR),
+				bounty_id,
+				CHILD_BENEFICIARY,
+				150
+			));
+			assert_eq!(Ring::free_balance(&CHILD_BENEFICIARY), 150);
+
+			assert_ok!(Bounties::award_bounty(
+				Origin::signed(CURATOR),
+				bounty_id,
+				BENEFICIARY
+			));
+			assert_noop!(
+				Bounties::claim_bounty(Origin::signed(BENEFICIARY), bounty_id),
+				<Error<Test>>::Premature
+			);
+
+			System::set_block_number(4);
+
+			assert_ok!(Bounties::claim_bounty(
+				Origin::signed(BENEFICIARY),
+				bounty_id
+			));
+			assert_eq!(Ring::free_balance(&BENEFICIARY), 500 - 150 - 100);
+			assert_eq!(Ring::free_balance(&CURATOR), 1_000 + 100);
+			assert_eq!(Ring::reserved_balance(&CURATOR), 0);
+			assert_eq!(
+				Ring::free_balance(&Bounties::bounty_account_id(bounty_id)),
+				0
+			);
+			assert_eq!(status(bounty_id), None);
+			assert_eq!(Bounties::bounty_descriptions(bounty_id), None);
+		});
+	}
+
+	#[test]
+	fn kton_bounty_should_reserve_the_curator_deposit_in_kton() {
+		new_test_ext().execute_with(|| {
+			let bounty_id = fund_bounty(BountyAsset::Kton, 50);
+
+			assert_ok!(Bounties::propose_curator(
+				Origin::root(),
+				bounty_id,
+				CURATOR,
+				20
+			));
+			assert_ok!(Bounties::accept_curator(Origin::signed(CURATOR), bounty_id));
+			assert_eq!(Kton::reserved_balance(&CURATOR), 10);
+			assert_eq!(Ring::reserved_balance(&CURATOR), 0);
+
+			assert_ok!(Bounties::award_bounty(
+				Origin::signed(CURATOR),
+				bounty_id,
+				BENEFICIARY
+			));
+
+			System::set_block_number(4);
+
+			assert_ok!(Bounties::claim_bounty(
+				Origin::signed(BENEFICIARY),
+				bounty_id
+			));
+			assert_eq!(Kton::free_balance(&BENEFICIARY), 30);
+			assert_eq!(Kton::free_balance(&CURATOR), 1_000 + 20);
+			assert_eq!(Kton::reserved_balance(&CURATOR), 0);
+		});
+	}
+
+	#[test]
+	fn failed_claim_should_keep_the_curator_deposit_reserved() {
+		new_test_ext().execute_with(|| {
+			let bounty_id = fund_bounty(BountyAsset::Ring, 100);
+
+			assert_ok!(Bounties::propose_curator(
+				Origin::root(),
+				bounty_id,
+				CURATOR,
+				95
+			));
+			assert_ok!(Bounties::accept_curator(Origin::signed(CURATOR), bounty_id));
+
+			let curator_deposit = Ring::reserved_balance(&CURATOR);
+
+			assert_ok!(Bounties::award_bounty(
+				Origin::signed(CURATOR),
+				bounty_id,
+				BENEFICIARY
+			));
+
+			System::set_block_number(4);
+
+			// The 5 RING left after the fee can't open the account of the beneficiary, so the
+			// returned deposit and the paid fee are rolled back.
+			assert!(Bounties::claim_bounty(Origin::signed(BENEFICIARY), bounty_id).is_err());
+			assert_eq!(Ring::reserved_balance(&CURATOR), curator_deposit);
+			assert_eq!(Ring::free_balance(&CURATOR), 1_000 - curator_deposit);
+			assert_eq!(
+				Ring::free_balance(&Bounties::bounty_account_id(bounty_id)),
+				100
+			);
+			assert!(matches!(
+				status(bounty_id),
+				Some(BountyStatus::PendingPayout { .. })
+			));
+		});
+	}
+
+	#[test]
+	fn close_bounty_should_return_the_funds_to_the_pot() {
+		new_test_ext().execute_with(|| {
+			let treasury = Bounties::account_id();
+
+			assert_ok!(Bounties::propose_bounty(
+				Origin::signed(PROPOSER),
+				BountyAsset::Ring,
+				100,
+				vec![]
+			));
+			assert_ok!(Bounties::close_bounty(Origin::root(), 0));
+			// The bond is slashed.
+			assert_eq!(Ring::reserved_balance(&PROPOSER), 0);
+			assert_eq!(Ring::free_balance(&PROPOSER), 1_000 - 80);
+
+			let bounty_id = fund_bounty(BountyAsset::Ring, 300);
+
+			assert_ok!(Bounties::propose_curator(
+				Origin::root(),
+				bounty_id,
+				CURATOR,
+				100
+			));
+			assert_ok!(Bounties::accept_curator(Origin::signed(CURATOR), bounty_id));
+			assert_ok!(Bounties::close_bounty(Origin::root(), bounty_id));
+			assert_eq!(Ring::free_balance(&treasury), 1_000);
+			assert_eq!(Ring::reserved_balance(&CURATOR), 0);
+			assert_eq!(Ring::free_balance(&CURATOR), 1_000);
+			assert_eq!(status(bounty_id), None);
+		});
+	}
+}
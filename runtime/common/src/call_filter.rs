@@ -0,0 +1,416 @@
+//! Emergency call filter, a circuit breaker which pauses whole pallets or single calls without a
+//! runtime upgrade.
+//!
+//! The module is used as the `frame_system::Trait::BaseCallFilter`. The pallets in
+//! `UnpausablePallets` can never be paused, which should at least cover `System`, the governance
+//! and this module itself so that unpausing always stays possible.
+//!
+//! Only the pallets and calls of the runtime can be paused, named as in `construct_runtime!` and as
+//! the functions of their `decl_module!`.
+//!
+//! The filter only applies at dispatch, so the runtime should also reject the paused calls in
+//! `TaggedTransactionQueue::validate_transaction`, or they would pay their fees only to fail at
+//! dispatch.
+
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{CallMetadata, EnsureOrigin, Filter, Get, GetCallMetadata},
+};
+use sp_std::prelude::*;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The origin which may pause and unpause the pallets and calls.
+	type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The names of the pallets, as in `construct_runtime!`, which can never be paused.
+	type UnpausablePallets: Get<Vec<&'static str>>;
+}
+
+decl_event! {
+	pub enum Event {
+		/// A pallet was paused. [pallet]
+		PalletPaused(Vec<u8>),
+		/// A pallet was unpaused. [pallet]
+		PalletUnpaused(Vec<u8>),
+		/// A call was paused. [pallet, call]
+		CallPaused(Vec<u8>, Vec<u8>),
+		/// A call was unpaused. [pallet, call]
+		CallUnpaused(Vec<u8>, Vec<u8>),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Pallet - UNPAUSABLE
+		Unpausable,
+		/// Pallet - NOT EXISTED
+		PalletNE,
+		/// Call - NOT EXISTED
+		CallNE,
+		/// Pallet - NOT PAUSED
+		PalletNotPaused,
+		/// Call - NOT PAUSED
+		CallNotPaused,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaCallFilter {
+		/// The paused pallets, all of their calls are filtered.
+		pub PausedPallets get(fn paused_pallets):
+			map hasher(blake2_128_concat) Vec<u8> => bool;
+
+		/// The paused calls, keyed by pallet and call name.
+		pub PausedCalls get(fn paused_calls):
+			double_map hasher(blake2_128_concat) Vec<u8>, hasher(blake2_128_concat) Vec<u8> => bool;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin,
+		<T as frame_system::Trait>::Call: GetCallMetadata
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Pause all the calls of a pallet.
+		///
+		/// The dispatch origin for this call must be `PauseOrigin`.
+		///
+		/// - `pallet`: The name of the pallet in `construct_runtime!`, e.g. `EthereumBacking`.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn pause_pallet(origin, pallet: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(Self::pallet_name(&pallet).is_some(), <Error<T>>::PalletNE);
+			ensure!(!Self::is_unpausable(&pallet), <Error<T>>::Unpausable);
+
+			<PausedPallets>::insert(&pallet, true);
+
+			Self::deposit_event(Event::PalletPaused(pallet));
+		}
+
+		/// Unpause a paused pallet.
+		///
+		/// The dispatch origin for this call must be `PauseOrigin`.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn unpause_pallet(origin, pallet: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(<PausedPallets>::contains_key(&pallet), <Error<T>>::PalletNotPaused);
+
+			<PausedPallets>::remove(&pallet);
+
+			Self::deposit_event(Event::PalletUnpaused(pallet));
+		}
+
+		/// Pause a single call of a pallet.
+		///
+		/// The dispatch origin for this call must be `PauseOrigin`.
+		///
+		/// - `pallet`: The name of the pallet in `construct_runtime!`, e.g. `Balances`.
+		/// - `call`: The name of the call, e.g. `transfer`.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn pause_call(origin, pallet: Vec<u8>, call: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let pallet_name = Self::pallet_name(&pallet).ok_or(<Error<T>>::PalletNE)?;
+
+			ensure!(
+				<<T as frame_system::Trait>::Call>::get_call_names(pallet_name)
+					.iter()
+					.any(|call_name| call_name.as_bytes() == &call[..]),
+				<Error<T>>::CallNE
+			);
+			ensure!(!Self::is_unpausable(&pallet), <Error<T>>::Unpausable);
+
+			<PausedCalls>::insert(&pallet, &call, true);
+
+			Self::deposit_event(Event::CallPaused(pallet, call));
+		}
+
+		/// Unpause a paused call.
+		///
+		/// The dispatch origin for this call must be `PauseOrigin`.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn unpause_call(origin, pallet: Vec<u8>, call: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(<PausedCalls>::contains_key(&pallet, &call), <Error<T>>::CallNotPaused);
+
+			<PausedCalls>::remove(&pallet, &call);
+
+			Self::deposit_event(Event::CallUnpaused(pallet, call));
+		}
+	}
+}
+
+impl<T: Trait> Module<T>
+where
+	<T as frame_system::Trait>::Call: GetCallMetadata,
+{
+	/// The name of `pallet` in `construct_runtime!`, `None` if the runtime has no such pallet.
+	fn pallet_name(pallet: &[u8]) -> Option<&'static str> {
+		<<T as frame_system::Trait>::Call>::get_module_names()
+			.iter()
+			.find(|pallet_name| pallet_name.as_bytes() == pallet)
+			.copied()
+	}
+
+	fn is_unpausable(pallet: &[u8]) -> bool {
+		T::UnpausablePallets::get()
+			.iter()
+			.any(|unpausable| unpausable.as_bytes() == pallet)
+	}
+}
+
+impl<T: Trait> Filter<<T as frame_system::Trait>::Call> for Module<T>
+where
+	<T as frame_system::Trait>::Call: GetCallMetadata,
+{
+	fn filter(call: &<T as frame_system::Trait>::Call) -> bool {
+		let CallMetadata {
+			function_name,
+			pallet_name,
+		} = call.get_call_metadata();
+
+		if Self::is_unpausable(pallet_name.as_bytes()) {
+			return true;
+		}
+
+		!<PausedPallets>::contains_key(pallet_name.as_bytes())
+			&& !<PausedCalls>::contains_key(pallet_name.as_bytes(), function_name.as_bytes())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+		weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, Dispatchable, IdentityLookup},
+		DispatchError, Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::call_filter;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			frame_system::System,
+			call_filter::CallFilter,
+		}
+	}
+
+	type System = frame_system::Module<Test>;
+	type CallFilter = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = CallFilter;
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub UnpausablePallets: Vec<&'static str> = vec!["CallFilter"];
+	}
+	impl Trait for Test {
+		type Event = ();
+		type PauseOrigin = EnsureRoot<u64>;
+		type UnpausablePallets = UnpausablePallets;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap()
+			.into()
+	}
+
+	fn remark() -> Call {
+		Call::System(frame_system::Call::remark(vec![]))
+	}
+
+	fn set_heap_pages() -> Call {
+		Call::System(frame_system::Call::set_heap_pages(64))
+	}
+
+	fn unpause_pallet() -> Call {
+		Call::CallFilter(call_filter::Call::unpause_pallet(b"System".to_vec()))
+	}
+
+	fn is_allowed(call: &Call) -> bool {
+		<CallFilter as Filter<Call>>::filter(call)
+	}
+
+	#[test]
+	fn pause_pallet_should_filter_all_of_its_calls() {
+		new_test_ext().execute_with(|| {
+			assert!(is_allowed(&remark()));
+			assert!(is_allowed(&set_heap_pages()));
+
+			assert_ok!(CallFilter::pause_pallet(Origin::root(), b"System".to_vec()));
+
+			assert!(!is_allowed(&remark()));
+			assert!(!is_allowed(&set_heap_pages()));
+			assert!(is_allowed(&unpause_pallet()));
+
+			assert_ok!(CallFilter::unpause_pallet(
+				Origin::root(),
+				b"System".to_vec()
+			));
+
+			assert!(is_allowed(&remark()));
+			assert!(is_allowed(&set_heap_pages()));
+		});
+	}
+
+	#[test]
+	fn pause_call_should_only_filter_this_call() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CallFilter::pause_call(
+				Origin::root(),
+				b"System".to_vec(),
+				b"remark".to_vec()
+			));
+
+			assert!(!is_allowed(&remark()));
+			assert!(is_allowed(&set_heap_pages()));
+
+			assert_ok!(CallFilter::unpause_call(
+				Origin::root(),
+				b"System".to_vec(),
+				b"remark".to_vec()
+			));
+
+			assert!(is_allowed(&remark()));
+		});
+	}
+
+	#[test]
+	fn paused_call_should_fail_at_dispatch() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CallFilter::pause_call(
+				Origin::root(),
+				b"System".to_vec(),
+				b"remark".to_vec()
+			));
+
+			assert_noop!(
+				remark().dispatch(Origin::signed(1)),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn unpausable_pallets_should_never_be_paused() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CallFilter::pause_pallet(Origin::root(), b"CallFilter".to_vec()),
+				<Error<Test>>::Unpausable
+			);
+			assert_noop!(
+				CallFilter::pause_call(
+					Origin::root(),
+					b"CallFilter".to_vec(),
+					b"unpause_pallet".to_vec()
+				),
+				<Error<Test>>::Unpausable
+			);
+
+			// Even if it was paused before becoming unpausable.
+			<PausedPallets>::insert(b"CallFilter".to_vec(), true);
+
+			assert!(is_allowed(&unpause_pallet()));
+		});
+	}
+
+	#[test]
+	fn unknown_pallets_and_calls_should_be_rejected() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CallFilter::pause_pallet(Origin::root(), b"Sytem".to_vec()),
+				<Error<Test>>::PalletNE
+			);
+			assert_noop!(
+				CallFilter::pause_pallet(Origin::root(), b"system".to_vec()),
+				<Error<Test>>::PalletNE
+			);
+			assert_noop!(
+				CallFilter::pause_call(Origin::root(), b"Sytem".to_vec(), b"remark".to_vec()),
+				<Error<Test>>::PalletNE
+			);
+			assert_noop!(
+				CallFilter::pause_call(Origin::root(), b"System".to_vec(), b"remak".to_vec()),
+				<Error<Test>>::CallNE
+			);
+			// A call of another pallet.
+			assert_noop!(
+				CallFilter::pause_call(Origin::root(), b"System".to_vec(), b"pause_call".to_vec()),
+				<Error<Test>>::CallNE
+			);
+		});
+	}
+
+	#[test]
+	fn only_pause_origin_should_pause() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CallFilter::pause_pallet(Origin::signed(1), b"System".to_vec()),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				CallFilter::pause_call(Origin::signed(1), b"System".to_vec(), b"remark".to_vec()),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				CallFilter::unpause_pallet(Origin::root(), b"System".to_vec()),
+				<Error<Test>>::PalletNotPaused
+			);
+		});
+	}
+}
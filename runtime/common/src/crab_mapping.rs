@@ -0,0 +1,331 @@
+//! Map CRING to the mainnet RING.
+//!
+//! `darwinia_crab_issuing` records in `TotalMappedRing` how much RING the mainnet honours for
+//! CRING. A holder burns CRING within that allowance, and the burn is stored as a `MappingRecord`
+//! and deposited as a `Mapped` event. The record is proven to the mainnet with a storage proof
+//! against a Crab header, which is itself proven through the Header MMR, and the mainnet pays the
+//! receiver the same amount of RING.
+
+pub use darwinia_rpc_runtime_api::crab_mapping::MappingAllowance;
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{Currency, ExistenceRequirement, Get, WithdrawReason},
+};
+use frame_system::ensure_signed;
+use sp_runtime::{traits::Saturating, RuntimeDebug};
+
+pub type AccountId<T> = <T as frame_system::Trait>::AccountId;
+pub type RingBalance<T> = <<T as Trait>::RingCurrency as Currency<AccountId<T>>>::Balance;
+pub type MappingRecordOf<T> =
+	MappingRecord<AccountId<T>, RingBalance<T>, <T as frame_system::Trait>::BlockNumber>;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	type RingCurrency: Currency<Self::AccountId>;
+
+	/// The CRING the mainnet honours in total.
+	type TotalMappable: Get<RingBalance<Self>>;
+
+	/// The minimum CRING of a mapping.
+	type MinimumMapping: Get<RingBalance<Self>>;
+}
+
+/// A mapping of CRING to the mainnet RING.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct MappingRecord<AccountId, Balance, BlockNumber> {
+	/// The account which burned the CRING.
+	pub who: AccountId,
+	/// The mainnet account which receives the RING.
+	pub receiver: AccountId,
+	/// The CRING burned.
+	pub amount: Balance,
+	/// The block of the burn.
+	pub at: BlockNumber,
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = AccountId<T>,
+		RingBalance = RingBalance<T>,
+	{
+		/// CRING was burned to be mapped to the mainnet RING. [index, who, mainnet receiver, amount]
+		Mapped(u64, AccountId, AccountId, RingBalance),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Mapping - BELOW MINIMUM
+		MappingBM,
+		/// Mapping Allowance - INSUFFICIENT
+		MappingAllowanceInsu,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaCrabMapping {
+		/// The CRING mapped so far.
+		pub MappedRing get(fn mapped_ring): RingBalance<T>;
+
+		/// The number of mappings, which is the index of the next one.
+		pub MappingCount get(fn mapping_count): u64;
+
+		/// The mappings, by index.
+		pub MappingRecords get(fn mapping_record):
+			map hasher(identity) u64 => Option<MappingRecordOf<T>>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		const MinimumMapping: RingBalance<T> = T::MinimumMapping::get();
+
+		fn deposit_event() = default;
+
+		/// Burn `amount` CRING to be paid the same amount of RING on the mainnet.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `receiver`: The mainnet account which receives the RING.
+		#[weight = T::DbWeight::get().reads_writes(4, 4)]
+		pub fn map(origin, receiver: AccountId<T>, #[compact] amount: RingBalance<T>) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(amount >= T::MinimumMapping::get(), <Error<T>>::MappingBM);
+			ensure!(amount <= Self::mapping_allowance().remaining, <Error<T>>::MappingAllowanceInsu);
+
+			// Dropping the imbalance burns the CRING.
+			let _ = T::RingCurrency::withdraw(
+				&who,
+				amount,
+				WithdrawReason::Transfer.into(),
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			let index = Self::mapping_count();
+
+			<MappedRing<T>>::mutate(|mapped_ring| *mapped_ring = mapped_ring.saturating_add(amount));
+			<MappingCount>::put(index + 1);
+			<MappingRecords<T>>::insert(
+				index,
+				MappingRecord {
+					who: who.clone(),
+					receiver: receiver.clone(),
+					amount,
+					at: <frame_system::Module<T>>::block_number(),
+				},
+			);
+
+			Self::deposit_event(RawEvent::Mapped(index, who, receiver, amount));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The CRING which may still be mapped.
+	pub fn mapping_allowance() -> MappingAllowance<RingBalance<T>> {
+		let total = T::TotalMappable::get();
+		let mapped = Self::mapped_ring();
+
+		MappingAllowance {
+			total,
+			mapped,
+			remaining: total.saturating_sub(mapped),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight,
+	};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type CrabMapping = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const TotalMappable: Balance = 1_000;
+		pub const MinimumMapping: Balance = 10;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type RingCurrency = Ring;
+		type TotalMappable = TotalMappable;
+		type MinimumMapping = MinimumMapping;
+	}
+
+	const RECEIVER: u64 = 100;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(1, 2_000), (2, 2_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::from(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	#[test]
+	fn map_should_burn_the_cring() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CrabMapping::map(Origin::signed(1), RECEIVER, 300));
+			assert_eq!(Ring::free_balance(1), 1_700);
+			assert_eq!(Ring::total_issuance(), 3_700);
+			assert_eq!(
+				CrabMapping::mapping_record(0),
+				Some(MappingRecord {
+					who: 1,
+					receiver: RECEIVER,
+					amount: 300,
+					at: 1,
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn map_should_keep_within_the_total() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CrabMapping::map(Origin::signed(1), RECEIVER, 9),
+				<Error<Test>>::MappingBM
+			);
+			assert_noop!(
+				CrabMapping::map(Origin::signed(1), RECEIVER, 1_001),
+				<Error<Test>>::MappingAllowanceInsu
+			);
+			assert_ok!(CrabMapping::map(Origin::signed(1), RECEIVER, 1_000));
+			assert_eq!(
+				CrabMapping::mapping_allowance(),
+				MappingAllowance {
+					total: 1_000,
+					mapped: 1_000,
+					remaining: 0,
+				}
+			);
+			assert_noop!(
+				CrabMapping::map(Origin::signed(2), RECEIVER, 10),
+				<Error<Test>>::MappingAllowanceInsu
+			);
+		});
+	}
+
+	#[test]
+	fn each_mapping_should_be_recorded_apart() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CrabMapping::map(Origin::signed(1), RECEIVER, 400));
+			assert_ok!(CrabMapping::map(Origin::signed(1), RECEIVER, 400));
+			assert_eq!(CrabMapping::mapping_count(), 2);
+			assert_eq!(CrabMapping::mapped_ring(), 800);
+			assert_eq!(Ring::free_balance(1), 1_200);
+			assert_eq!(
+				CrabMapping::mapping_record(1).map(|record| record.amount),
+				Some(400)
+			);
+			// The second mapping of the same holder counts against the same total.
+			assert_noop!(
+				CrabMapping::map(Origin::signed(1), RECEIVER, 201),
+				<Error<Test>>::MappingAllowanceInsu
+			);
+			assert_eq!(CrabMapping::mapping_allowance().remaining, 200);
+		});
+	}
+}
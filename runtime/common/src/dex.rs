@@ -0,0 +1,744 @@
+//! A constant product market maker trading RING against KTON.
+//!
+//! The liquidity providers deposit both assets at the pool price in exchange for shares. Each swap
+//! pays `FeeRate` of the amount swapped in to the treasury, the rest goes into the pool.
+//!
+//! `MinimumLiquidity` of the first shares are locked in the pool for good, so a share can't be
+//! priced so high that the next deposits round down to no share. The reserves never go below the
+//! existential deposit, so the pool account can't be reaped.
+
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, EnsureOrigin, ExistenceRequirement, Get, OnUnbalanced, WithdrawReason},
+	transactional,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational,
+	traits::{AccountIdConversion, AtLeast32BitUnsigned, SaturatedConversion, Saturating, Zero},
+	ModuleId, Permill,
+};
+// --- darwinia ---
+pub use darwinia_rpc_runtime_api::dex::{DexAsset, PoolReserves, SwapQuote};
+
+pub type AccountId<T> = <T as frame_system::Trait>::AccountId;
+pub type RingBalance<T> = <<T as Trait>::RingCurrency as Currency<AccountId<T>>>::Balance;
+pub type RingNegativeImbalance<T> =
+	<<T as Trait>::RingCurrency as Currency<AccountId<T>>>::NegativeImbalance;
+pub type KtonNegativeImbalance<T> =
+	<<T as Trait>::KtonCurrency as Currency<AccountId<T>>>::NegativeImbalance;
+
+pub trait Trait: frame_system::Trait {
+	/// The DEX's module id, used for deriving the pool account.
+	type ModuleId: Get<ModuleId>;
+
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	type RingCurrency: Currency<Self::AccountId>;
+	type KtonCurrency: Currency<Self::AccountId, Balance = RingBalance<Self>>;
+
+	/// Handler for the RING swap fees.
+	type RingTreasury: OnUnbalanced<RingNegativeImbalance<Self>>;
+	/// Handler for the KTON swap fees.
+	type KtonTreasury: OnUnbalanced<KtonNegativeImbalance<Self>>;
+
+	/// The shares locked in the pool on the first deposit.
+	type MinimumLiquidity: Get<RingBalance<Self>>;
+
+	/// The maximum fee rate which can be set.
+	type MaxFeeRate: Get<Permill>;
+
+	/// The origin which may set the fee rate.
+	type SetOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = AccountId<T>,
+		Balance = RingBalance<T>,
+	{
+		/// Liquidity was added to the pool. [provider, ring, kton, shares minted]
+		LiquidityAdded(AccountId, Balance, Balance, Balance),
+		/// Liquidity was removed from the pool. [provider, ring, kton, shares burned]
+		LiquidityRemoved(AccountId, Balance, Balance, Balance),
+		/// An asset was swapped. [account, asset in, amount in, amount out, fee]
+		Swapped(AccountId, DexAsset, Balance, Balance, Balance),
+		/// The fee rate was set. [new rate]
+		FeeRateSet(Permill),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Amount - ZERO
+		AmountZero,
+		/// First Deposit - BELOW MINIMUM LIQUIDITY
+		BelowMinimumLiquidity,
+		/// Liquidity - INSUFFICIENT
+		InsufficientLiquidity,
+		/// Kton Deposit - ABOVE MAXIMUM
+		KtonDepositTooHigh,
+		/// Shares - INSUFFICIENT
+		InsufficientShares,
+		/// Amount Out - BELOW MINIMUM
+		SlippageExceeded,
+		/// Fee Rate - TOO HIGH
+		FeeRateTooHigh,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaDex {
+		/// The RING and the KTON in the pool.
+		pub Reserves get(fn reserves): (RingBalance<T>, RingBalance<T>);
+
+		/// The liquidity shares issued.
+		pub TotalShares get(fn total_shares): RingBalance<T>;
+
+		/// The liquidity shares of each provider.
+		pub Shares get(fn shares_of): map hasher(blake2_128_concat) T::AccountId => RingBalance<T>;
+
+		/// The part of each amount swapped in which is paid to the treasury.
+		pub FeeRate get(fn fee_rate): Permill = Permill::from_parts(3_000);
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		const MinimumLiquidity: RingBalance<T> = T::MinimumLiquidity::get();
+
+		const MaxFeeRate: Permill = T::MaxFeeRate::get();
+
+		fn deposit_event() = default;
+
+		/// Add liquidity to the pool.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// The KTON deposited follows the pool price. The first provider sets the price, and
+		/// deposits exactly `kton_max`. `MinimumLiquidity` of the first provider's shares are
+		/// locked in the pool.
+		///
+		/// - `ring`: The RING to deposit.
+		/// - `kton_max`: The maximum KTON to deposit.
+		#[weight = T::DbWeight::get().reads_writes(5, 5)]
+		#[transactional]
+		pub fn add_liquidity(
+			origin,
+			#[compact] ring: RingBalance<T>,
+			#[compact] kton_max: RingBalance<T>,
+		) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!ring.is_zero() && !kton_max.is_zero(), <Error<T>>::AmountZero);
+
+			let (ring_reserve, kton_reserve) = Self::reserves();
+			let total_shares = Self::total_shares();
+			let (kton, shares) = if total_shares.is_zero() {
+				ensure!(
+					ring > T::MinimumLiquidity::get(),
+					<Error<T>>::BelowMinimumLiquidity
+				);
+
+				(kton_max, ring)
+			} else {
+				(
+					mul_div(ring, kton_reserve, ring_reserve)
+						.ok_or(<Error<T>>::InsufficientLiquidity)?,
+					mul_div(ring, total_shares, ring_reserve)
+						.ok_or(<Error<T>>::InsufficientLiquidity)?,
+				)
+			};
+
+			ensure!(kton <= kton_max, <Error<T>>::KtonDepositTooHigh);
+			ensure!(!shares.is_zero(), <Error<T>>::AmountZero);
+
+			// The locked shares are counted in the total, but owned by no one.
+			let shares_minted = if total_shares.is_zero() {
+				shares - T::MinimumLiquidity::get()
+			} else {
+				shares
+			};
+
+			let pool = Self::account_id();
+
+			T::RingCurrency::transfer(&who, &pool, ring, ExistenceRequirement::KeepAlive)?;
+			T::KtonCurrency::transfer(&who, &pool, kton, ExistenceRequirement::KeepAlive)?;
+
+			<Reserves<T>>::put((ring_reserve + ring, kton_reserve + kton));
+			<TotalShares<T>>::put(total_shares + shares);
+			<Shares<T>>::mutate(&who, |shares_of| *shares_of += shares_minted);
+
+			Self::deposit_event(RawEvent::LiquidityAdded(who, ring, kton, shares_minted));
+		}
+
+		/// Burn liquidity shares for their part of the pool.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[weight = T::DbWeight::get().reads_writes(5, 5)]
+		#[transactional]
+		pub fn remove_liquidity(origin, #[compact] shares: RingBalance<T>) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!shares.is_zero(), <Error<T>>::AmountZero);
+			ensure!(Self::shares_of(&who) >= shares, <Error<T>>::InsufficientShares);
+
+			let (ring_reserve, kton_reserve) = Self::reserves();
+			let total_shares = Self::total_shares();
+			let ring = mul_div(shares, ring_reserve, total_shares)
+				.ok_or(<Error<T>>::InsufficientLiquidity)?;
+			let kton = mul_div(shares, kton_reserve, total_shares)
+				.ok_or(<Error<T>>::InsufficientLiquidity)?;
+
+			ensure!(
+				ring_reserve.saturating_sub(ring) >= T::RingCurrency::minimum_balance()
+					&& kton_reserve.saturating_sub(kton) >= T::KtonCurrency::minimum_balance(),
+				<Error<T>>::InsufficientLiquidity
+			);
+
+			let pool = Self::account_id();
+
+			T::RingCurrency::transfer(&pool, &who, ring, ExistenceRequirement::KeepAlive)?;
+			T::KtonCurrency::transfer(&pool, &who, kton, ExistenceRequirement::KeepAlive)?;
+
+			<Reserves<T>>::put((ring_reserve - ring, kton_reserve - kton));
+			<TotalShares<T>>::put(total_shares - shares);
+			<Shares<T>>::mutate_exists(&who, |maybe_shares| {
+				let left = maybe_shares.unwrap_or_default() - shares;
+
+				*maybe_shares = if left.is_zero() { None } else { Some(left) };
+			});
+
+			Self::deposit_event(RawEvent::LiquidityRemoved(who, ring, kton, shares));
+		}
+
+		/// Swap `amount_in` of `asset_in` for the other asset.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `min_amount_out`: Fail if less than this would be received.
+		#[weight = T::DbWeight::get().reads_writes(6, 5)]
+		#[transactional]
+		pub fn swap(
+			origin,
+			asset_in: DexAsset,
+			#[compact] amount_in: RingBalance<T>,
+			#[compact] min_amount_out: RingBalance<T>,
+		) {
+			let who = ensure_signed(origin)?;
+			let SwapQuote { amount_out, fee } =
+				Self::quote(asset_in, amount_in).ok_or(<Error<T>>::InsufficientLiquidity)?;
+
+			ensure!(!amount_out.is_zero(), <Error<T>>::AmountZero);
+			ensure!(amount_out >= min_amount_out, <Error<T>>::SlippageExceeded);
+
+			let pool = Self::account_id();
+			let amount_in_pool = amount_in - fee;
+			let (ring_reserve, kton_reserve) = Self::reserves();
+
+			match asset_in {
+				DexAsset::Ring => {
+					Self::pay_ring_fee(&who, fee)?;
+
+					T::RingCurrency::transfer(
+						&who,
+						&pool,
+						amount_in_pool,
+						ExistenceRequirement::KeepAlive,
+					)?;
+					T::KtonCurrency::transfer(
+						&pool,
+						&who,
+						amount_out,
+						ExistenceRequirement::KeepAlive,
+					)?;
+
+					<Reserves<T>>::put((ring_reserve + amount_in_pool, kton_reserve - amount_out));
+				}
+				DexAsset::Kton => {
+					Self::pay_kton_fee(&who, fee)?;
+
+					T::KtonCurrency::transfer(
+						&who,
+						&pool,
+						amount_in_pool,
+						ExistenceRequirement::KeepAlive,
+					)?;
+					T::RingCurrency::transfer(
+						&pool,
+						&who,
+						amount_out,
+						ExistenceRequirement::KeepAlive,
+					)?;
+
+					<Reserves<T>>::put((ring_reserve - amount_out, kton_reserve + amount_in_pool));
+				}
+			}
+
+			Self::deposit_event(RawEvent::Swapped(who, asset_in, amount_in, amount_out, fee));
+		}
+
+		/// Set the part of each amount swapped in which is paid to the treasury.
+		///
+		/// The dispatch origin for this call must be `SetOrigin`.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_fee_rate(origin, rate: Permill) {
+			T::SetOrigin::ensure_origin(origin)?;
+			ensure!(rate <= T::MaxFeeRate::get(), <Error<T>>::FeeRateTooHigh);
+
+			<FeeRate>::put(rate);
+
+			Self::deposit_event(RawEvent::FeeRateSet(rate));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account ID of the pool.
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	/// Quote swapping `amount_in` of `asset_in`, `None` if the pool can't serve it without its
+	/// reserve out going below the existential deposit.
+	pub fn quote(
+		asset_in: DexAsset,
+		amount_in: RingBalance<T>,
+	) -> Option<SwapQuote<RingBalance<T>>> {
+		let (ring_reserve, kton_reserve) = Self::reserves();
+		let (reserve_in, reserve_out, minimum_reserve_out) = match asset_in {
+			DexAsset::Ring => (
+				ring_reserve,
+				kton_reserve,
+				T::KtonCurrency::minimum_balance(),
+			),
+			DexAsset::Kton => (
+				kton_reserve,
+				ring_reserve,
+				T::RingCurrency::minimum_balance(),
+			),
+		};
+
+		if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+			return None;
+		}
+
+		let fee = Self::fee_rate() * amount_in;
+		let amount_in_pool = amount_in - fee;
+		let amount_out = mul_div(
+			reserve_out,
+			amount_in_pool,
+			reserve_in.saturating_add(amount_in_pool),
+		)?;
+
+		if reserve_out.saturating_sub(amount_out) < minimum_reserve_out {
+			return None;
+		}
+
+		Some(SwapQuote { amount_out, fee })
+	}
+
+	/// The reserves of the pool.
+	pub fn pool_reserves() -> PoolReserves<RingBalance<T>> {
+		let (ring, kton) = Self::reserves();
+
+		PoolReserves {
+			ring,
+			kton,
+			total_shares: Self::total_shares(),
+		}
+	}
+
+	fn pay_ring_fee(who: &T::AccountId, fee: RingBalance<T>) -> DispatchResult {
+		if !fee.is_zero() {
+			T::RingTreasury::on_unbalanced(T::RingCurrency::withdraw(
+				who,
+				fee,
+				WithdrawReason::Transfer.into(),
+				ExistenceRequirement::KeepAlive,
+			)?);
+		}
+
+		Ok(())
+	}
+
+	fn pay_kton_fee(who: &T::AccountId, fee: RingBalance<T>) -> DispatchResult {
+		if !fee.is_zero() {
+			T::KtonTreasury::on_unbalanced(T::KtonCurrency::withdraw(
+				who,
+				fee,
+				WithdrawReason::Transfer.into(),
+				ExistenceRequirement::KeepAlive,
+			)?);
+		}
+
+		Ok(())
+	}
+}
+
+/// `a * b / c` without overflowing, `None` if `c` is zero.
+fn mul_div<Balance: AtLeast32BitUnsigned>(a: Balance, b: Balance, c: Balance) -> Option<Balance> {
+	multiply_by_rational(a.saturated_into(), b.saturated_into(), c.saturated_into())
+		.ok()
+		.map(|x| x.saturated_into())
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type Dex = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 10;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const DexModuleId: ModuleId = ModuleId(*b"da/dexpl");
+		pub const MinimumLiquidity: Balance = 1_000;
+		pub const MaxFeeRate: Permill = Permill::from_percent(5);
+	}
+	impl Trait for Test {
+		type ModuleId = DexModuleId;
+		type Event = ();
+		type RingCurrency = Ring;
+		type KtonCurrency = Kton;
+		type RingTreasury = ();
+		type KtonTreasury = ();
+		type MinimumLiquidity = MinimumLiquidity;
+		type MaxFeeRate = MaxFeeRate;
+		type SetOrigin = EnsureRoot<u64>;
+	}
+
+	/// Accounts 1 and 2 have 1_000_000 RING and KTON, account 3 has 100_000_000 RING and KTON,
+	/// account 4 has 5_000 RING and 500 KTON.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(1, 1_000_000), (2, 1_000_000), (3, 100_000_000), (4, 5_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		darwinia_balances::GenesisConfig::<Test, KtonInstance> {
+			balances: vec![(1, 1_000_000), (2, 1_000_000), (3, 100_000_000), (4, 500)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	/// Account 1 opens the pool with 10_000 RING and 20_000 KTON.
+	fn open_pool() {
+		assert_ok!(Dex::add_liquidity(Origin::signed(1), 10_000, 20_000));
+	}
+
+	#[test]
+	fn first_deposit_should_lock_the_minimum_liquidity() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(1), 1_000, 20_000),
+				<Error<Test>>::BelowMinimumLiquidity
+			);
+
+			open_pool();
+
+			assert_eq!(Dex::reserves(), (10_000, 20_000));
+			assert_eq!(Dex::total_shares(), 10_000);
+			assert_eq!(Dex::shares_of(&1), 9_000);
+			assert_eq!(Ring::free_balance(&Dex::account_id()), 10_000);
+			assert_eq!(Kton::free_balance(&Dex::account_id()), 20_000);
+			assert_eq!(Ring::free_balance(&1), 990_000);
+			assert_eq!(Kton::free_balance(&1), 980_000);
+		});
+	}
+
+	#[test]
+	fn add_liquidity_should_follow_the_pool_price() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(1), 0, 20_000),
+				<Error<Test>>::AmountZero
+			);
+
+			open_pool();
+
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(2), 5_000, 9_999),
+				<Error<Test>>::KtonDepositTooHigh
+			);
+			assert_ok!(Dex::add_liquidity(Origin::signed(2), 5_000, 20_000));
+			assert_eq!(Dex::reserves(), (15_000, 30_000));
+			assert_eq!(Dex::total_shares(), 15_000);
+			assert_eq!(Dex::shares_of(&2), 5_000);
+			assert_eq!(Kton::free_balance(&2), 990_000);
+		});
+	}
+
+	#[test]
+	fn remove_liquidity_should_pay_out_pro_rata() {
+		new_test_ext().execute_with(|| {
+			open_pool();
+
+			assert_noop!(
+				Dex::remove_liquidity(Origin::signed(1), 9_001),
+				<Error<Test>>::InsufficientShares
+			);
+			assert_noop!(
+				Dex::remove_liquidity(Origin::signed(2), 1),
+				<Error<Test>>::InsufficientShares
+			);
+			assert_ok!(Dex::remove_liquidity(Origin::signed(1), 9_000));
+			assert_eq!(Dex::reserves(), (1_000, 2_000));
+			assert_eq!(Dex::total_shares(), 1_000);
+			assert_eq!(Dex::shares_of(&1), 0);
+			assert!(!<Shares<Test>>::contains_key(&1));
+			assert_eq!(Ring::free_balance(&1), 999_000);
+			assert_eq!(Kton::free_balance(&1), 998_000);
+		});
+	}
+
+	#[test]
+	fn locked_liquidity_should_keep_the_share_price() {
+		new_test_ext().execute_with(|| {
+			open_pool();
+			assert_ok!(Dex::remove_liquidity(Origin::signed(1), 9_000));
+
+			// The pool is only left with the locked shares, priced as at the opening.
+			assert_ok!(Dex::add_liquidity(Origin::signed(2), 1, 2));
+			assert_eq!(Dex::shares_of(&2), 1);
+		});
+	}
+
+	#[test]
+	fn swap_should_follow_the_constant_product() {
+		new_test_ext().execute_with(|| {
+			open_pool();
+
+			// 3 of the 1_000 RING in are paid as fee, 20_000 * 997 / 10_997 KTON out.
+			assert_eq!(
+				Dex::quote(DexAsset::Ring, 1_000),
+				Some(SwapQuote {
+					amount_out: 1_813,
+					fee: 3
+				})
+			);
+			assert_noop!(
+				Dex::swap(Origin::signed(2), DexAsset::Ring, 1_000, 1_814),
+				<Error<Test>>::SlippageExceeded
+			);
+			assert_ok!(Dex::swap(Origin::signed(2), DexAsset::Ring, 1_000, 1_813));
+			assert_eq!(Dex::reserves(), (10_997, 18_187));
+			assert_eq!(Ring::free_balance(&2), 999_000);
+			assert_eq!(Kton::free_balance(&2), 1_001_813);
+
+			assert_ok!(Dex::swap(Origin::signed(2), DexAsset::Kton, 1_813, 0));
+			assert_eq!(Dex::reserves().1, 18_187 + 1_813 - 5);
+			assert!(Dex::reserves().0 > 10_000);
+		});
+	}
+
+	#[test]
+	fn swap_should_keep_the_reserve_out_above_the_existential_deposit() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Dex::swap(Origin::signed(2), DexAsset::Ring, 1_000, 0),
+				<Error<Test>>::InsufficientLiquidity
+			);
+
+			open_pool();
+
+			// 20_000 * 49_850_000 / 49_860_000 KTON out would leave 5 KTON in the pool.
+			assert_eq!(Dex::quote(DexAsset::Ring, 50_000_000), None);
+			assert_noop!(
+				Dex::swap(Origin::signed(3), DexAsset::Ring, 50_000_000, 0),
+				<Error<Test>>::InsufficientLiquidity
+			);
+		});
+	}
+
+	#[test]
+	fn set_fee_rate_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Dex::set_fee_rate(Origin::signed(1), Permill::from_percent(1)),
+				BadOrigin
+			);
+			assert_noop!(
+				Dex::set_fee_rate(Origin::root(), Permill::from_percent(6)),
+				<Error<Test>>::FeeRateTooHigh
+			);
+			assert_ok!(Dex::set_fee_rate(Origin::root(), Permill::zero()));
+			assert_eq!(Dex::quote(DexAsset::Ring, 1_000).unwrap().fee, 0);
+		});
+	}
+
+	#[test]
+	fn add_liquidity_should_fail_without_changes() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(1), 10_000, 0),
+				<Error<Test>>::AmountZero
+			);
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(4), 10_000, 20),
+				darwinia_balances::Error::<Test, RingInstance>::InsufficientBalance
+			);
+			// The RING is deposited before the KTON is found missing.
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(4), 2_000, 20_000),
+				darwinia_balances::Error::<Test, KtonInstance>::InsufficientBalance
+			);
+
+			open_pool();
+
+			assert_noop!(
+				Dex::add_liquidity(Origin::signed(4), 400, 1_000),
+				darwinia_balances::Error::<Test, KtonInstance>::InsufficientBalance
+			);
+		});
+	}
+
+	#[test]
+	fn remove_liquidity_should_fail_without_changes() {
+		new_test_ext().execute_with(|| {
+			open_pool();
+
+			assert_noop!(
+				Dex::remove_liquidity(Origin::signed(1), 0),
+				<Error<Test>>::AmountZero
+			);
+
+			// 10_000 * 9_970_000 / 9_990_000 RING out, down to 21 RING in the pool.
+			assert_ok!(Dex::swap(Origin::signed(3), DexAsset::Kton, 10_000_000, 0));
+			assert_eq!(Dex::reserves().0, 21);
+			// Paying out 18 of them would leave the pool below the existential deposit.
+			assert_noop!(
+				Dex::remove_liquidity(Origin::signed(1), 9_000),
+				<Error<Test>>::InsufficientLiquidity
+			);
+			assert_ok!(Dex::remove_liquidity(Origin::signed(1), 5_000));
+		});
+	}
+
+	#[test]
+	fn swap_should_fail_without_changes() {
+		new_test_ext().execute_with(|| {
+			open_pool();
+
+			// 10_000 * 1 / 20_001 RING out.
+			assert_noop!(
+				Dex::swap(Origin::signed(2), DexAsset::Kton, 1, 0),
+				<Error<Test>>::AmountZero
+			);
+			// The fee is paid before the rest is found missing.
+			assert_noop!(
+				Dex::swap(Origin::signed(4), DexAsset::Ring, 6_000, 0),
+				darwinia_balances::Error::<Test, RingInstance>::InsufficientBalance
+			);
+			assert_noop!(
+				Dex::swap(Origin::signed(4), DexAsset::Kton, 1_000, 0),
+				darwinia_balances::Error::<Test, KtonInstance>::InsufficientBalance
+			);
+			assert_noop!(
+				Dex::swap(Origin::signed(2), DexAsset::Kton, 1_000, 1_000),
+				<Error<Test>>::SlippageExceeded
+			);
+		});
+	}
+}
@@ -0,0 +1,647 @@
+//! Charge the transaction fees in RING, or in KTON at a governable rate if the RING balance is too
+//! low to cover them.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	traits::{
+		Currency, EnsureOrigin, ExistenceRequirement, Get, Imbalance, OnUnbalanced, WithdrawReason,
+		WithdrawReasons,
+	},
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use sp_runtime::{
+	traits::{
+		CheckedSub, DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SaturatedConversion,
+		Saturating, SignedExtension, Zero,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
+	},
+	FixedPointNumber, FixedPointOperand, FixedU128,
+};
+
+pub type AccountId<T> = <T as frame_system::Trait>::AccountId;
+pub type BalanceOf<T> =
+	<<T as pallet_transaction_payment::Trait>::Currency as Currency<AccountId<T>>>::Balance;
+pub type RingNegativeImbalance<T> = <<T as pallet_transaction_payment::Trait>::Currency as Currency<
+	AccountId<T>,
+>>::NegativeImbalance;
+pub type KtonNegativeImbalance<T> =
+	<<T as Trait>::KtonCurrency as Currency<AccountId<T>>>::NegativeImbalance;
+
+pub trait Trait: pallet_transaction_payment::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	type KtonCurrency: Currency<Self::AccountId, Balance = BalanceOf<Self>>;
+
+	/// Handler for the unbalanced reduction when paying the transaction fees in KTON.
+	type OnKtonTransactionPayment: OnUnbalanced<KtonNegativeImbalance<Self>>;
+
+	/// The origin which may set the KTON rate.
+	type SetOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = AccountId<T>,
+		Balance = BalanceOf<T>,
+	{
+		/// The KTON rate was set, `None` disables paying the fees in KTON. [new rate]
+		KtonRateSet(Option<FixedU128>),
+		/// The transaction fee was paid in KTON. [account, kton amount]
+		KtonFeePaid(AccountId, Balance),
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaFeePayment {
+		/// The KTON charged for each RING of fee.
+		///
+		/// Paying the fees in KTON is disabled while this is `None`.
+		pub KtonRate get(fn kton_rate): Option<FixedU128>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		fn deposit_event() = default;
+
+		/// Set the KTON charged for each RING of fee, `None` disables paying the fees in KTON.
+		///
+		/// The dispatch origin for this call must be `SetOrigin`.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_kton_rate(origin, rate: Option<FixedU128>) {
+			T::SetOrigin::ensure_origin(origin)?;
+
+			if let Some(rate) = rate {
+				<KtonRate>::put(rate);
+			} else {
+				<KtonRate>::kill();
+			}
+
+			Self::deposit_event(RawEvent::KtonRateSet(rate));
+		}
+	}
+}
+
+impl<T: Trait> Module<T>
+where
+	BalanceOf<T>: FixedPointOperand,
+{
+	/// The KTON charged for a fee of `ring_fee`, `None` if paying the fees in KTON is disabled.
+	pub fn kton_fee_of(ring_fee: BalanceOf<T>) -> Option<BalanceOf<T>> {
+		Self::kton_rate().map(|rate| rate.saturating_mul_int(ring_fee))
+	}
+
+	/// The KTON `who` pays for a fee of `ring_fee`, `None` if it is paid in RING, i.e. if the RING
+	/// balance covers it along with `tip` or if paying the fees in KTON is disabled.
+	pub fn kton_fee_for(
+		who: &T::AccountId,
+		ring_fee: BalanceOf<T>,
+		tip: BalanceOf<T>,
+	) -> Option<BalanceOf<T>> {
+		let total_fee = ring_fee.saturating_add(tip);
+
+		if total_fee.is_zero()
+			|| can_withdraw::<_, <T as pallet_transaction_payment::Trait>::Currency>(
+				who,
+				total_fee,
+				withdraw_reasons(tip),
+			) {
+			return None;
+		}
+
+		Self::kton_fee_of(ring_fee)
+	}
+}
+
+/// The currency a transaction fee was withdrawn in.
+pub enum Payment<T: Trait> {
+	Ring(RingNegativeImbalance<T>),
+	/// Paid in KTON, along with the rate at the time of the withdrawal.
+	Kton(KtonNegativeImbalance<T>, FixedU128),
+}
+
+/// Require the transactor pay for themselves and maybe include a tip to gain additional priority
+/// in the queue.
+///
+/// The fee is withdrawn in RING, falling back to KTON at `KtonRate` if the RING balance is too
+/// low. It is encoded as `pallet_transaction_payment::ChargeTransactionPayment` and shares its
+/// identifier, so the existing wallets keep working.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeTransactionPayment<T: Trait + Send + Sync>(#[codec(compact)] BalanceOf<T>);
+impl<T: Trait + Send + Sync> ChargeTransactionPayment<T>
+where
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+{
+	/// Utility constructor. Used only in client/factory code.
+	pub fn from(fee: BalanceOf<T>) -> Self {
+		Self(fee)
+	}
+
+	/// The tip of the transaction.
+	pub fn tip(&self) -> BalanceOf<T> {
+		self.0
+	}
+
+	fn withdraw_fee(
+		&self,
+		who: &T::AccountId,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, Option<Payment<T>>), TransactionValidityError> {
+		let tip = self.0;
+		let fee = <pallet_transaction_payment::Module<T>>::compute_fee(len as u32, info, tip);
+
+		if fee.is_zero() {
+			return Ok((fee, None));
+		}
+
+		let reasons = withdraw_reasons(tip);
+
+		if let Ok(imbalance) = <T as pallet_transaction_payment::Trait>::Currency::withdraw(
+			who,
+			fee,
+			reasons,
+			ExistenceRequirement::KeepAlive,
+		) {
+			return Ok((fee, Some(Payment::Ring(imbalance))));
+		}
+
+		let rate = <Module<T>>::kton_rate().ok_or(InvalidTransaction::Payment)?;
+
+		T::KtonCurrency::withdraw(
+			who,
+			rate.saturating_mul_int(fee),
+			reasons,
+			ExistenceRequirement::KeepAlive,
+		)
+		.map(|imbalance| (fee, Some(Payment::Kton(imbalance, rate))))
+		.map_err(|_| InvalidTransaction::Payment.into())
+	}
+
+	/// Get an appropriate priority for a transaction with the given length and info.
+	///
+	/// The priority is always computed from the RING fee, whichever currency pays it.
+	pub fn get_priority(
+		len: usize,
+		info: &DispatchInfoOf<T::Call>,
+		final_fee: BalanceOf<T>,
+	) -> TransactionPriority {
+		let weight_saturation = T::MaximumBlockWeight::get() / info.weight.max(1);
+		let len_saturation = T::MaximumBlockLength::get() as u64 / (len as u64).max(1);
+		let coefficient: BalanceOf<T> = weight_saturation
+			.min(len_saturation)
+			.saturated_into::<BalanceOf<T>>();
+
+		final_fee
+			.saturating_mul(coefficient)
+			.saturated_into::<TransactionPriority>()
+	}
+}
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for ChargeTransactionPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeTransactionPayment<{:?}>", self.0)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+impl<T: Trait + Send + Sync> SignedExtension for ChargeTransactionPayment<T>
+where
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+{
+	const IDENTIFIER: &'static str = "ChargeTransactionPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = (
+		BalanceOf<T>,
+		Self::AccountId,
+		Option<Payment<T>>,
+		BalanceOf<T>,
+	);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let (fee, _) = self.withdraw_fee(who, info, len)?;
+
+		Ok(ValidTransaction {
+			priority: Self::get_priority(len, info, fee),
+			..Default::default()
+		})
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let (fee, payment) = self.withdraw_fee(who, info, len)?;
+
+		Ok((self.0, who.clone(), payment, fee))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (tip, who, payment, fee) = pre;
+		let actual_fee = <pallet_transaction_payment::Module<T>>::compute_actual_fee(
+			len as u32, info, post_info, tip,
+		);
+		let refund = fee.saturating_sub(actual_fee);
+
+		match payment {
+			Some(Payment::Ring(paid)) => {
+				let actual_payment = settle::<
+					T::AccountId,
+					<T as pallet_transaction_payment::Trait>::Currency,
+				>(&who, paid, refund)?;
+				let (tip, fee) = actual_payment.split(tip);
+
+				<T as pallet_transaction_payment::Trait>::OnTransactionPayment::on_unbalanceds(
+					Some(fee).into_iter().chain(Some(tip)),
+				);
+			}
+			Some(Payment::Kton(paid, rate)) => {
+				let actual_payment = settle::<T::AccountId, T::KtonCurrency>(
+					&who,
+					paid,
+					rate.saturating_mul_int(refund),
+				)?;
+
+				<Module<T>>::deposit_event(RawEvent::KtonFeePaid(who, actual_payment.peek()));
+
+				let (tip, fee) = actual_payment.split(rate.saturating_mul_int(tip));
+
+				T::OnKtonTransactionPayment::on_unbalanceds(Some(fee).into_iter().chain(Some(tip)));
+			}
+			None => (),
+		}
+
+		Ok(())
+	}
+}
+
+/// The reasons a transaction fee is withdrawn for, the tip included if any.
+fn withdraw_reasons<Balance: Zero>(tip: Balance) -> WithdrawReasons {
+	if tip.is_zero() {
+		WithdrawReason::TransactionPayment.into()
+	} else {
+		WithdrawReason::TransactionPayment | WithdrawReason::Tip
+	}
+}
+
+/// Whether `fee` can be withdrawn from `who` for `reasons`, keeping the account alive.
+fn can_withdraw<AccountId, C: Currency<AccountId>>(
+	who: &AccountId,
+	fee: C::Balance,
+	reasons: WithdrawReasons,
+) -> bool {
+	C::free_balance(who)
+		.checked_sub(&fee)
+		.map_or(false, |new_balance| {
+			new_balance >= C::minimum_balance()
+				&& C::ensure_can_withdraw(who, fee, reasons, new_balance).is_ok()
+		})
+}
+
+/// Give `refund` back to `who` and return what is left of `paid`.
+fn settle<AccountId, C: Currency<AccountId>>(
+	who: &AccountId,
+	paid: C::NegativeImbalance,
+	refund: C::Balance,
+) -> Result<C::NegativeImbalance, TransactionValidityError> {
+	match C::deposit_into_existing(who, refund) {
+		Ok(refund_imbalance) => paid
+			.offset(refund_imbalance)
+			.map_err(|_| InvalidTransaction::Payment.into()),
+		Err(_) => Ok(paid),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_dispatch, impl_outer_event, impl_outer_origin,
+		parameter_types,
+		weights::{IdentityFee, Weight},
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{fee_payment, AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			frame_system::System,
+		}
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			frame_system<T>,
+			fee_payment<T>,
+		}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type FeePayment = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = TestEvent;
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 10;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const TransactionByteFee: Balance = 1;
+	}
+	impl pallet_transaction_payment::Trait for Test {
+		type Currency = Ring;
+		type OnTransactionPayment = ();
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = IdentityFee<Balance>;
+		type FeeMultiplierUpdate = ();
+	}
+
+	impl Trait for Test {
+		type Event = TestEvent;
+		type KtonCurrency = Kton;
+		type OnKtonTransactionPayment = ();
+		type SetOrigin = EnsureRoot<u64>;
+	}
+
+	const LEN: usize = 10;
+
+	/// Account 1 has 1_000_000 RING and KTON, account 2 has 100 RING and 1_000_000 KTON.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(1, 1_000_000), (2, 100)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		darwinia_balances::GenesisConfig::<Test, KtonInstance> {
+			balances: vec![(1, 1_000_000), (2, 1_000_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	/// The fee of a call is its weight plus its length, plus the tip.
+	fn call() -> Call {
+		Call::System(frame_system::Call::remark(vec![]))
+	}
+
+	fn info(weight: Weight) -> DispatchInfo {
+		DispatchInfo {
+			weight,
+			..Default::default()
+		}
+	}
+
+	fn half() -> FixedU128 {
+		FixedU128::saturating_from_rational(1, 2)
+	}
+
+	fn fee_payment_events() -> Vec<RawEvent<u64, Balance>> {
+		System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::fee_payment(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn kton_rate_should_convert_the_ring_fee() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(FeePayment::kton_fee_of(110), None);
+			assert_noop!(
+				FeePayment::set_kton_rate(Origin::signed(1), Some(half())),
+				BadOrigin
+			);
+
+			assert_ok!(FeePayment::set_kton_rate(Origin::root(), Some(half())));
+			assert_eq!(FeePayment::kton_fee_of(110), Some(55));
+			assert_eq!(FeePayment::kton_fee_of(111), Some(55));
+
+			assert_ok!(FeePayment::set_kton_rate(
+				Origin::root(),
+				Some(FixedU128::saturating_from_integer(3))
+			));
+			assert_eq!(FeePayment::kton_fee_of(110), Some(330));
+
+			assert_ok!(FeePayment::set_kton_rate(Origin::root(), None));
+			assert_eq!(FeePayment::kton_fee_of(110), None);
+			assert_eq!(
+				fee_payment_events(),
+				vec![
+					RawEvent::KtonRateSet(Some(half())),
+					RawEvent::KtonRateSet(Some(FixedU128::saturating_from_integer(3))),
+					RawEvent::KtonRateSet(None),
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn fees_should_be_paid_in_ring_while_the_ring_balance_covers_them() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(FeePayment::set_kton_rate(Origin::root(), Some(half())));
+			assert_eq!(FeePayment::kton_fee_for(&1, 110, 0), None);
+			// 100 RING pay a fee of 80 keeping the existential deposit, but not with a tip of 20.
+			assert_eq!(FeePayment::kton_fee_for(&2, 80, 0), None);
+			assert_eq!(FeePayment::kton_fee_for(&2, 80, 20), Some(40));
+
+			ChargeTransactionPayment::<Test>::from(0)
+				.pre_dispatch(&1, &call(), &info(100), LEN)
+				.unwrap();
+			assert_eq!(Ring::free_balance(&1), 1_000_000 - 110);
+			assert_eq!(Kton::free_balance(&1), 1_000_000);
+		});
+	}
+
+	#[test]
+	fn fees_should_fall_back_to_kton_at_the_rate() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				ChargeTransactionPayment::<Test>::from(0)
+					.pre_dispatch(&2, &call(), &info(100), LEN)
+					.err(),
+				Some(InvalidTransaction::Payment.into())
+			);
+			assert_eq!(Kton::free_balance(&2), 1_000_000);
+			assert_eq!(FeePayment::kton_fee_for(&2, 110, 0), None);
+
+			assert_ok!(FeePayment::set_kton_rate(Origin::root(), Some(half())));
+			assert_eq!(FeePayment::kton_fee_for(&2, 110, 0), Some(55));
+			ChargeTransactionPayment::<Test>::from(0)
+				.pre_dispatch(&2, &call(), &info(100), LEN)
+				.unwrap();
+			assert_eq!(Ring::free_balance(&2), 100);
+			assert_eq!(Kton::free_balance(&2), 1_000_000 - 55);
+		});
+	}
+
+	#[test]
+	fn kton_fees_should_be_refunded_at_the_rate_of_the_withdrawal() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(FeePayment::set_kton_rate(Origin::root(), Some(half())));
+
+			let pre = ChargeTransactionPayment::<Test>::from(10)
+				.pre_dispatch(&2, &call(), &info(100), LEN)
+				.unwrap();
+
+			// 120 RING of fee, tip included.
+			assert_eq!(Kton::free_balance(&2), 1_000_000 - 60);
+
+			// The rate changing before the refund doesn't change the refunded KTON.
+			assert_ok!(FeePayment::set_kton_rate(
+				Origin::root(),
+				Some(FixedU128::saturating_from_integer(2))
+			));
+			assert_ok!(ChargeTransactionPayment::<Test>::post_dispatch(
+				pre,
+				&info(100),
+				&PostDispatchInfo::from(Some(50)),
+				LEN,
+				&Ok(())
+			));
+			// 70 RING of actual fee, 50 RING refunded as 25 KTON.
+			assert_eq!(Kton::free_balance(&2), 1_000_000 - 35);
+			assert_eq!(Ring::free_balance(&2), 100);
+			assert_eq!(
+				fee_payment_events().last(),
+				Some(&RawEvent::KtonFeePaid(2, 35))
+			);
+		});
+	}
+
+	#[test]
+	fn ring_fees_should_be_refunded() {
+		new_test_ext().execute_with(|| {
+			let pre = ChargeTransactionPayment::<Test>::from(10)
+				.pre_dispatch(&1, &call(), &info(100), LEN)
+				.unwrap();
+
+			assert_eq!(Ring::free_balance(&1), 1_000_000 - 120);
+			assert_ok!(ChargeTransactionPayment::<Test>::post_dispatch(
+				pre,
+				&info(100),
+				&PostDispatchInfo::from(Some(50)),
+				LEN,
+				&Ok(())
+			));
+			assert_eq!(Ring::free_balance(&1), 1_000_000 - 70);
+			assert_eq!(Kton::free_balance(&1), 1_000_000);
+		});
+	}
+}
@@ -0,0 +1,532 @@
+//! Governable split of transaction fees and tips between the treasury, the block author and burn.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{Currency, EnsureOrigin, Get, Imbalance, OnUnbalanced},
+};
+use sp_runtime::{traits::Zero, Percent, RuntimeDebug};
+
+pub type RingBalance<T> =
+	<<T as Trait>::RingCurrency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type RingNegativeImbalance<T> = <<T as Trait>::RingCurrency as Currency<
+	<T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+pub type KtonBalance<T> =
+	<<T as Trait>::KtonCurrency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type KtonNegativeImbalance<T> = <<T as Trait>::KtonCurrency as Currency<
+	<T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	type RingCurrency: Currency<Self::AccountId>;
+
+	/// Handler for the treasury share of the RING fees and tips.
+	type RingTreasury: OnUnbalanced<RingNegativeImbalance<Self>>;
+	/// Handler for the block author share of the RING fees and tips.
+	type RingAuthor: OnUnbalanced<RingNegativeImbalance<Self>>;
+
+	type KtonCurrency: Currency<Self::AccountId>;
+
+	/// Handler for the treasury share of the KTON fees and tips.
+	type KtonTreasury: OnUnbalanced<KtonNegativeImbalance<Self>>;
+	/// Handler for the block author share of the KTON fees and tips.
+	type KtonAuthor: OnUnbalanced<KtonNegativeImbalance<Self>>;
+
+	/// The origin which may change the split ratios.
+	type SetOrigin: EnsureOrigin<Self::Origin>;
+}
+
+/// How an imbalance is shared out. The three parts must add up to exactly 100%.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct SplitRatio {
+	/// Part which goes to the treasury.
+	pub treasury: Percent,
+	/// Part which goes to the block author.
+	pub author: Percent,
+	/// Part which is burned.
+	pub burn: Percent,
+}
+impl SplitRatio {
+	/// Check that the parts add up to 100%.
+	pub fn is_valid(&self) -> bool {
+		self.treasury.deconstruct() as u16
+			+ self.author.deconstruct() as u16
+			+ self.burn.deconstruct() as u16
+			== 100
+	}
+
+	/// Split `imbalance` into `(treasury, author, burn)`.
+	///
+	/// Rounding dust is kept by the treasury part.
+	pub fn split<B, I>(&self, imbalance: I) -> (I, I, I)
+	where
+		B: sp_runtime::traits::AtLeast32BitUnsigned + Copy,
+		I: Imbalance<B>,
+	{
+		let total = imbalance.peek();
+		let (burn, rest) = imbalance.split(self.burn * total);
+		let (author, treasury) = rest.split(self.author * total);
+
+		(treasury, author, burn)
+	}
+}
+impl Default for SplitRatio {
+	fn default() -> Self {
+		Self {
+			treasury: Percent::from_percent(80),
+			author: Percent::from_percent(20),
+			burn: Percent::from_percent(0),
+		}
+	}
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		RingBalance = RingBalance<T>,
+		KtonBalance = KtonBalance<T>,
+	{
+		/// RING fee split. [to treasury, to author, burned]
+		RingFeeSplit(RingBalance, RingBalance, RingBalance),
+		/// RING tip split. [to treasury, to author, burned]
+		RingTipSplit(RingBalance, RingBalance, RingBalance),
+		/// KTON fee split. [to treasury, to author, burned]
+		KtonFeeSplit(KtonBalance, KtonBalance, KtonBalance),
+		/// KTON tip split. [to treasury, to author, burned]
+		KtonTipSplit(KtonBalance, KtonBalance, KtonBalance),
+		/// The fee split ratio was updated. [new ratio]
+		FeeRatioSet(SplitRatio),
+		/// The tip split ratio was updated. [new ratio]
+		TipRatioSet(SplitRatio),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Ratio - INVALID
+		RatioInvalid,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaFeeSplit {
+		/// The split applied to the transaction fees.
+		pub FeeRatio get(fn fee_ratio): SplitRatio = SplitRatio::default();
+		/// The split applied to the transaction tips.
+		pub TipRatio get(fn tip_ratio): SplitRatio = SplitRatio::default();
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Set the split applied to the transaction fees.
+		///
+		/// The dispatch origin for this call must be `SetOrigin`.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_fee_ratio(origin, ratio: SplitRatio) {
+			T::SetOrigin::ensure_origin(origin)?;
+			ensure!(ratio.is_valid(), <Error<T>>::RatioInvalid);
+
+			<FeeRatio>::put(ratio);
+
+			Self::deposit_event(RawEvent::FeeRatioSet(ratio));
+		}
+
+		/// Set the split applied to the transaction tips.
+		///
+		/// The dispatch origin for this call must be `SetOrigin`.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_tip_ratio(origin, ratio: SplitRatio) {
+			T::SetOrigin::ensure_origin(origin)?;
+			ensure!(ratio.is_valid(), <Error<T>>::RatioInvalid);
+
+			<TipRatio>::put(ratio);
+
+			Self::deposit_event(RawEvent::TipRatioSet(ratio));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn deal_with_ring(imbalance: RingNegativeImbalance<T>, ratio: SplitRatio, is_tip: bool) {
+		let (treasury, author, burn) = ratio.split(imbalance);
+		let (to_treasury, to_author, burned) = (treasury.peek(), author.peek(), burn.peek());
+
+		T::RingTreasury::on_unbalanced(treasury);
+		T::RingAuthor::on_unbalanced(author);
+		// dropping the negative imbalance reduces the total issuance
+		drop(burn);
+
+		if is_tip {
+			Self::deposit_event(RawEvent::RingTipSplit(to_treasury, to_author, burned));
+		} else {
+			Self::deposit_event(RawEvent::RingFeeSplit(to_treasury, to_author, burned));
+		}
+	}
+
+	fn deal_with_kton(imbalance: KtonNegativeImbalance<T>, ratio: SplitRatio, is_tip: bool) {
+		let (treasury, author, burn) = ratio.split(imbalance);
+		let (to_treasury, to_author, burned) = (treasury.peek(), author.peek(), burn.peek());
+
+		T::KtonTreasury::on_unbalanced(treasury);
+		T::KtonAuthor::on_unbalanced(author);
+		// dropping the negative imbalance reduces the total issuance
+		drop(burn);
+
+		if is_tip {
+			Self::deposit_event(RawEvent::KtonTipSplit(to_treasury, to_author, burned));
+		} else {
+			Self::deposit_event(RawEvent::KtonFeeSplit(to_treasury, to_author, burned));
+		}
+	}
+}
+
+impl<T: Trait> OnUnbalanced<RingNegativeImbalance<T>> for Module<T> {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = RingNegativeImbalance<T>>) {
+		if let Some(fees) = fees_then_tips.next() {
+			Self::deal_with_ring(fees, Self::fee_ratio(), false);
+
+			if let Some(tips) = fees_then_tips.next() {
+				if !tips.peek().is_zero() {
+					Self::deal_with_ring(tips, Self::tip_ratio(), true);
+				}
+			}
+		}
+	}
+}
+
+/// Split the KTON fees and tips with the same ratios as the RING ones.
+pub struct DealWithKtonFees<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> OnUnbalanced<KtonNegativeImbalance<T>> for DealWithKtonFees<T> {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = KtonNegativeImbalance<T>>) {
+		if let Some(fees) = fees_then_tips.next() {
+			<Module<T>>::deal_with_kton(fees, <Module<T>>::fee_ratio(), false);
+
+			if let Some(tips) = fees_then_tips.next() {
+				if !tips.peek().is_zero() {
+					<Module<T>>::deal_with_kton(tips, <Module<T>>::tip_ratio(), true);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+		traits::{ExistenceRequirement, WithdrawReason, WithdrawReasons},
+		weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{fee_split, AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			frame_system<T>,
+			fee_split<T>,
+		}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type FeeSplit = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = TestEvent;
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	/// Deposit the whole imbalance into account `A`.
+	pub struct ResolveTo<C, A>(sp_std::marker::PhantomData<(C, A)>);
+	impl<C, A> OnUnbalanced<C::NegativeImbalance> for ResolveTo<C, A>
+	where
+		C: Currency<u64>,
+		A: Get<u64>,
+	{
+		fn on_nonzero_unbalanced(amount: C::NegativeImbalance) {
+			C::resolve_creating(&A::get(), amount);
+		}
+	}
+
+	parameter_types! {
+		pub const Treasury: u64 = 100;
+		pub const Author: u64 = 200;
+	}
+	impl Trait for Test {
+		type Event = TestEvent;
+		type RingCurrency = Ring;
+		type RingTreasury = ResolveTo<Ring, Treasury>;
+		type RingAuthor = ResolveTo<Ring, Author>;
+		type KtonCurrency = Kton;
+		type KtonTreasury = ResolveTo<Kton, Treasury>;
+		type KtonAuthor = ResolveTo<Kton, Author>;
+		type SetOrigin = EnsureRoot<u64>;
+	}
+
+	/// Account 1 has 1_000_000 RING and KTON.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(1, 1_000_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		darwinia_balances::GenesisConfig::<Test, KtonInstance> {
+			balances: vec![(1, 1_000_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	fn ratio(treasury: u8, author: u8, burn: u8) -> SplitRatio {
+		SplitRatio {
+			treasury: Percent::from_percent(treasury),
+			author: Percent::from_percent(author),
+			burn: Percent::from_percent(burn),
+		}
+	}
+
+	fn withdraw_ring(amount: Balance) -> RingNegativeImbalance<Test> {
+		Ring::withdraw(
+			&1,
+			amount,
+			WithdrawReasons::from(WithdrawReason::TransactionPayment),
+			ExistenceRequirement::KeepAlive,
+		)
+		.unwrap()
+	}
+
+	fn withdraw_kton(amount: Balance) -> KtonNegativeImbalance<Test> {
+		Kton::withdraw(
+			&1,
+			amount,
+			WithdrawReasons::from(WithdrawReason::TransactionPayment),
+			ExistenceRequirement::KeepAlive,
+		)
+		.unwrap()
+	}
+
+	fn fee_split_events() -> Vec<RawEvent<Balance, Balance>> {
+		System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::fee_split(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn split_should_share_out_the_whole_imbalance() {
+		new_test_ext().execute_with(|| {
+			let split_ratio = ratio(33, 33, 34);
+
+			for &total in &[0, 1, 2, 100, 101, 102, 999, 1_000_001] {
+				let (treasury, author, burn) = split_ratio.split(withdraw_ring(total));
+
+				assert_eq!(treasury.peek() + author.peek() + burn.peek(), total);
+				assert_eq!(author.peek(), Percent::from_percent(33) * total);
+				assert_eq!(burn.peek(), Percent::from_percent(34) * total);
+				// The rounding dust goes to the treasury.
+				assert!(treasury.peek() >= Percent::from_percent(33) * total);
+			}
+		});
+	}
+
+	#[test]
+	fn ratios_not_summing_to_100_should_be_rejected() {
+		assert!(ratio(80, 20, 0).is_valid());
+		assert!(ratio(0, 0, 100).is_valid());
+		assert!(!ratio(80, 20, 1).is_valid());
+		assert!(!ratio(50, 20, 0).is_valid());
+		assert!(!ratio(100, 100, 100).is_valid());
+
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				FeeSplit::set_fee_ratio(Origin::signed(1), ratio(80, 20, 0)),
+				BadOrigin
+			);
+			assert_noop!(
+				FeeSplit::set_fee_ratio(Origin::root(), ratio(80, 19, 0)),
+				<Error<Test>>::RatioInvalid
+			);
+			assert_noop!(
+				FeeSplit::set_tip_ratio(Origin::root(), ratio(90, 20, 0)),
+				<Error<Test>>::RatioInvalid
+			);
+			assert_ok!(FeeSplit::set_tip_ratio(Origin::root(), ratio(0, 0, 100)));
+			assert_eq!(FeeSplit::tip_ratio(), ratio(0, 0, 100));
+			assert_eq!(FeeSplit::fee_ratio(), SplitRatio::default());
+		});
+	}
+
+	#[test]
+	fn ring_fees_and_tips_should_be_split_with_their_own_ratio() {
+		new_test_ext().execute_with(|| {
+			let total_issuance = Ring::total_issuance();
+
+			assert_ok!(FeeSplit::set_fee_ratio(Origin::root(), ratio(50, 30, 20)));
+
+			FeeSplit::on_unbalanceds(vec![withdraw_ring(1_000), withdraw_ring(100)].into_iter());
+
+			assert_eq!(Ring::free_balance(&100), 500 + 80);
+			assert_eq!(Ring::free_balance(&200), 300 + 20);
+			assert_eq!(Ring::free_balance(&1), 1_000_000 - 1_100);
+			assert_eq!(Ring::total_issuance(), total_issuance - 200);
+			assert_eq!(
+				fee_split_events(),
+				vec![
+					RawEvent::FeeRatioSet(ratio(50, 30, 20)),
+					RawEvent::RingFeeSplit(500, 300, 200),
+					RawEvent::RingTipSplit(80, 20, 0),
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn zero_tips_should_not_be_reported() {
+		new_test_ext().execute_with(|| {
+			FeeSplit::on_unbalanceds(
+				vec![withdraw_ring(1_000), RingNegativeImbalance::<Test>::zero()].into_iter(),
+			);
+
+			assert_eq!(
+				fee_split_events(),
+				vec![RawEvent::RingFeeSplit(800, 200, 0)]
+			);
+		});
+	}
+
+	#[test]
+	fn kton_fees_and_tips_should_be_split_with_the_ring_ratios() {
+		new_test_ext().execute_with(|| {
+			let total_issuance = Kton::total_issuance();
+
+			assert_ok!(FeeSplit::set_tip_ratio(Origin::root(), ratio(0, 50, 50)));
+
+			<DealWithKtonFees<Test>>::on_unbalanceds(
+				vec![withdraw_kton(1_001), withdraw_kton(11)].into_iter(),
+			);
+
+			let events = fee_split_events();
+			let (fee_to_treasury, fee_to_author, fee_burned) = match events[1] {
+				RawEvent::KtonFeeSplit(to_treasury, to_author, burned) => {
+					(to_treasury, to_author, burned)
+				}
+				ref event => panic!("Unexpected event {:?}", event),
+			};
+			let (tip_to_treasury, tip_to_author, tip_burned) = match events[2] {
+				RawEvent::KtonTipSplit(to_treasury, to_author, burned) => {
+					(to_treasury, to_author, burned)
+				}
+				ref event => panic!("Unexpected event {:?}", event),
+			};
+
+			assert_eq!(events.len(), 3);
+			assert_eq!(fee_to_treasury + fee_to_author + fee_burned, 1_001);
+			assert_eq!(
+				fee_to_author,
+				Percent::from_percent(20) * (1_001 as Balance)
+			);
+			assert_eq!(fee_burned, 0);
+			assert_eq!(tip_to_treasury + tip_to_author + tip_burned, 11);
+			assert_eq!(tip_burned, Percent::from_percent(50) * (11 as Balance));
+			assert_eq!(Kton::free_balance(&100), fee_to_treasury + tip_to_treasury);
+			assert_eq!(Kton::free_balance(&200), fee_to_author + tip_to_author);
+			assert_eq!(Kton::total_issuance(), total_issuance - tip_burned);
+		});
+	}
+}
@@ -0,0 +1,1234 @@
+//! Report BABE and GRANDPA equivocations and relayer game fraud, signed with the fisherman key.
+//!
+//! The client already detects equivocations, BABE while importing blocks and GRANDPA while
+//! voting, and hands the proofs to the runtime through the
+//! `submit_report_equivocation_unsigned_extrinsic` runtime APIs. Unsigned reports carry no
+//! reporter, so nobody is rewarded for them. If the keystore holds a `fish` key, the report is
+//! signed with it instead and the fisherman account becomes the reporter of the offence, which the
+//! staking module rewards with `slash_reward_fraction` of the slash. Without a fisherman key, or
+//! if signing fails, the unsigned report is submitted as before.
+//!
+//! A relayed header is fraud if the target chain holds another block at its number. The report
+//! proves it on chain with the target chain headers from that number up to a header confirmed by
+//! the relay, each the parent of the next. The reporter is paid `SlashRewardFraction` of the
+//! bond of the header, slashed from its relayer. The reports are kept until the game settles, so
+//! each header is only paid for once.
+//!
+//! The runtime doesn't have the target chain headers. With an Ethereum RPC endpoint set by the
+//! node, the offchain worker builds the proofs of the relayed headers the endpoint disagrees with
+//! and reports them, signed with the fisherman key. The headers which can't be proven yet, with no
+//! confirmed header close enough above them, are skipped until they can.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	debug, decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{Currency, Get, Imbalance},
+	weights::Weight,
+	Parameter,
+};
+use frame_system::{
+	ensure_signed,
+	offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+};
+use pallet_grandpa::fg_primitives;
+use sp_core::{offchain::StorageKind, H256};
+use sp_runtime::{
+	offchain::{http, Duration},
+	traits::Header,
+	Perbill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+/// The offchain persistent storage key of the Ethereum RPC endpoint set by the node.
+pub const ETHEREUM_RPC_KEY: &[u8] = b"darwinia/fisherman/ethereum-rpc";
+/// The offchain persistent storage key of the relay frauds already reported by the node.
+const REPORTED_KEY: &[u8] = b"darwinia/fisherman/reported";
+/// The time the Ethereum RPC endpoint has to answer.
+const ETHEREUM_RPC_TIMEOUT: Duration = Duration::from_millis(5_000);
+/// The most Ethereum headers in a fraud proof.
+pub const MAX_ETHEREUM_HEADER_CHAIN_LEN: usize = 256;
+/// The fields of an Ethereum header in an `eth_getBlockByNumber` response, in their RLP order, and
+/// whether each is a quantity. The fields past `nonce` only exist since the forks adding them.
+const ETHEREUM_HEADER_FIELDS: &[(&str, bool)] = &[
+	("parentHash", false),
+	("sha3Uncles", false),
+	("miner", false),
+	("stateRoot", false),
+	("transactionsRoot", false),
+	("receiptsRoot", false),
+	("logsBloom", false),
+	("difficulty", true),
+	("number", true),
+	("gasLimit", true),
+	("gasUsed", true),
+	("timestamp", true),
+	("extraData", false),
+	("mixHash", false),
+	("nonce", false),
+	("baseFeePerGas", true),
+	("withdrawalsRoot", false),
+	("blobGasUsed", true),
+	("excessBlobGas", true),
+	("parentBeaconBlockRoot", false),
+	("requestsHash", false),
+];
+/// The fields every Ethereum header has.
+const ETHEREUM_HEADER_LEGACY_FIELDS_COUNT: usize = 15;
+
+pub type RingBalance<T> =
+	<<T as Trait>::RingCurrency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type RelayedHeaderOf<T> = RelayedHeader<<T as frame_system::Trait>::AccountId, RingBalance<T>>;
+pub type FraudProofOf<T> = <<T as Trait>::RelayedHeaders as RelayedHeaders<
+	<T as frame_system::Trait>::AccountId,
+	RingBalance<T>,
+>>::FraudProof;
+pub type BabeEquivocationProof<T> =
+	sp_consensus_babe::EquivocationProof<<T as frame_system::Trait>::Header>;
+pub type GrandpaEquivocationProof<T> = fg_primitives::EquivocationProof<
+	<T as frame_system::Trait>::Hash,
+	<<T as frame_system::Trait>::Header as Header>::Number,
+>;
+/// Ethereum headers, RLP encoded, each the parent of the next.
+pub type EthereumHeaderChain = Vec<Vec<u8>>;
+
+/// An Ethereum header proposed in a relayer game.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct RelayedHeader<AccountId, Balance> {
+	/// The Ethereum block the game is about, which the relay rejects.
+	pub game_id: u64,
+	/// The proposal of the game holding the header.
+	pub proposal_index: u32,
+	/// The relayer proposing the header.
+	pub relayer: AccountId,
+	/// The bond of the header.
+	pub bond: Balance,
+	pub number: u64,
+	pub hash: H256,
+}
+
+/// The Ethereum headers proposed in the ongoing relayer games, and the proofs of their fraud.
+pub trait RelayedHeaders<AccountId, Balance> {
+	/// The proof that the target chain holds another block than a relayed header.
+	type FraudProof: Parameter;
+
+	/// The headers of all the ongoing games.
+	fn relayed_headers() -> Vec<RelayedHeader<AccountId, Balance>>;
+
+	/// The headers of the proposal `proposal_index` of the ongoing game `game_id`.
+	fn relayed_headers_of(
+		game_id: u64,
+		proposal_index: u32,
+	) -> Vec<RelayedHeader<AccountId, Balance>>;
+
+	/// Whether the game `game_id` is still being played.
+	fn is_ongoing(game_id: u64) -> bool;
+
+	/// Whether `fraud_proof` proves `relayed_header` off the target chain.
+	fn verify_fraud_proof(
+		relayed_header: &RelayedHeader<AccountId, Balance>,
+		fraud_proof: &Self::FraudProof,
+	) -> bool;
+
+	/// The weight of verifying `fraud_proof`.
+	fn verify_fraud_proof_weight(fraud_proof: &Self::FraudProof) -> Weight;
+
+	/// Build the fraud proof of `relayed_header` from the Ethereum RPC `endpoint`, offchain.
+	/// `None` if the endpoint holds the header, or if it can't be proven yet.
+	fn fraud_proof_of(
+		endpoint: &str,
+		relayed_header: &RelayedHeader<AccountId, Balance>,
+	) -> Result<Option<Self::FraudProof>, &'static str>;
+}
+
+pub trait Trait: CreateSignedTransaction<Call<Self>> {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The fisherman key the reports are signed with.
+	type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+	/// The currency the relayers bond.
+	type RingCurrency: Currency<Self::AccountId>;
+
+	/// The part of the bond of a fraudulent header paid to its reporter.
+	type SlashRewardFraction: Get<Perbill>;
+
+	/// The Ethereum headers checked by the offchain worker, and the proofs of their fraud.
+	type RelayedHeaders: RelayedHeaders<Self::AccountId, RingBalance<Self>>;
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = RingBalance<T>,
+	{
+		/// A relayed Ethereum header was proven fraud. [reporter, relayer, game id, number, reward]
+		RelayFraudReported(AccountId, AccountId, u64, u64, Balance),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Relayed Header - NOT EXISTED
+		RelayedHeaderNE,
+		/// Relay Fraud - ALREADY REPORTED
+		RelayFraudAR,
+		/// Fraud Proof - INVALID
+		FraudProofInv,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaFisherman {
+		/// The reporter of each fraud, by game and by the relayer and the number of the header.
+		pub RelayFraudReports
+			get(fn relay_fraud_report)
+			: double_map hasher(twox_64_concat) u64, hasher(blake2_128_concat) (T::AccountId, u64)
+			=> Option<T::AccountId>;
+
+		/// The games with fraud reports, whose reports are dropped once they settle.
+		pub ReportedGames get(fn reported_games): Vec<u64>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		fn on_initialize(_: T::BlockNumber) -> Weight {
+			Self::prune_settled_games()
+		}
+
+		/// Report a relayed Ethereum header which is not on the Ethereum chain.
+		///
+		/// The dispatch origin for this call must be _Signed_, and is paid `SlashRewardFraction`
+		/// of the bond of the header, slashed from its relayer.
+		///
+		/// - `game_id`, `proposal_index`, `number`: The header `number` of the proposal
+		///   `proposal_index` of the ongoing game `game_id`, not reported yet.
+		/// - `fraud_proof`: The proof that the Ethereum chain holds another block at `number`.
+		#[weight = T::RelayedHeaders::verify_fraud_proof_weight(fraud_proof)
+			+ T::DbWeight::get().reads_writes(5, 4)]
+		pub fn report_relay_fraud(
+			origin,
+			game_id: u64,
+			proposal_index: u32,
+			number: u64,
+			fraud_proof: FraudProofOf<T>,
+		) {
+			let reporter = ensure_signed(origin)?;
+			let relayed_header = T::RelayedHeaders::relayed_headers_of(game_id, proposal_index)
+				.into_iter()
+				.find(|relayed_header| relayed_header.number == number)
+				.ok_or(<Error<T>>::RelayedHeaderNE)?;
+			let report_key = (relayed_header.relayer.clone(), number);
+
+			ensure!(
+				!<RelayFraudReports<T>>::contains_key(game_id, &report_key),
+				<Error<T>>::RelayFraudAR
+			);
+			ensure!(
+				T::RelayedHeaders::verify_fraud_proof(&relayed_header, &fraud_proof),
+				<Error<T>>::FraudProofInv
+			);
+
+			let (imbalance, _) = T::RingCurrency::slash(
+				&relayed_header.relayer,
+				T::SlashRewardFraction::get() * relayed_header.bond,
+			);
+			let reward = imbalance.peek();
+
+			T::RingCurrency::resolve_creating(&reporter, imbalance);
+
+			<RelayFraudReports<T>>::insert(game_id, &report_key, &reporter);
+			<ReportedGames>::mutate(|reported_games| {
+				if !reported_games.contains(&game_id) {
+					reported_games.push(game_id);
+				}
+			});
+
+			Self::deposit_event(RawEvent::RelayFraudReported(
+				reporter,
+				relayed_header.relayer,
+				game_id,
+				number,
+				reward,
+			));
+		}
+
+		fn offchain_worker(_: T::BlockNumber) {
+			if let Err(e) = Self::check_relayed_headers() {
+				debug::warn!(target: "fisherman", "Failed to check the relayed headers: {}", e);
+			}
+		}
+	}
+}
+
+impl<T> Module<T>
+where
+	T: Trait + pallet_babe::Trait + CreateSignedTransaction<pallet_babe::Call<T>>,
+{
+	/// Report a BABE equivocation, signed with the fisherman key if there is one.
+	pub fn submit_babe_equivocation_report(
+		equivocation_proof: BabeEquivocationProof<T>,
+		key_owner_proof: <T as pallet_babe::Trait>::KeyOwnerProof,
+	) -> Option<()> {
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+
+		if signer.can_sign() {
+			match signer.send_signed_transaction(|_| {
+				pallet_babe::Call::report_equivocation(
+					equivocation_proof.clone(),
+					key_owner_proof.clone(),
+				)
+			}) {
+				Some((_, Ok(()))) => return Some(()),
+				_ => debug::warn!(
+					target: "fisherman",
+					"Failed to sign the BABE equivocation report, submitting it unsigned",
+				),
+			}
+		}
+
+		<pallet_babe::Module<T>>::submit_unsigned_equivocation_report(
+			equivocation_proof,
+			key_owner_proof,
+		)
+	}
+}
+
+impl<T> Module<T>
+where
+	T: Trait + pallet_grandpa::Trait + CreateSignedTransaction<pallet_grandpa::Call<T>>,
+{
+	/// Report a GRANDPA equivocation, signed with the fisherman key if there is one.
+	pub fn submit_grandpa_equivocation_report(
+		equivocation_proof: GrandpaEquivocationProof<T>,
+		key_owner_proof: <T as pallet_grandpa::Trait>::KeyOwnerProof,
+	) -> Option<()> {
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+
+		if signer.can_sign() {
+			match signer.send_signed_transaction(|_| {
+				pallet_grandpa::Call::report_equivocation(
+					equivocation_proof.clone(),
+					key_owner_proof.clone(),
+				)
+			}) {
+				Some((_, Ok(()))) => return Some(()),
+				_ => debug::warn!(
+					target: "fisherman",
+					"Failed to sign the GRANDPA equivocation report, submitting it unsigned",
+				),
+			}
+		}
+
+		<pallet_grandpa::Module<T>>::submit_unsigned_equivocation_report(
+			equivocation_proof,
+			key_owner_proof,
+		)
+	}
+}
+
+impl<T: Trait> Module<T> {
+	// Drop the reports of the settled games.
+	fn prune_settled_games() -> Weight {
+		let reported_games = <ReportedGames>::get();
+		let weight = T::DbWeight::get().reads(1 + reported_games.len() as Weight);
+		let (ongoing_games, settled_games): (Vec<_>, Vec<_>) = reported_games
+			.into_iter()
+			.partition(|game_id| T::RelayedHeaders::is_ongoing(*game_id));
+
+		if settled_games.is_empty() {
+			return weight;
+		}
+
+		for game_id in &settled_games {
+			<RelayFraudReports<T>>::remove_prefix(game_id);
+		}
+
+		<ReportedGames>::put(ongoing_games);
+
+		weight + T::DbWeight::get().writes(1 + settled_games.len() as Weight)
+	}
+
+	// Report the relayed headers which are not on the chain of the Ethereum RPC endpoint.
+	fn check_relayed_headers() -> Result<(), &'static str> {
+		let endpoint =
+			match sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, ETHEREUM_RPC_KEY) {
+				Some(endpoint) if !endpoint.is_empty() => endpoint,
+				_ => return Ok(()),
+			};
+		let endpoint =
+			core::str::from_utf8(&endpoint).map_err(|_| "invalid Ethereum RPC endpoint")?;
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+
+		if !signer.can_sign() {
+			return Ok(());
+		}
+
+		let relayed_headers = T::RelayedHeaders::relayed_headers();
+		// Only remember the reports of the ongoing games.
+		let mut reported =
+			sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, REPORTED_KEY)
+				.and_then(|reported| <Vec<RelayedHeaderOf<T>>>::decode(&mut &reported[..]).ok())
+				.unwrap_or_default()
+				.into_iter()
+				.filter(|relayed_header| relayed_headers.contains(relayed_header))
+				.collect::<Vec<_>>();
+
+		for relayed_header in relayed_headers {
+			if reported.contains(&relayed_header)
+				|| <RelayFraudReports<T>>::contains_key(
+					relayed_header.game_id,
+					(&relayed_header.relayer, relayed_header.number),
+				) {
+				continue;
+			}
+
+			let fraud_proof = match T::RelayedHeaders::fraud_proof_of(endpoint, &relayed_header)? {
+				Some(fraud_proof) => fraud_proof,
+				None => continue,
+			};
+
+			match signer.send_signed_transaction(|_| {
+				Call::report_relay_fraud(
+					relayed_header.game_id,
+					relayed_header.proposal_index,
+					relayed_header.number,
+					fraud_proof.clone(),
+				)
+			}) {
+				Some((_, Ok(()))) => reported.push(relayed_header),
+				_ => debug::warn!(
+					target: "fisherman",
+					"Failed to report the relayed header #{} {:?}",
+					relayed_header.number,
+					relayed_header.hash,
+				),
+			}
+		}
+
+		sp_io::offchain::local_storage_set(
+			StorageKind::PERSISTENT,
+			REPORTED_KEY,
+			&reported.encode(),
+		);
+
+		Ok(())
+	}
+}
+
+/// Prove `relayed_header` off the Ethereum chain of `endpoint` with its headers from the relayed
+/// number up to `confirmed_number`. `None` if the endpoint holds the relayed header, doesn't know
+/// one of the blocks, or if `confirmed_number` is too far above.
+pub fn ethereum_fraud_proof<AccountId, Balance>(
+	endpoint: &str,
+	relayed_header: &RelayedHeader<AccountId, Balance>,
+	confirmed_number: u64,
+) -> Result<Option<EthereumHeaderChain>, &'static str> {
+	let number = relayed_header.number;
+
+	if confirmed_number < number
+		|| confirmed_number - number >= MAX_ETHEREUM_HEADER_CHAIN_LEN as u64
+	{
+		return Ok(None);
+	}
+
+	let mut header_chain = vec![];
+
+	for number in number..=confirmed_number {
+		let header = match ethereum_header_rlp(&ethereum_block(endpoint, number)?) {
+			Some(header) => header,
+			None => return Ok(None),
+		};
+
+		if header_chain.is_empty() && keccak_256(&header) == relayed_header.hash {
+			return Ok(None);
+		}
+
+		header_chain.push(header);
+	}
+
+	Ok(Some(header_chain))
+}
+
+/// Whether `header_chain` proves `relayed_header` off the Ethereum chain: it starts with another
+/// header at the relayed number, each header is the parent of the next, and the last one is
+/// confirmed, as told by `confirmed_hash`.
+pub fn verify_ethereum_header_chain<AccountId, Balance>(
+	relayed_header: &RelayedHeader<AccountId, Balance>,
+	header_chain: &[Vec<u8>],
+	confirmed_hash: impl Fn(u64) -> Option<H256>,
+) -> bool {
+	if header_chain.is_empty() || header_chain.len() > MAX_ETHEREUM_HEADER_CHAIN_LEN {
+		return false;
+	}
+
+	let mut last_hash = None;
+
+	for (number, header) in (relayed_header.number..).zip(header_chain) {
+		match ethereum_header_parent_and_number(header) {
+			Some((parent_hash, header_number))
+				if header_number == number
+					&& last_hash.map_or(true, |last_hash| last_hash == parent_hash) => {}
+			_ => return false,
+		}
+
+		last_hash = Some(keccak_256(header));
+	}
+
+	let last_number = relayed_header.number + header_chain.len() as u64 - 1;
+
+	keccak_256(&header_chain[0]) != relayed_header.hash && last_hash == confirmed_hash(last_number)
+}
+
+fn keccak_256(data: &[u8]) -> H256 {
+	H256::from(sp_io::hashing::keccak_256(data))
+}
+
+// The `eth_getBlockByNumber` response of the Ethereum block `number`.
+fn ethereum_block(endpoint: &str, number: u64) -> Result<Vec<u8>, &'static str> {
+	let mut body =
+		br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBlockByNumber","params":["0x"#.to_vec();
+	let mut digits = vec![];
+	let mut n = number;
+
+	loop {
+		digits.push(b"0123456789abcdef"[(n & 0xf) as usize]);
+		n >>= 4;
+
+		if n == 0 {
+			break;
+		}
+	}
+
+	body.extend(digits.into_iter().rev());
+	body.extend_from_slice(br#"",false]}"#);
+
+	let deadline = sp_io::offchain::timestamp().add(ETHEREUM_RPC_TIMEOUT);
+	let response = http::Request::post(endpoint, vec![body])
+		.add_header("Content-Type", "application/json")
+		.deadline(deadline)
+		.send()
+		.map_err(|_| "failed to reach the Ethereum RPC endpoint")?
+		.try_wait(deadline)
+		.map_err(|_| "the Ethereum RPC endpoint timed out")?
+		.map_err(|_| "the Ethereum RPC endpoint failed")?;
+
+	if response.code != 200 {
+		return Err("the Ethereum RPC endpoint failed");
+	}
+
+	Ok(response.body().collect())
+}
+
+// The RLP encoded header of the block in an `eth_getBlockByNumber` response, `None` if the result
+// is `null`, or if the header doesn't hash as the block.
+fn ethereum_header_rlp(response: &[u8]) -> Option<Vec<u8>> {
+	let mut fields = vec![];
+
+	for (i, &(key, quantity)) in ETHEREUM_HEADER_FIELDS.iter().enumerate() {
+		let value = match json_string(response, key) {
+			Some(value) => value,
+			None if i >= ETHEREUM_HEADER_LEGACY_FIELDS_COUNT => break,
+			None => return None,
+		};
+
+		rlp_append_bytes(&mut fields, &hex_bytes(value, quantity)?);
+	}
+
+	let mut header = vec![];
+
+	rlp_append_len(&mut header, 0xc0, fields.len());
+	header.extend(fields);
+
+	if Some(keccak_256(&header)) == block_hash_of(response) {
+		Some(header)
+	} else {
+		None
+	}
+}
+
+// The parent hash and the number of an RLP encoded Ethereum header.
+fn ethereum_header_parent_and_number(header: &[u8]) -> Option<(H256, u64)> {
+	let (is_list, mut fields, rest) = rlp_split(header)?;
+
+	if !is_list || !rest.is_empty() {
+		return None;
+	}
+
+	let mut values = vec![];
+
+	while !fields.is_empty() {
+		let (is_list, value, rest) = rlp_split(fields)?;
+
+		if is_list {
+			return None;
+		}
+
+		values.push(value);
+		fields = rest;
+	}
+
+	if values.len() < ETHEREUM_HEADER_LEGACY_FIELDS_COUNT
+		|| values[0].len() != 32
+		|| values[8].len() > 8
+	{
+		return None;
+	}
+
+	Some((
+		H256::from_slice(values[0]),
+		values[8]
+			.iter()
+			.fold(0, |number, byte| number << 8 | *byte as u64),
+	))
+}
+
+// The `hash` of the block in an `eth_getBlockByNumber` response, `None` if the result is `null`.
+fn block_hash_of(response: &[u8]) -> Option<H256> {
+	let hash = hex_bytes(json_string(response, "hash")?, false)?;
+
+	if hash.len() == 32 {
+		Some(H256::from_slice(&hash))
+	} else {
+		None
+	}
+}
+
+// The string value of `key` in a JSON response, `None` if it's missing or not a string.
+fn json_string<'a>(json: &'a [u8], key: &str) -> Option<&'a [u8]> {
+	let key_len = key.len() + 2;
+	let start = json.windows(key_len).position(|window| {
+		window[0] == b'"'
+			&& &window[1..key_len - 1] == key.as_bytes()
+			&& window[key_len - 1] == b'"'
+	})? + key_len;
+	let value = json[start..]
+		.iter()
+		.position(|byte| !matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b':'))
+		.map(|offset| &json[start + offset..])?;
+
+	if value.first() != Some(&b'"') {
+		return None;
+	}
+
+	let end = value[1..].iter().position(|byte| *byte == b'"')?;
+
+	Some(&value[1..1 + end])
+}
+
+// The bytes of a `0x` prefixed hex string, without the leading zeros of a quantity.
+fn hex_bytes(hex: &[u8], quantity: bool) -> Option<Vec<u8>> {
+	if !hex.starts_with(b"0x") {
+		return None;
+	}
+
+	let mut digits = hex[2..]
+		.iter()
+		.map(|digit| (*digit as char).to_digit(16).map(|digit| digit as u8))
+		.collect::<Option<Vec<_>>>()?;
+
+	if quantity {
+		let leading_zeros = digits.iter().take_while(|digit| **digit == 0).count();
+
+		digits.drain(..leading_zeros);
+
+		if digits.len() % 2 == 1 {
+			digits.insert(0, 0);
+		}
+	}
+
+	if digits.len() % 2 == 1 {
+		return None;
+	}
+
+	Some(
+		digits
+			.chunks(2)
+			.map(|pair| pair[0] << 4 | pair[1])
+			.collect(),
+	)
+}
+
+// Append the RLP of the string `bytes`.
+fn rlp_append_bytes(rlp: &mut Vec<u8>, bytes: &[u8]) {
+	match bytes {
+		[byte] if *byte < 0x80 => rlp.push(*byte),
+		_ => {
+			rlp_append_len(rlp, 0x80, bytes.len());
+			rlp.extend_from_slice(bytes);
+		}
+	}
+}
+
+// Append the RLP prefix of a string, at `offset` 0x80, or of a list, at `offset` 0xc0, of `len`
+// bytes.
+fn rlp_append_len(rlp: &mut Vec<u8>, offset: u8, len: usize) {
+	if len < 56 {
+		rlp.push(offset + len as u8);
+	} else {
+		let len = (len as u64).to_be_bytes();
+		let len = &len[len.iter().take_while(|byte| **byte == 0).count()..];
+
+		rlp.push(offset + 55 + len.len() as u8);
+		rlp.extend_from_slice(len);
+	}
+}
+
+// Split the first RLP item off `rlp`, as whether it is a list, its payload and the rest.
+fn rlp_split(rlp: &[u8]) -> Option<(bool, &[u8], &[u8])> {
+	let (prefix, rest) = rlp.split_first()?;
+	let (is_list, len_of_len, short_len) = match *prefix {
+		0x00..=0x7f => return Some((false, &rlp[..1], rest)),
+		0x80..=0xb7 => (false, 0, (prefix - 0x80) as usize),
+		0xb8..=0xbf => (false, (prefix - 0xb7) as usize, 0),
+		0xc0..=0xf7 => (true, 0, (prefix - 0xc0) as usize),
+		_ => (true, (prefix - 0xf7) as usize, 0),
+	};
+	let len = if len_of_len == 0 {
+		short_len
+	} else {
+		rest.get(..len_of_len)?
+			.iter()
+			.try_fold(0usize, |len, byte| {
+				len.checked_mul(256)?.checked_add(*byte as usize)
+			})?
+	};
+	let rest = &rest[len_of_len..];
+
+	Some((is_list, rest.get(..len)?, rest.get(len..)?))
+}
+
+#[cfg(test)]
+mod tests {
+	// --- std ---
+	use std::cell::RefCell;
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_origin, parameter_types, traits::OnInitialize,
+	};
+	use sp_core::crypto::AccountId32;
+	use sp_runtime::{
+		testing::{Header, TestXt},
+		traits::{BlakeTwo256, IdentityLookup},
+		MultiSignature, MultiSigner,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type Fisherman = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		// Identified by the fisherman keys, as in the runtime.
+		type AccountId = AccountId32;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	impl frame_system::offchain::SigningTypes for Test {
+		type Public = MultiSigner;
+		type Signature = MultiSignature;
+	}
+	impl frame_system::offchain::SendTransactionTypes<Call<Test>> for Test {
+		type OverarchingCall = Call<Test>;
+		type Extrinsic = TestXt<Call<Test>, ()>;
+	}
+	impl CreateSignedTransaction<Call<Test>> for Test {
+		fn create_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+			call: Call<Test>,
+			_: MultiSigner,
+			_: AccountId32,
+			nonce: u64,
+		) -> Option<(Call<Test>, (u64, ()))> {
+			Some((call, (nonce, ())))
+		}
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	thread_local! {
+		static RELAYED_HEADERS: RefCell<Vec<RelayedHeader<AccountId32, Balance>>> =
+			RefCell::new(vec![]);
+		static CONFIRMED_HASHES: RefCell<Vec<(u64, H256)>> = RefCell::new(vec![]);
+	}
+
+	fn confirmed_hash(number: u64) -> Option<H256> {
+		CONFIRMED_HASHES.with(|confirmed_hashes| {
+			confirmed_hashes
+				.borrow()
+				.iter()
+				.find(|(confirmed_number, _)| *confirmed_number == number)
+				.map(|(_, hash)| *hash)
+		})
+	}
+
+	/// The relayer games of `RELAYED_HEADERS`, confirmed by `CONFIRMED_HASHES`.
+	pub struct MockRelayedHeaders;
+	impl RelayedHeaders<AccountId32, Balance> for MockRelayedHeaders {
+		type FraudProof = EthereumHeaderChain;
+
+		fn relayed_headers() -> Vec<RelayedHeader<AccountId32, Balance>> {
+			RELAYED_HEADERS.with(|relayed_headers| relayed_headers.borrow().clone())
+		}
+
+		fn relayed_headers_of(
+			game_id: u64,
+			proposal_index: u32,
+		) -> Vec<RelayedHeader<AccountId32, Balance>> {
+			Self::relayed_headers()
+				.into_iter()
+				.filter(|relayed_header| {
+					relayed_header.game_id == game_id
+						&& relayed_header.proposal_index == proposal_index
+				})
+				.collect()
+		}
+
+		fn is_ongoing(game_id: u64) -> bool {
+			Self::relayed_headers()
+				.iter()
+				.any(|relayed_header| relayed_header.game_id == game_id)
+		}
+
+		fn verify_fraud_proof(
+			relayed_header: &RelayedHeader<AccountId32, Balance>,
+			fraud_proof: &EthereumHeaderChain,
+		) -> bool {
+			verify_ethereum_header_chain(relayed_header, fraud_proof, confirmed_hash)
+		}
+
+		fn verify_fraud_proof_weight(fraud_proof: &EthereumHeaderChain) -> Weight {
+			fraud_proof.len() as Weight
+		}
+
+		fn fraud_proof_of(
+			_: &str,
+			_: &RelayedHeader<AccountId32, Balance>,
+		) -> Result<Option<EthereumHeaderChain>, &'static str> {
+			Ok(None)
+		}
+	}
+
+	parameter_types! {
+		pub const SlashRewardFraction: Perbill = Perbill::from_percent(10);
+	}
+	impl Trait for Test {
+		type Event = ();
+		type AuthorityId = darwinia_primitives::fisherman::FishermanAppCrypto;
+		type RingCurrency = Ring;
+		type SlashRewardFraction = SlashRewardFraction;
+		type RelayedHeaders = MockRelayedHeaders;
+	}
+
+	const ALICE: [u8; 32] = [1; 32];
+	const BOB: [u8; 32] = [2; 32];
+	const BOND: Balance = 1_000;
+
+	fn alice() -> AccountId32 {
+		AccountId32::from(ALICE)
+	}
+
+	fn bob() -> AccountId32 {
+		AccountId32::from(BOB)
+	}
+
+	fn h256(hex: &str) -> H256 {
+		H256::from_slice(&hex_bytes(hex.as_bytes(), false).unwrap())
+	}
+
+	// The `eth_getBlockByNumber` responses of the first two Ethereum mainnet blocks.
+	fn ethereum_block_response(number: u64) -> String {
+		let zero_hash = format!("0x{}", "00".repeat(32));
+		let empty_trie = "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421";
+		let (
+			hash,
+			parent_hash,
+			miner,
+			state_root,
+			difficulty,
+			timestamp,
+			extra_data,
+			mix_hash,
+			nonce,
+		) = match number {
+			0 => (
+				"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+				zero_hash.as_str(),
+				"0x0000000000000000000000000000000000000000",
+				"0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+				"0x400000000",
+				"0x0",
+				"0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+				zero_hash.as_str(),
+				"0x0000000000000042",
+			),
+			_ => (
+				"0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6",
+				"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+				"0x05a56e2d52c817161883f50c441c3228cfe54d9f",
+				"0xd67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3",
+				"0x3ff800000",
+				"0x55ba4224",
+				"0x476574682f76312e302e302f6c696e75782f676f312e342e32",
+				"0x969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59",
+				"0x539bd4979fef1ec4",
+			),
+		};
+
+		format!(
+			r#"{{"jsonrpc":"2.0","id":1,"result":{{"difficulty":"{}","extraData":"{}","gasLimit":"0x1388","gasUsed":"0x0","hash":"{}","logsBloom":"0x{}","miner":"{}","mixHash":"{}","nonce":"{}","number":"0x{:x}","parentHash":"{}","receiptsRoot":"{}","sha3Uncles":"0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347","size":"0x21c","stateRoot":"{}","timestamp":"{}","totalDifficulty":"0x7ff800000","transactions":[],"transactionsRoot":"{}","uncles":[]}}}}"#,
+			difficulty,
+			extra_data,
+			hash,
+			"00".repeat(256),
+			miner,
+			mix_hash,
+			nonce,
+			number,
+			parent_hash,
+			empty_trie,
+			state_root,
+			timestamp,
+			empty_trie,
+		)
+	}
+
+	fn ethereum_header(number: u64) -> Vec<u8> {
+		ethereum_header_rlp(ethereum_block_response(number).as_bytes()).unwrap()
+	}
+
+	fn genesis_hash() -> H256 {
+		h256("0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3")
+	}
+
+	fn block_1_hash() -> H256 {
+		h256("0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6")
+	}
+
+	// A header of `bob` in the proposal 0 of the game 7, at the height of the Ethereum genesis.
+	fn forged_genesis() -> RelayedHeader<AccountId32, Balance> {
+		RelayedHeader {
+			game_id: 7,
+			proposal_index: 0,
+			relayer: bob(),
+			bond: BOND,
+			number: 0,
+			hash: H256::repeat_byte(0xff),
+		}
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(alice(), 1_000), (bob(), 10_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		RELAYED_HEADERS.with(|relayed_headers| *relayed_headers.borrow_mut() = vec![]);
+		CONFIRMED_HASHES
+			.with(|confirmed_hashes| *confirmed_hashes.borrow_mut() = vec![(1, block_1_hash())]);
+
+		storage.into()
+	}
+
+	fn relay(relayed_header: RelayedHeader<AccountId32, Balance>) {
+		RELAYED_HEADERS.with(|relayed_headers| relayed_headers.borrow_mut().push(relayed_header));
+	}
+
+	fn settle(game_id: u64) {
+		RELAYED_HEADERS.with(|relayed_headers| {
+			relayed_headers
+				.borrow_mut()
+				.retain(|relayed_header| relayed_header.game_id != game_id)
+		});
+	}
+
+	#[test]
+	fn block_hash_of_should_read_the_block_hash() {
+		let hash = H256::repeat_byte(0xab);
+		let response = format!(
+			r#"{{"jsonrpc":"2.0","id":1,"result":{{"parentHash":"0x{0:x}","hash": "0x{1:x}","number":"0x1"}}}}"#,
+			H256::repeat_byte(0x01),
+			hash
+		);
+
+		assert_eq!(block_hash_of(response.as_bytes()), Some(hash));
+		assert_eq!(
+			block_hash_of(br#"{"jsonrpc":"2.0","id":1,"result":null}"#),
+			None
+		);
+		assert_eq!(
+			block_hash_of(br#"{"jsonrpc":"2.0","id":1,"result":{"hash":"0x12"}}"#),
+			None
+		);
+	}
+
+	#[test]
+	fn ethereum_header_rlp_should_hash_as_the_block() {
+		assert_eq!(keccak_256(&ethereum_header(0)), genesis_hash());
+		assert_eq!(keccak_256(&ethereum_header(1)), block_1_hash());
+		assert_eq!(
+			ethereum_header_parent_and_number(&ethereum_header(1)),
+			Some((genesis_hash(), 1))
+		);
+
+		let forged_response = ethereum_block_response(1)
+			.replace("\"gasLimit\":\"0x1388\"", "\"gasLimit\":\"0x1389\"");
+
+		assert_eq!(ethereum_header_rlp(forged_response.as_bytes()), None);
+		assert_eq!(
+			ethereum_header_rlp(br#"{"jsonrpc":"2.0","id":1,"result":null}"#),
+			None
+		);
+	}
+
+	#[test]
+	fn verify_ethereum_header_chain_should_prove_another_header_up_to_a_confirmed_one() {
+		let header_chain = vec![ethereum_header(0), ethereum_header(1)];
+
+		assert!(verify_ethereum_header_chain(
+			&forged_genesis(),
+			&header_chain,
+			confirmed_hash
+		));
+		// The relayed header itself.
+		assert!(!verify_ethereum_header_chain(
+			&RelayedHeader {
+				hash: genesis_hash(),
+				..forged_genesis()
+			},
+			&header_chain,
+			confirmed_hash
+		));
+		// Not up to a confirmed header.
+		assert!(!verify_ethereum_header_chain(
+			&forged_genesis(),
+			&header_chain[..1],
+			confirmed_hash
+		));
+		// Not from the relayed number.
+		assert!(!verify_ethereum_header_chain(
+			&RelayedHeader {
+				number: 1,
+				..forged_genesis()
+			},
+			&header_chain,
+			|_| Some(block_1_hash())
+		));
+
+		let mut orphan = ethereum_header(1);
+
+		// The first byte of the parent hash, after the list and the string prefixes.
+		orphan[4] ^= 0xff;
+
+		// Not each the parent of the next.
+		assert!(!verify_ethereum_header_chain(
+			&forged_genesis(),
+			&[ethereum_header(0), orphan.clone()],
+			|_| Some(keccak_256(&orphan))
+		));
+		assert!(!verify_ethereum_header_chain(
+			&forged_genesis(),
+			&[],
+			confirmed_hash
+		));
+		assert!(!verify_ethereum_header_chain(
+			&forged_genesis(),
+			&[vec![0xc0], ethereum_header(1)],
+			confirmed_hash
+		));
+	}
+
+	#[test]
+	fn report_relay_fraud_should_pay_the_reporter_from_the_relayer() {
+		new_test_ext().execute_with(|| {
+			relay(forged_genesis());
+
+			assert_ok!(Fisherman::report_relay_fraud(
+				Origin::signed(alice()),
+				7,
+				0,
+				0,
+				vec![ethereum_header(0), ethereum_header(1)],
+			));
+			assert_eq!(Ring::free_balance(alice()), 1_000 + BOND / 10);
+			assert_eq!(Ring::free_balance(bob()), 10_000 - BOND / 10);
+			assert_eq!(Fisherman::relay_fraud_report(7, (bob(), 0)), Some(alice()));
+			assert_eq!(Fisherman::reported_games(), vec![7]);
+		});
+	}
+
+	#[test]
+	fn report_relay_fraud_should_fail_without_a_valid_proof() {
+		new_test_ext().execute_with(|| {
+			relay(forged_genesis());
+
+			let header_chain = vec![ethereum_header(0), ethereum_header(1)];
+
+			assert_noop!(
+				Fisherman::report_relay_fraud(
+					Origin::signed(alice()),
+					7,
+					1,
+					0,
+					header_chain.clone()
+				),
+				<Error<Test>>::RelayedHeaderNE
+			);
+			assert_noop!(
+				Fisherman::report_relay_fraud(
+					Origin::signed(alice()),
+					7,
+					0,
+					1,
+					header_chain.clone()
+				),
+				<Error<Test>>::RelayedHeaderNE
+			);
+			assert_noop!(
+				Fisherman::report_relay_fraud(
+					Origin::signed(alice()),
+					7,
+					0,
+					0,
+					header_chain[..1].to_vec()
+				),
+				<Error<Test>>::FraudProofInv
+			);
+		});
+	}
+
+	#[test]
+	fn report_relay_fraud_should_pay_each_header_once() {
+		new_test_ext().execute_with(|| {
+			relay(forged_genesis());
+
+			let header_chain = vec![ethereum_header(0), ethereum_header(1)];
+
+			assert_ok!(Fisherman::report_relay_fraud(
+				Origin::signed(alice()),
+				7,
+				0,
+				0,
+				header_chain.clone(),
+			));
+			assert_noop!(
+				Fisherman::report_relay_fraud(Origin::signed(alice()), 7, 0, 0, header_chain),
+				<Error<Test>>::RelayFraudAR
+			);
+		});
+	}
+
+	#[test]
+	fn relay_fraud_reports_should_be_pruned_once_the_game_settles() {
+		new_test_ext().execute_with(|| {
+			relay(forged_genesis());
+			relay(RelayedHeader {
+				game_id: 8,
+				..forged_genesis()
+			});
+
+			let header_chain = vec![ethereum_header(0), ethereum_header(1)];
+
+			assert_ok!(Fisherman::report_relay_fraud(
+				Origin::signed(alice()),
+				7,
+				0,
+				0,
+				header_chain.clone(),
+			));
+			assert_ok!(Fisherman::report_relay_fraud(
+				Origin::signed(alice()),
+				8,
+				0,
+				0,
+				header_chain,
+			));
+
+			Fisherman::on_initialize(1);
+
+			assert_eq!(Fisherman::reported_games(), vec![7, 8]);
+
+			settle(7);
+			Fisherman::on_initialize(2);
+
+			assert_eq!(Fisherman::relay_fraud_report(7, (bob(), 0)), None);
+			assert_eq!(Fisherman::relay_fraud_report(8, (bob(), 0)), Some(alice()));
+			assert_eq!(Fisherman::reported_games(), vec![8]);
+		});
+	}
+}
@@ -21,30 +21,34 @@ darwinia_support::impl_account_data! {
 }
 
 /// Logic for the author to get a portion of fees.
-pub struct ToAuthor<R>(sp_std::marker::PhantomData<R>);
-impl<R> OnUnbalanced<NegativeImbalance<R>> for ToAuthor<R>
+///
+/// Defaults to the RING instance, use `ToAuthor<R, KtonInstance>` for the KTON fees.
+pub struct ToAuthor<R, I = RingInstance>(sp_std::marker::PhantomData<(R, I)>);
+impl<R, I> OnUnbalanced<NegativeImbalance<R, I>> for ToAuthor<R, I>
 where
-	R: darwinia_balances::Trait<RingInstance> + pallet_authorship::Trait,
+	R: darwinia_balances::Trait<I> + pallet_authorship::Trait,
+	I: darwinia_balances::Instance,
 	<R as frame_system::Trait>::AccountId: From<darwinia_primitives::AccountId>,
 	<R as frame_system::Trait>::AccountId: Into<darwinia_primitives::AccountId>,
 	<R as frame_system::Trait>::Event: From<
 		darwinia_balances::RawEvent<
 			<R as frame_system::Trait>::AccountId,
-			<R as darwinia_balances::Trait<RingInstance>>::Balance,
-			RingInstance,
+			<R as darwinia_balances::Trait<I>>::Balance,
+			I,
 		>,
 	>,
 {
-	fn on_nonzero_unbalanced(amount: NegativeImbalance<R>) {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance<R, I>) {
 		let numeric_amount = amount.peek();
 		let author = <pallet_authorship::Module<R>>::author();
-		<darwinia_balances::Module<R, RingInstance>>::resolve_creating(
+		<darwinia_balances::Module<R, I>>::resolve_creating(
 			&<pallet_authorship::Module<R>>::author(),
 			amount,
 		);
-		<frame_system::Module<R>>::deposit_event(
-			<darwinia_balances::RawEvent<_, _, RingInstance>>::Deposit(author, numeric_amount),
-		);
+		<frame_system::Module<R>>::deposit_event(<darwinia_balances::RawEvent<_, _, I>>::Deposit(
+			author,
+			numeric_amount,
+		));
 	}
 }
 
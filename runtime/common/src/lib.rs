@@ -6,6 +6,37 @@
 pub mod impls;
 pub use impls::*;
 
+/// Treasury bounties paid in RING or KTON.
+pub mod bounties;
+/// Emergency call filter driven by the technical committee.
+pub mod call_filter;
+/// Map CRING to the mainnet RING.
+pub mod crab_mapping;
+/// RING/KTON constant product market maker.
+pub mod dex;
+/// Pay the transaction fees in KTON.
+pub mod fee_payment;
+/// Governable split of transaction fees and tips.
+pub mod fee_split;
+/// Equivocation and relayer game fraud reports signed with the fisherman key.
+pub mod fisherman;
+/// The chain level metrics published by the node.
+pub mod metrics;
+/// Cross-chain NFTs between Ethereum and Darwinia.
+pub mod nft_bridge;
+/// Node side tuning of the offchain phragmen election.
+pub mod offchain_election;
+/// Relayer game adjustor with selectable sampling strategies.
+pub mod relayer_game_adjustor;
+/// Governable relayer game parameters.
+pub mod relayer_game_params;
+/// Agenda of the scheduler.
+pub mod scheduler;
+/// Root calls approved by both collectives and the retirement of sudo.
+pub mod sudo_retirement;
+/// Vesting schedules of the RING and KTON balances.
+pub mod vesting;
+
 // --- substrate ---
 pub use frame_support::weights::constants::{
 	BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight,
@@ -32,7 +63,7 @@ use darwinia_primitives::BlockNumber;
 pub type RingInstance = darwinia_balances::Instance0;
 pub type KtonInstance = darwinia_balances::Instance1;
 
-pub type NegativeImbalance<T> = <darwinia_balances::Module<T, RingInstance> as Currency<
+pub type NegativeImbalance<T, I = RingInstance> = <darwinia_balances::Module<T, I> as Currency<
 	<T as frame_system::Trait>::AccountId,
 >>::NegativeImbalance;
 
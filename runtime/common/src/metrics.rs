@@ -0,0 +1,47 @@
+//! The chain level metrics published by the node.
+
+pub use darwinia_rpc_runtime_api::metrics::ChainMetrics;
+
+/// The leaves of a Merkle Mountain Range of `mmr_size` nodes.
+///
+/// A valid MMR splits into perfect binary trees of strictly decreasing heights, the peaks, and a
+/// peak of height `h` holds `2^(h + 1) - 1` nodes and `2^h` leaves.
+pub fn mmr_leaf_count(mut mmr_size: u64) -> u64 {
+	let mut leaf_count = 0;
+
+	for height in (0..63).rev() {
+		let peak_size = (1 << (height + 1)) - 1;
+
+		if mmr_size >= peak_size {
+			mmr_size -= peak_size;
+			leaf_count += 1 << height;
+		}
+	}
+
+	leaf_count
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn mmr_leaf_count_should_work() {
+		// (mmr size, leaf count)
+		for &(mmr_size, leaf_count) in &[
+			(0, 0),
+			(1, 1),
+			(3, 2),
+			(4, 3),
+			(7, 4),
+			(8, 5),
+			(10, 6),
+			(11, 7),
+			(15, 8),
+			(19, 11),
+			(2_u64.pow(33) - 1, 2_u64.pow(32)),
+		] {
+			assert_eq!(mmr_leaf_count(mmr_size), leaf_count);
+		}
+	}
+}
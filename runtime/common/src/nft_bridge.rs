@@ -0,0 +1,694 @@
+//! Cross-chain NFTs between Ethereum and Darwinia, following RFC-0005.
+//!
+//! An ERC-721 token locked in the Ethereum-side `NftLocker` contract is proven through an
+//! `EthereumRelay` receipt and mirrored on Darwinia under the same contract address and token id.
+//! Each receipt is proven once, minting every NFT it locked.
+//! Redeeming burns the mirrored token and deposits a `Redeemed` event, which the Ethereum-side
+//! relayers prove through the Header MMR to unlock the original.
+
+// --- crates ---
+use ethabi::{Event as EthEvent, EventParam as EthEventParam, ParamType, RawLog, Token};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{Currency, ExistenceRequirement, Get},
+	transactional,
+};
+use frame_system::{ensure_root, ensure_signed};
+use sp_runtime::DispatchError;
+use sp_std::{convert::TryFrom, prelude::*};
+// --- darwinia ---
+use darwinia_support::traits::EthereumReceipt;
+use ethereum_primitives::{receipt::EthereumTransactionIndex, EthereumAddress, U256};
+
+pub type AccountId<T> = <T as frame_system::Trait>::AccountId;
+pub type RingBalance<T> = <<T as Trait>::RingCurrency as Currency<AccountId<T>>>::Balance;
+pub type EthereumReceiptProofThing<T> = <<T as Trait>::EthereumRelay as EthereumReceipt<
+	AccountId<T>,
+	RingBalance<T>,
+>>::EthereumReceiptProofThing;
+
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The currency the receipt verification fee is paid in.
+	type RingCurrency: Currency<Self::AccountId>;
+
+	type EthereumRelay: EthereumReceipt<Self::AccountId, RingBalance<Self>>;
+
+	/// The account id decoded from the `bytes32` receiver of the Ethereum event.
+	type RedeemAccountId: From<[u8; 32]> + Into<Self::AccountId>;
+}
+
+/// A mirrored NFT, identified by its ERC-721 contract and token id.
+pub type NftId = (EthereumAddress, U256);
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = AccountId<T>,
+	{
+		/// An NFT locked on Ethereum was mirrored. [owner, token contract, token id]
+		Minted(AccountId, EthereumAddress, U256),
+		/// A mirrored NFT was transferred. [from, to, token contract, token id]
+		Transferred(AccountId, AccountId, EthereumAddress, U256),
+		/// A mirrored NFT was burned to unlock the original on Ethereum.
+		/// [owner, token contract, token id, ethereum receiver, nonce]
+		Redeemed(AccountId, EthereumAddress, U256, EthereumAddress, u64),
+		/// The NFT locker contract address was set. [new address]
+		NftLockerAddressSet(EthereumAddress),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Receipt Proof - INVALID
+		ReceiptProofInv,
+		/// Asset - ALREADY REDEEMED
+		AssetAR,
+		/// Eth Log - NOT EXISTED
+		LogEntryNE,
+		/// Eth Log - PARSING FAILED
+		EthLogPF,
+		/// Nft - ALREADY EXISTED
+		NftAE,
+		/// Nft - NOT EXISTED
+		NftNE,
+		/// Nft - NOT OWNER
+		NotOwner,
+		/// NftLocker Address - NOT SET
+		NftLockerAddrNS,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaNftBridge {
+		/// The Ethereum contract which locks the ERC-721 tokens, no NFT is minted while unset.
+		pub NftLockerAddress get(fn nft_locker_address) config(): EthereumAddress;
+
+		/// The Ethereum receipts which already minted their NFTs.
+		pub VerifiedProof get(fn verified_proof):
+			map hasher(blake2_128_concat) EthereumTransactionIndex => bool;
+
+		/// The owners of the mirrored NFTs.
+		pub Owners get(fn owner_of):
+			map hasher(blake2_128_concat) NftId => Option<T::AccountId>;
+
+		/// The number of redeems, which makes each `Redeemed` event unique.
+		pub RedeemNonce get(fn redeem_nonce): u64;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Mirror the NFTs locked in the `NftLocker` contract on Ethereum.
+		///
+		/// The dispatch origin for this call must be _Signed_ and pays the receipt verification
+		/// fee.
+		///
+		/// - `proof`: The receipt proof of one or more `NftLocked` events, all of which are minted.
+		#[weight = T::DbWeight::get().reads_writes(3, 2)]
+		#[transactional]
+		pub fn mint(origin, proof: EthereumReceiptProofThing<T>) {
+			let submitter = ensure_signed(origin)?;
+
+			ensure!(!Self::nft_locker_address().is_zero(), <Error<T>>::NftLockerAddrNS);
+
+			let tx_index = T::EthereumRelay::gen_receipt_index(&proof);
+
+			ensure!(!<VerifiedProof>::contains_key(tx_index), <Error<T>>::AssetAR);
+
+			let locked_nfts = Self::parse_nft_locked_proof(&proof)?;
+
+			T::RingCurrency::transfer(
+				&submitter,
+				&T::EthereumRelay::account_id(),
+				T::EthereumRelay::receipt_verify_fee(),
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			<VerifiedProof>::insert(tx_index, true);
+
+			for (token, token_id, owner) in locked_nfts {
+				let nft_id = (token, token_id);
+
+				ensure!(!<Owners<T>>::contains_key(nft_id), <Error<T>>::NftAE);
+
+				<Owners<T>>::insert(nft_id, &owner);
+
+				Self::deposit_event(RawEvent::Minted(owner, token, token_id));
+			}
+		}
+
+		/// Transfer a mirrored NFT.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the owner of the NFT.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		#[transactional]
+		pub fn transfer(origin, dest: T::AccountId, token: EthereumAddress, token_id: U256) {
+			let who = ensure_signed(origin)?;
+			let nft_id = (token, token_id);
+
+			Self::ensure_owner(&who, &nft_id)?;
+
+			<Owners<T>>::insert(nft_id, &dest);
+
+			Self::deposit_event(RawEvent::Transferred(who, dest, token, token_id));
+		}
+
+		/// Burn a mirrored NFT to unlock the original on Ethereum.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the owner of the NFT.
+		///
+		/// - `ethereum_receiver`: The Ethereum account which receives the original NFT.
+		#[weight = T::DbWeight::get().reads_writes(2, 2)]
+		#[transactional]
+		pub fn redeem(
+			origin,
+			token: EthereumAddress,
+			token_id: U256,
+			ethereum_receiver: EthereumAddress,
+		) {
+			let who = ensure_signed(origin)?;
+			let nft_id = (token, token_id);
+
+			Self::ensure_owner(&who, &nft_id)?;
+
+			let nonce = Self::redeem_nonce();
+
+			<Owners<T>>::remove(nft_id);
+			<RedeemNonce>::put(nonce + 1);
+
+			Self::deposit_event(RawEvent::Redeemed(who, token, token_id, ethereum_receiver, nonce));
+		}
+
+		/// Set the Ethereum contract which locks the ERC-721 tokens.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_nft_locker_address(origin, new: EthereumAddress) {
+			ensure_root(origin)?;
+
+			<NftLockerAddress>::put(new);
+
+			Self::deposit_event(RawEvent::NftLockerAddressSet(new));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn ensure_owner(who: &T::AccountId, nft_id: &NftId) -> Result<(), Error<T>> {
+		let owner = Self::owner_of(nft_id).ok_or(<Error<T>>::NftNE)?;
+
+		ensure!(&owner == who, <Error<T>>::NotOwner);
+
+		Ok(())
+	}
+
+	/// Parse every `NftLocked(address indexed token, uint256 indexed tokenId, bytes32 receiver)`
+	/// of the `NftLocker` contract out of the proven receipt.
+	fn parse_nft_locked_proof(
+		proof: &EthereumReceiptProofThing<T>,
+	) -> Result<Vec<(EthereumAddress, U256, T::AccountId)>, DispatchError> {
+		let verified_receipt =
+			T::EthereumRelay::verify_receipt(proof).map_err(|_| <Error<T>>::ReceiptProofInv)?;
+		let eth_event = nft_locked_event();
+		let nft_locker_address = Self::nft_locker_address();
+		let locked_nfts = verified_receipt
+			.logs
+			.into_iter()
+			.filter(|x| {
+				x.address == nft_locker_address && x.topics.get(0) == Some(&eth_event.signature())
+			})
+			.map(|log_entry| {
+				let log = RawLog {
+					topics: log_entry.topics,
+					data: log_entry.data,
+				};
+				let params = eth_event
+					.parse_log(log)
+					.map_err(|_| <Error<T>>::EthLogPF)?
+					.params;
+				let token = params[0]
+					.value
+					.clone()
+					.to_address()
+					.ok_or(<Error<T>>::EthLogPF)?;
+				let token_id = params[1]
+					.value
+					.clone()
+					.to_uint()
+					.ok_or(<Error<T>>::EthLogPF)?;
+				let owner = match &params[2].value {
+					Token::FixedBytes(receiver) => <[u8; 32]>::try_from(&receiver[..])
+						.map(|receiver| T::RedeemAccountId::from(receiver).into())
+						.map_err(|_| <Error<T>>::EthLogPF)?,
+					_ => return Err(<Error<T>>::EthLogPF),
+				};
+
+				Ok((token, token_id, owner))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		ensure!(!locked_nfts.is_empty(), <Error<T>>::LogEntryNE);
+
+		Ok(locked_nfts)
+	}
+}
+
+/// The `NftLocked` event of the `NftLocker` contract.
+fn nft_locked_event() -> EthEvent {
+	EthEvent {
+		name: "NftLocked".into(),
+		inputs: vec![
+			EthEventParam {
+				name: "token".into(),
+				kind: ParamType::Address,
+				indexed: true,
+			},
+			EthEventParam {
+				name: "tokenId".into(),
+				kind: ParamType::Uint(256),
+				indexed: true,
+			},
+			EthEventParam {
+				name: "receiver".into(),
+				kind: ParamType::FixedBytes(32),
+				indexed: false,
+			},
+		],
+		anonymous: false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- crates ---
+	use codec::{Decode, Encode};
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight,
+	};
+	use sp_core::crypto::AccountId32;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill, RuntimeDebug,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+	use ethereum_primitives::{
+		receipt::{EthereumReceipt as Receipt, LogEntry, TransactionOutcome},
+		H256,
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type NftBridge = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = sp_core::H256;
+		type Hashing = BlakeTwo256;
+		// Decoded from the `bytes32` receivers, as in the runtime.
+		type AccountId = AccountId32;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	/// A receipt proof carrying its logs, verified unless `verified` is false.
+	#[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug)]
+	pub struct MockProof {
+		tx_index: u64,
+		verified: bool,
+		logs: Vec<LogEntry>,
+	}
+
+	const RECEIPT_VERIFY_FEE: Balance = 10;
+
+	pub struct MockEthereumRelay;
+	impl EthereumReceipt<AccountId32, Balance> for MockEthereumRelay {
+		type EthereumReceiptProofThing = MockProof;
+
+		fn account_id() -> AccountId32 {
+			AccountId32::from([0; 32])
+		}
+
+		fn receipt_verify_fee() -> Balance {
+			RECEIPT_VERIFY_FEE
+		}
+
+		fn verify_receipt(proof: &MockProof) -> Result<Receipt, DispatchError> {
+			if proof.verified {
+				Ok(Receipt::new(
+					TransactionOutcome::StatusCode(1),
+					U256::zero(),
+					proof.logs.clone(),
+				))
+			} else {
+				Err("Receipt - INVALID".into())
+			}
+		}
+
+		fn gen_receipt_index(proof: &MockProof) -> EthereumTransactionIndex {
+			(H256::zero(), proof.tx_index)
+		}
+	}
+
+	impl Trait for Test {
+		type Event = ();
+		type RingCurrency = Ring;
+		type EthereumRelay = MockEthereumRelay;
+		type RedeemAccountId = AccountId32;
+	}
+
+	const ALICE: [u8; 32] = [1; 32];
+	const BOB: [u8; 32] = [2; 32];
+
+	fn alice() -> AccountId32 {
+		AccountId32::from(ALICE)
+	}
+
+	fn bob() -> AccountId32 {
+		AccountId32::from(BOB)
+	}
+
+	fn nft_locker() -> EthereumAddress {
+		EthereumAddress::repeat_byte(0x11)
+	}
+
+	fn token() -> EthereumAddress {
+		EthereumAddress::repeat_byte(0x22)
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(alice(), 1_000), (bob(), 1_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		GenesisConfig {
+			nft_locker_address: nft_locker(),
+		}
+		.assimilate_storage::<Test>(&mut storage)
+		.unwrap();
+
+		storage.into()
+	}
+
+	/// An `NftLocked(token, token_id, receiver)` log emitted by `address`.
+	fn nft_locked_log(address: EthereumAddress, token_id: u64, receiver: [u8; 32]) -> LogEntry {
+		let mut token_id_topic = [0; 32];
+
+		U256::from(token_id).to_big_endian(&mut token_id_topic);
+
+		LogEntry {
+			address,
+			topics: vec![
+				nft_locked_event().signature(),
+				H256::from(token()),
+				H256::from(token_id_topic),
+			],
+			data: receiver.to_vec(),
+		}
+	}
+
+	fn proof(tx_index: u64, logs: Vec<LogEntry>) -> MockProof {
+		MockProof {
+			tx_index,
+			verified: true,
+			logs,
+		}
+	}
+
+	#[test]
+	fn mint_should_only_mint_the_nft_locked_logs_of_the_locker() {
+		new_test_ext().execute_with(|| {
+			let mut other_event_log = nft_locked_log(nft_locker(), 2, BOB);
+
+			other_event_log.topics[0] = H256::repeat_byte(0x33);
+
+			assert_ok!(NftBridge::mint(
+				Origin::signed(bob()),
+				proof(
+					0,
+					vec![
+						nft_locked_log(EthereumAddress::repeat_byte(0x44), 1, BOB),
+						other_event_log,
+						nft_locked_log(nft_locker(), 3, ALICE),
+					]
+				)
+			));
+			assert_eq!(NftBridge::owner_of((token(), U256::from(1))), None);
+			assert_eq!(NftBridge::owner_of((token(), U256::from(2))), None);
+			assert_eq!(NftBridge::owner_of((token(), U256::from(3))), Some(alice()));
+			// The submitter pays the verification fee.
+			assert_eq!(Ring::free_balance(&bob()), 1_000 - RECEIPT_VERIFY_FEE);
+			assert_eq!(
+				Ring::free_balance(&MockEthereumRelay::account_id()),
+				RECEIPT_VERIFY_FEE
+			);
+			assert!(NftBridge::verified_proof((H256::zero(), 0)));
+		});
+	}
+
+	#[test]
+	fn mint_should_reject_the_invalid_proofs() {
+		new_test_ext().execute_with(|| {
+			let mut truncated_log = nft_locked_log(nft_locker(), 1, ALICE);
+
+			truncated_log.data.truncate(16);
+
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(alice()),
+					MockProof {
+						tx_index: 0,
+						verified: false,
+						logs: vec![nft_locked_log(nft_locker(), 1, ALICE)],
+					}
+				),
+				<Error<Test>>::ReceiptProofInv
+			);
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(alice()),
+					proof(0, vec![nft_locked_log(token(), 1, ALICE)])
+				),
+				<Error<Test>>::LogEntryNE
+			);
+			// One malformed lock fails the whole receipt.
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(alice()),
+					proof(
+						0,
+						vec![nft_locked_log(nft_locker(), 2, ALICE), truncated_log]
+					)
+				),
+				<Error<Test>>::EthLogPF
+			);
+
+			assert_ok!(NftBridge::set_nft_locker_address(
+				Origin::root(),
+				EthereumAddress::zero()
+			));
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(alice()),
+					proof(0, vec![nft_locked_log(EthereumAddress::zero(), 1, ALICE)])
+				),
+				<Error<Test>>::NftLockerAddrNS
+			);
+		});
+	}
+
+	#[test]
+	fn mint_should_mint_every_lock_of_the_receipt() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NftBridge::mint(
+				Origin::signed(alice()),
+				proof(
+					0,
+					vec![
+						nft_locked_log(nft_locker(), 1, ALICE),
+						nft_locked_log(nft_locker(), 2, BOB),
+						nft_locked_log(nft_locker(), 3, ALICE),
+					]
+				)
+			));
+			assert_eq!(NftBridge::owner_of((token(), U256::from(1))), Some(alice()));
+			assert_eq!(NftBridge::owner_of((token(), U256::from(2))), Some(bob()));
+			assert_eq!(NftBridge::owner_of((token(), U256::from(3))), Some(alice()));
+			// A single fee for the whole receipt.
+			assert_eq!(Ring::free_balance(&alice()), 1_000 - RECEIPT_VERIFY_FEE);
+		});
+	}
+
+	#[test]
+	fn mint_should_not_mint_twice() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NftBridge::mint(
+				Origin::signed(alice()),
+				proof(0, vec![nft_locked_log(nft_locker(), 1, ALICE)])
+			));
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(alice()),
+					proof(0, vec![nft_locked_log(nft_locker(), 1, ALICE)])
+				),
+				<Error<Test>>::AssetAR
+			);
+			// Nor through another receipt, the fee is not taken then.
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(bob()),
+					proof(
+						1,
+						vec![
+							nft_locked_log(nft_locker(), 2, BOB),
+							nft_locked_log(nft_locker(), 1, BOB),
+						]
+					)
+				),
+				<Error<Test>>::NftAE
+			);
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(bob()),
+					proof(
+						1,
+						vec![
+							nft_locked_log(nft_locker(), 2, BOB),
+							nft_locked_log(nft_locker(), 2, ALICE),
+						]
+					)
+				),
+				<Error<Test>>::NftAE
+			);
+			assert_eq!(NftBridge::owner_of((token(), U256::from(1))), Some(alice()));
+		});
+	}
+
+	#[test]
+	fn redeem_should_burn_the_nft_of_its_owner() {
+		new_test_ext().execute_with(|| {
+			let token_id = U256::from(1);
+			let ethereum_receiver = EthereumAddress::repeat_byte(0x55);
+
+			assert_ok!(NftBridge::mint(
+				Origin::signed(alice()),
+				proof(0, vec![nft_locked_log(nft_locker(), 1, ALICE)])
+			));
+
+			assert_noop!(
+				NftBridge::transfer(Origin::signed(bob()), bob(), token(), token_id),
+				<Error<Test>>::NotOwner
+			);
+			assert_ok!(NftBridge::transfer(
+				Origin::signed(alice()),
+				bob(),
+				token(),
+				token_id
+			));
+			assert_noop!(
+				NftBridge::redeem(
+					Origin::signed(alice()),
+					token(),
+					token_id,
+					ethereum_receiver
+				),
+				<Error<Test>>::NotOwner
+			);
+			assert_ok!(NftBridge::redeem(
+				Origin::signed(bob()),
+				token(),
+				token_id,
+				ethereum_receiver
+			));
+			assert_eq!(NftBridge::owner_of((token(), token_id)), None);
+			assert_eq!(NftBridge::redeem_nonce(), 1);
+			assert_noop!(
+				NftBridge::redeem(Origin::signed(bob()), token(), token_id, ethereum_receiver),
+				<Error<Test>>::NftNE
+			);
+			// A redeemed NFT is not minted again by its old receipt.
+			assert_noop!(
+				NftBridge::mint(
+					Origin::signed(alice()),
+					proof(0, vec![nft_locked_log(nft_locker(), 1, ALICE)])
+				),
+				<Error<Test>>::AssetAR
+			);
+		});
+	}
+}
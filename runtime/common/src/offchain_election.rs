@@ -0,0 +1,47 @@
+//! Node side tuning of the offchain phragmen election of `darwinia_staking`.
+//!
+//! The staking offchain worker mines a solution in the `ElectionLookahead` window of each era,
+//! weighting the votes by power, so RING and KTON are already both accounted for. The balancing
+//! iterations it runs are the runtime's `MaxIterations`. A validator can pick another value on
+//! the command line, which the node writes into the offchain persistent storage.
+//!
+//! The runtime's `MaxIterations` is also read outside of the offchain worker, e.g. for the module
+//! constants in the metadata, where the offchain storage can not be accessed. So the offchain
+//! worker entry point copies the node's value into a transient storage key with
+//! `with_node_settings`, and `MaxIterations` reads that key, falling back to the runtime's default.
+//! The offchain worker's state changes are never committed, hence the key never exists on chain.
+
+// --- crates ---
+use codec::Decode;
+// --- substrate ---
+use frame_support::traits::Get;
+use sp_core::offchain::StorageKind;
+use sp_std::marker::PhantomData;
+
+/// The offchain persistent storage key of the balancing iterations set by the node.
+pub const MAX_ITERATIONS_KEY: &[u8] = b"darwinia/staking-election/max-iterations";
+/// The transient storage key the offchain worker copies the node's settings to.
+const MAX_ITERATIONS_OVERRIDE_KEY: &[u8] = b":darwinia:staking-election:max-iterations:";
+
+/// Run the offchain worker `f` with the election settings of the node.
+///
+/// Must only be called in the offchain worker context.
+pub fn with_node_settings<R>(f: impl FnOnce() -> R) -> R {
+	if let Some(max_iterations) =
+		sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, MAX_ITERATIONS_KEY)
+	{
+		sp_io::storage::set(MAX_ITERATIONS_OVERRIDE_KEY, &max_iterations);
+	}
+
+	f()
+}
+
+/// The balancing iterations set by the node, `DefaultMaxIterations` if none.
+pub struct MaxIterations<DefaultMaxIterations>(PhantomData<DefaultMaxIterations>);
+impl<DefaultMaxIterations: Get<u32>> Get<u32> for MaxIterations<DefaultMaxIterations> {
+	fn get() -> u32 {
+		sp_io::storage::get(MAX_ITERATIONS_OVERRIDE_KEY)
+			.and_then(|max_iterations| u32::decode(&mut &max_iterations[..]).ok())
+			.unwrap_or_else(DefaultMaxIterations::get)
+	}
+}
@@ -0,0 +1,444 @@
+//! A relayer game adjustor reading its parameters from `relayer_game_params`, with a selectable
+//! sampling strategy.
+//!
+//! - `Linear` walks back one target chain block each round, which is what the Crab adjustor always
+//!   did.
+//! - `ExponentialBackoff` samples the `2 ^ (round - 1)` blocks right below the last sample each
+//!   round. The game does not know the last confirmed block, so this is the galloping half of a
+//!   bisection: a dispute going `d` blocks back is settled in about `log2(d)` rounds instead of
+//!   `d`. Each sample is bonded, and the last run may overshoot the dispute, so the relayers bond
+//!   up to twice as many samples as with `Linear`.
+//!
+//! Both keep the samples contiguous, so that each header of a round links to the header before
+//! it. The sampling only depends on the round, never on the parameters, so the parameters may be
+//! set in the middle of a game.
+
+// --- substrate ---
+use sp_runtime::traits::{AtLeast32BitUnsigned, One, SaturatedConversion, Saturating};
+use sp_std::{marker::PhantomData, prelude::*};
+// --- darwinia ---
+use crate::relayer_game_params::{self, RingBalance};
+use darwinia_relay_primitives::{AdjustableRelayerGame, Round};
+
+/// How the next samples are picked from the previous rounds' samples.
+pub trait SampleStrategy<TcBlockNumber> {
+	/// The samples of the next round, `samples` holds the samples of each previous round.
+	fn next_samples(samples: &[Vec<TcBlockNumber>]) -> Vec<TcBlockNumber>;
+
+	/// The samples of the rounds up to `round`, the proposed header included.
+	fn samples_count_of_round(round: Round) -> u64;
+
+	/// The round of a proposal of `samples_count` samples, the proposed header included.
+	fn round_of_samples_count(samples_count: u64) -> Round;
+}
+
+/// Sample the block right below the last sample.
+pub struct Linear;
+impl<TcBlockNumber> SampleStrategy<TcBlockNumber> for Linear
+where
+	TcBlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	fn next_samples(samples: &[Vec<TcBlockNumber>]) -> Vec<TcBlockNumber> {
+		vec![last_sample(samples).saturating_sub(One::one())]
+	}
+
+	fn samples_count_of_round(round: Round) -> u64 {
+		round + 1
+	}
+
+	fn round_of_samples_count(samples_count: u64) -> Round {
+		samples_count - 1
+	}
+}
+
+/// Sample the `2 ^ (round - 1)` blocks right below the last sample, down to the genesis.
+pub struct ExponentialBackoff;
+impl ExponentialBackoff {
+	// The blocks sampled in `round`, past the first round.
+	fn run_len(round: Round) -> u64 {
+		1u64.checked_shl(round.saturating_sub(1).min(64) as u32)
+			.unwrap_or(u64::max_value())
+	}
+}
+impl<TcBlockNumber> SampleStrategy<TcBlockNumber> for ExponentialBackoff
+where
+	TcBlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	fn next_samples(samples: &[Vec<TcBlockNumber>]) -> Vec<TcBlockNumber> {
+		let last_sample = last_sample(samples);
+		let run_len = Self::run_len(samples.len() as Round).min(last_sample.saturated_into());
+
+		(1..=run_len)
+			.map(|distance| last_sample - distance.saturated_into())
+			.collect()
+	}
+
+	fn samples_count_of_round(round: Round) -> u64 {
+		// The runs saturate past the 65th round.
+		(1..=round.min(65)).fold(1, |samples_count: u64, round| {
+			samples_count.saturating_add(Self::run_len(round))
+		})
+	}
+
+	fn round_of_samples_count(samples_count: u64) -> Round {
+		let mut round = 0;
+
+		while <Self as SampleStrategy<TcBlockNumber>>::samples_count_of_round(round) < samples_count
+		{
+			round += 1;
+		}
+
+		round
+	}
+}
+
+fn last_sample<TcBlockNumber: Copy>(samples: &[Vec<TcBlockNumber>]) -> TcBlockNumber {
+	*samples
+		.last()
+		.and_then(|round_samples| round_samples.last())
+		.expect("the first round always holds the proposed header; qed")
+}
+
+/// Play a relayer game with the parameters of `relayer_game_params::Module<T, I>`, sampling with
+/// `S`.
+pub struct RelayerGameAdjustor<T, I, TcBlockNumber, S>(PhantomData<(T, I, TcBlockNumber, S)>);
+impl<T, I, TcBlockNumber, S> AdjustableRelayerGame for RelayerGameAdjustor<T, I, TcBlockNumber, S>
+where
+	T: relayer_game_params::Trait<I>,
+	I: relayer_game_params::Instance,
+	TcBlockNumber: AtLeast32BitUnsigned + Copy,
+	S: SampleStrategy<TcBlockNumber>,
+{
+	type Moment = T::BlockNumber;
+	type Balance = RingBalance<T, I>;
+	type TcBlockNumber = TcBlockNumber;
+
+	fn challenge_time(round: Round) -> Self::Moment {
+		<relayer_game_params::Module<T, I>>::parameters().challenge_time_of(round)
+	}
+
+	fn round_of_samples_count(chain_len: u64) -> Round {
+		S::round_of_samples_count(chain_len)
+	}
+
+	fn samples_count_of_round(round: Round) -> u64 {
+		S::samples_count_of_round(round)
+	}
+
+	fn update_samples(samples: &mut Vec<Vec<Self::TcBlockNumber>>) {
+		samples.push(S::next_samples(samples));
+	}
+
+	fn estimate_bond(round: Round, proposals_count: u64) -> Self::Balance {
+		<relayer_game_params::Module<T, I>>::parameters().bond_of(round, proposals_count)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_ok, impl_outer_origin, parameter_types, traits::Get, weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{
+		relayer_game_params::{DefaultInstance, RelayerGameParameters},
+		AccountData, KtonInstance, RingInstance,
+	};
+
+	type Balance = u128;
+	type BlockNumber = u64;
+
+	const COIN: Balance = 1_000_000_000;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type RelayerGameParams = relayer_game_params::Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = BlockNumber;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const DefaultParameters: RelayerGameParameters<Balance, BlockNumber> =
+			RelayerGameParameters {
+				first_round_challenge_time: 30,
+				challenge_time: 10,
+				first_proposal_bond: 1000 * COIN,
+				competing_proposal_bond: 1500 * COIN,
+				round_bond: 100 * COIN,
+				confirm_period: 200,
+			};
+		pub const MinChallengeTime: BlockNumber = 5;
+		pub const MaxChallengeTime: BlockNumber = 14_400;
+		pub const MinBond: Balance = 1 * COIN;
+		pub const MinConfirmPeriod: BlockNumber = 10;
+		pub const MaxConfirmPeriod: BlockNumber = 100_800;
+	}
+	impl relayer_game_params::Trait for Test {
+		type Event = ();
+		type RingCurrency = Ring;
+		type DefaultParameters = DefaultParameters;
+		type MinChallengeTime = MinChallengeTime;
+		type MaxChallengeTime = MaxChallengeTime;
+		type MinBond = MinBond;
+		type MinConfirmPeriod = MinConfirmPeriod;
+		type MaxConfirmPeriod = MaxConfirmPeriod;
+		type SetOrigin = EnsureRoot<u64>;
+	}
+
+	type Adjustor<S> = RelayerGameAdjustor<Test, DefaultInstance, u64, S>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap()
+			.into()
+	}
+
+	/// Play a game between two relayers over `target`, until the samples reach a block which is
+	/// already confirmed. Returns the rounds played and the bonds locked by both relayers, each
+	/// of them bonding every sample of a round.
+	fn simulate<S: SampleStrategy<u64>>(
+		target: u64,
+		last_confirmed: u64,
+		parameters: RelayerGameParameters<Balance, BlockNumber>,
+	) -> (Round, Balance) {
+		let mut samples = vec![vec![target]];
+		let mut bonds = parameters.bond_of(0, 0) + parameters.bond_of(0, 1);
+
+		while last_sample(&samples) > last_confirmed {
+			samples.push(S::next_samples(&samples));
+
+			let round = samples.len() as Round - 1;
+			let round_samples_count = samples[round as usize].len() as Balance;
+
+			bonds +=
+				(parameters.bond_of(round, 0) + parameters.bond_of(round, 1)) * round_samples_count;
+		}
+
+		(samples.len() as Round - 1, bonds)
+	}
+
+	/// Play four rounds over the block 1000, setting the parameters after the second one.
+	fn play_with_parameters_set_mid_game<S: SampleStrategy<u64>>() {
+		new_test_ext().execute_with(|| {
+			let mut samples = vec![vec![1000u64]];
+
+			for _ in 0..2 {
+				<Adjustor<S>>::update_samples(&mut samples);
+			}
+
+			let proposed_samples_count = samples.concat().len() as u64;
+
+			assert_eq!(
+				<Adjustor<S>>::round_of_samples_count(proposed_samples_count),
+				2
+			);
+			assert_eq!(<Adjustor<S>>::estimate_bond(2, 0), 100 * COIN);
+
+			assert_ok!(RelayerGameParams::set_parameters(
+				Origin::root(),
+				RelayerGameParameters {
+					first_round_challenge_time: 60,
+					challenge_time: 20,
+					first_proposal_bond: 2000 * COIN,
+					competing_proposal_bond: 3000 * COIN,
+					round_bond: 200 * COIN,
+					confirm_period: 400,
+				}
+			));
+
+			for _ in 0..2 {
+				<Adjustor<S>>::update_samples(&mut samples);
+			}
+
+			let chain = samples.concat();
+
+			// The samples taken before and after still link.
+			assert!(chain.windows(2).all(|pair| pair[1] + 1 == pair[0]));
+			// The proposals made before still map to their rounds.
+			assert_eq!(
+				<Adjustor<S>>::round_of_samples_count(proposed_samples_count),
+				2
+			);
+			assert_eq!(
+				<Adjustor<S>>::samples_count_of_round(2),
+				proposed_samples_count
+			);
+			assert_eq!(<Adjustor<S>>::round_of_samples_count(chain.len() as u64), 4);
+			// The next rounds are played with the new parameters.
+			assert_eq!(<Adjustor<S>>::challenge_time(5), 20);
+			assert_eq!(<Adjustor<S>>::estimate_bond(5, 0), 200 * COIN);
+			assert_eq!(<relayer_game_params::ConfirmPeriod<Test>>::get(), 400);
+		});
+	}
+
+	#[test]
+	fn linear_should_walk_back_one_block_per_round() {
+		assert_eq!(
+			simulate::<Linear>(1000, 900, DefaultParameters::get()),
+			(100, 22_500 * COIN)
+		);
+	}
+
+	#[test]
+	fn exponential_backoff_should_double_the_distance_each_round() {
+		let mut samples = vec![vec![1000u64]];
+
+		for _ in 0..5 {
+			samples.push(ExponentialBackoff::next_samples(&samples));
+		}
+
+		assert_eq!(
+			samples
+				.iter()
+				.map(|round_samples| *round_samples.last().unwrap())
+				.collect::<Vec<_>>(),
+			vec![1000, 999, 997, 993, 985, 969]
+		);
+		// 1 + 2 + 4 + ... + 64 = 127 >= 100
+		assert_eq!(
+			simulate::<ExponentialBackoff>(1000, 900, DefaultParameters::get()),
+			(7, 27_900 * COIN)
+		);
+	}
+
+	#[test]
+	fn exponential_backoff_should_play_fewer_rounds_for_less_than_twice_the_bonds() {
+		let parameters = DefaultParameters::get();
+		let first_round_bonds = parameters.bond_of(0, 0) + parameters.bond_of(0, 1);
+
+		for &distance in &[1, 2, 3, 10, 100, 1_000, 100_000] {
+			let target = 1_000_000;
+			let last_confirmed = target - distance;
+			let (linear_rounds, linear_bonds) =
+				simulate::<Linear>(target, last_confirmed, parameters);
+			let (backoff_rounds, backoff_bonds) =
+				simulate::<ExponentialBackoff>(target, last_confirmed, parameters);
+
+			assert!(backoff_rounds <= linear_rounds);
+			// The runs sample `2 ^ rounds - 1` blocks, which is less than twice the distance.
+			assert!(backoff_bonds - first_round_bonds < 2 * (linear_bonds - first_round_bonds));
+		}
+
+		assert_eq!(
+			simulate::<Linear>(1_000_000, 900_000, DefaultParameters::get()).0,
+			100_000
+		);
+		assert_eq!(
+			simulate::<ExponentialBackoff>(1_000_000, 900_000, DefaultParameters::get()).0,
+			17
+		);
+	}
+
+	#[test]
+	fn exponential_backoff_should_sample_contiguously() {
+		let mut samples = vec![vec![1_000_000u64]];
+
+		for round in 1..10 {
+			samples.push(ExponentialBackoff::next_samples(&samples));
+
+			let chain = samples.concat();
+			let samples_count = chain.len() as u64;
+
+			// Each sample is the parent of the previous one.
+			assert!(chain.windows(2).all(|pair| pair[1] + 1 == pair[0]));
+			assert_eq!(
+				<ExponentialBackoff as SampleStrategy<u64>>::samples_count_of_round(round),
+				samples_count
+			);
+			assert_eq!(
+				<ExponentialBackoff as SampleStrategy<u64>>::round_of_samples_count(samples_count),
+				round
+			);
+		}
+	}
+
+	#[test]
+	fn samples_should_stop_at_genesis() {
+		assert_eq!(
+			simulate::<Linear>(3, 0, DefaultParameters::get()),
+			(3, 3_100 * COIN)
+		);
+		assert_eq!(
+			simulate::<ExponentialBackoff>(3, 0, DefaultParameters::get()),
+			(2, 3_100 * COIN)
+		);
+	}
+
+	#[test]
+	fn parameters_set_mid_game_should_not_break_the_game() {
+		play_with_parameters_set_mid_game::<Linear>();
+		play_with_parameters_set_mid_game::<ExponentialBackoff>();
+	}
+}
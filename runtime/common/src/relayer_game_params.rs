@@ -0,0 +1,194 @@
+//! On-chain parameters of a relayer game, one instance per relayer game.
+//!
+//! The challenge times, the bonds and the confirm period used to be hardcoded in the runtime's
+//! adjustor. They are stored here so that the game economics can be tuned by `SetOrigin` without a
+//! runtime upgrade, within the bounds given by the runtime.
+//!
+//! The games in progress read the new parameters as they go: a challenge time applies to the
+//! rounds opened afterwards, a bond to the proposals made afterwards and the confirm period to the
+//! headers settled afterwards. The sampling is left to the adjustor: the blocks a game samples must
+//! not change in the middle of the game, or its samples would stop linking to each other.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{Currency, EnsureOrigin, Get},
+};
+use sp_runtime::RuntimeDebug;
+
+pub type RingBalance<T, I> =
+	<<T as Trait<I>>::RingCurrency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type RelayerGameParametersOf<T, I> =
+	RelayerGameParameters<RingBalance<T, I>, <T as frame_system::Trait>::BlockNumber>;
+
+pub trait Trait<I: Instance = DefaultInstance>: frame_system::Trait {
+	type Event: From<Event<Self, I>> + Into<<Self as frame_system::Trait>::Event>;
+
+	type RingCurrency: Currency<Self::AccountId>;
+
+	/// The parameters in use until they are set for the first time.
+	type DefaultParameters: Get<RelayerGameParametersOf<Self, I>>;
+
+	/// The minimum challenge time of a round.
+	type MinChallengeTime: Get<Self::BlockNumber>;
+	/// The maximum challenge time of a round.
+	type MaxChallengeTime: Get<Self::BlockNumber>;
+	/// The minimum of each bond.
+	type MinBond: Get<RingBalance<Self, I>>;
+	/// The minimum confirm period of a relay proposal.
+	type MinConfirmPeriod: Get<Self::BlockNumber>;
+	/// The maximum confirm period of a relay proposal.
+	type MaxConfirmPeriod: Get<Self::BlockNumber>;
+
+	/// The origin which may set the parameters.
+	type SetOrigin: EnsureOrigin<Self::Origin>;
+}
+
+/// The economics of a relayer game.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct RelayerGameParameters<Balance, BlockNumber> {
+	/// The challenge time of the first round.
+	pub first_round_challenge_time: BlockNumber,
+	/// The challenge time of the following rounds.
+	pub challenge_time: BlockNumber,
+	/// The bond of the first proposal of the first round.
+	pub first_proposal_bond: Balance,
+	/// The bond of the competing proposals of the first round.
+	pub competing_proposal_bond: Balance,
+	/// The bond of each proposal of the following rounds.
+	pub round_bond: Balance,
+	/// The period a finished relay proposal waits before being confirmed.
+	pub confirm_period: BlockNumber,
+}
+impl<Balance, BlockNumber> RelayerGameParameters<Balance, BlockNumber>
+where
+	Balance: Copy,
+	BlockNumber: Copy,
+{
+	/// The challenge time of `round`.
+	pub fn challenge_time_of(&self, round: u64) -> BlockNumber {
+		match round {
+			0 => self.first_round_challenge_time,
+			_ => self.challenge_time,
+		}
+	}
+
+	/// The bond of a proposal of `round`, where `proposals_count` proposals were already made.
+	pub fn bond_of(&self, round: u64, proposals_count: u64) -> Balance {
+		match round {
+			0 => match proposals_count {
+				0 => self.first_proposal_bond,
+				_ => self.competing_proposal_bond,
+			},
+			_ => self.round_bond,
+		}
+	}
+}
+
+decl_event! {
+	pub enum Event<T, I: Instance = DefaultInstance>
+	where
+		RelayerGameParameters = RelayerGameParametersOf<T, I>,
+	{
+		/// The relayer game parameters were set. [new parameters]
+		ParametersSet(RelayerGameParameters),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait<I>, I: Instance> {
+		/// Challenge Time - OUT OF BOUNDS
+		ChallengeTimeOutOfBounds,
+		/// Bond - TOO LOW
+		BondTooLow,
+		/// Competing Proposal Bond - LOWER THAN FIRST PROPOSAL BOND
+		CompetingBondTooLow,
+		/// Confirm Period - OUT OF BOUNDS
+		ConfirmPeriodOutOfBounds,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait<I>, I: Instance = DefaultInstance> as DarwiniaRelayerGameParams {
+		/// The parameters of the relayer game.
+		pub Parameters get(fn parameters): RelayerGameParametersOf<T, I> = T::DefaultParameters::get();
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait<I>, I: Instance = DefaultInstance> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T, I>;
+
+		const MinChallengeTime: T::BlockNumber = T::MinChallengeTime::get();
+
+		const MaxChallengeTime: T::BlockNumber = T::MaxChallengeTime::get();
+
+		const MinBond: RingBalance<T, I> = T::MinBond::get();
+
+		const MinConfirmPeriod: T::BlockNumber = T::MinConfirmPeriod::get();
+
+		const MaxConfirmPeriod: T::BlockNumber = T::MaxConfirmPeriod::get();
+
+		fn deposit_event() = default;
+
+		/// Set the relayer game parameters.
+		///
+		/// The dispatch origin for this call must be `SetOrigin`.
+		///
+		/// The games in progress keep their proposals and their bonds, and play their next rounds
+		/// with the new parameters.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_parameters(origin, parameters: RelayerGameParametersOf<T, I>) {
+			T::SetOrigin::ensure_origin(origin)?;
+
+			Self::ensure_parameters_valid(&parameters)?;
+
+			<Parameters<T, I>>::put(parameters);
+
+			Self::deposit_event(RawEvent::ParametersSet(parameters));
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	fn ensure_parameters_valid(
+		parameters: &RelayerGameParametersOf<T, I>,
+	) -> Result<(), Error<T, I>> {
+		let challenge_time_bounds = T::MinChallengeTime::get()..=T::MaxChallengeTime::get();
+		let min_bond = T::MinBond::get();
+
+		ensure!(
+			challenge_time_bounds.contains(&parameters.first_round_challenge_time)
+				&& challenge_time_bounds.contains(&parameters.challenge_time),
+			<Error<T, I>>::ChallengeTimeOutOfBounds
+		);
+		ensure!(
+			parameters.first_proposal_bond >= min_bond && parameters.round_bond >= min_bond,
+			<Error<T, I>>::BondTooLow
+		);
+		ensure!(
+			parameters.competing_proposal_bond >= parameters.first_proposal_bond,
+			<Error<T, I>>::CompetingBondTooLow
+		);
+		ensure!(
+			(T::MinConfirmPeriod::get()..=T::MaxConfirmPeriod::get())
+				.contains(&parameters.confirm_period),
+			<Error<T, I>>::ConfirmPeriodOutOfBounds
+		);
+
+		Ok(())
+	}
+}
+
+/// The confirm period in storage, to be passed as the relayer game's `ConfirmPeriod`.
+pub struct ConfirmPeriod<T, I = DefaultInstance>(sp_std::marker::PhantomData<(T, I)>);
+impl<T: Trait<I>, I: Instance> Get<T::BlockNumber> for ConfirmPeriod<T, I> {
+	fn get() -> T::BlockNumber {
+		<Module<T, I>>::parameters().confirm_period
+	}
+}
@@ -0,0 +1,210 @@
+//! The agenda of `pallet_scheduler`, for announcing the upcoming dispatches ahead of time.
+
+pub use darwinia_rpc_runtime_api::scheduler::ScheduledTask;
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{storage::IterableStorageMap, traits::schedule::Priority};
+use sp_runtime::traits::Saturating;
+use sp_std::prelude::*;
+
+/// The leading fields of `pallet_scheduler::Scheduled`, which are private: the name, the
+/// priority, the call and the period. The origin which follows is left out.
+type ScheduledPrefix<T> = (
+	Option<Vec<u8>>,
+	Priority,
+	<T as pallet_scheduler::Trait>::Call,
+	Option<(<T as frame_system::Trait>::BlockNumber, u32)>,
+);
+
+/// The tasks dispatched in the `blocks` blocks following the current one, the soonest first.
+pub fn agenda<T: pallet_scheduler::Trait>(
+	blocks: T::BlockNumber,
+) -> Vec<ScheduledTask<T::BlockNumber>> {
+	let now = <frame_system::Module<T>>::block_number();
+	let until = now.saturating_add(blocks);
+	let mut agenda = <pallet_scheduler::Agenda<T>>::iter()
+		.filter(|(when, _)| *when > now && *when <= until)
+		.flat_map(|(when, tasks)| {
+			tasks
+				.into_iter()
+				.enumerate()
+				.filter_map(move |(index, task)| {
+					let (id, priority, call, maybe_periodic) =
+						<ScheduledPrefix<T>>::decode(&mut &task?.encode()[..]).ok()?;
+
+					Some(ScheduledTask {
+						when,
+						index: index as u32,
+						id,
+						priority,
+						maybe_periodic,
+						call: call.encode(),
+					})
+				})
+		})
+		.collect::<Vec<_>>();
+
+	agenda.sort_by_key(|task| (task.when, task.index));
+
+	agenda
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types, weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			frame_system::System,
+		}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Scheduler = pallet_scheduler::Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	impl pallet_scheduler::Trait for Test {
+		type Event = ();
+		type Origin = Origin;
+		type PalletsOrigin = OriginCaller;
+		type Call = Call;
+		type MaximumWeight = MaximumBlockWeight;
+		type ScheduleOrigin = EnsureRoot<u64>;
+		type WeightInfo = ();
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	fn remark(n: u8) -> Call {
+		Call::System(frame_system::Call::remark(vec![n]))
+	}
+
+	// `ScheduledPrefix` mirrors the private layout of `pallet_scheduler::Scheduled`, which an
+	// upgrade of `pallet_scheduler` could change: the tasks would then silently drop out.
+	#[test]
+	fn agenda_should_decode_the_scheduled_tasks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Scheduler::schedule_named(
+				Origin::root(),
+				b"periodic".to_vec(),
+				5,
+				Some((3, 2)),
+				7,
+				Box::new(remark(1)),
+			));
+			assert_ok!(Scheduler::schedule(
+				Origin::root(),
+				4,
+				None,
+				1,
+				Box::new(remark(2)),
+			));
+			assert_ok!(Scheduler::schedule(
+				Origin::root(),
+				5,
+				None,
+				0,
+				Box::new(remark(3)),
+			));
+			assert_ok!(Scheduler::schedule(
+				Origin::root(),
+				12,
+				None,
+				0,
+				Box::new(remark(4)),
+			));
+
+			assert_eq!(
+				agenda::<Test>(10),
+				vec![
+					ScheduledTask {
+						when: 4,
+						index: 0,
+						id: None,
+						priority: 1,
+						maybe_periodic: None,
+						call: remark(2).encode(),
+					},
+					ScheduledTask {
+						when: 5,
+						index: 0,
+						id: Some(b"periodic".to_vec()),
+						priority: 7,
+						maybe_periodic: Some((3, 2)),
+						call: remark(1).encode(),
+					},
+					ScheduledTask {
+						when: 5,
+						index: 1,
+						id: None,
+						priority: 0,
+						maybe_periodic: None,
+						call: remark(3).encode(),
+					},
+				]
+			);
+		});
+	}
+}
@@ -0,0 +1,493 @@
+//! The way out of `pallet_sudo`.
+//!
+//! A call approved by both the council and the technical committee within `ApprovalPeriod` is
+//! dispatched as _Root_, so every root gated operation stays reachable once the sudo key is gone.
+//! Retiring the sudo key is itself such a call: `retire_sudo` hands the key over to the default
+//! account, which nobody holds the private key of, through `Sudo::set_key`. Only the sudo key can
+//! set it again, so it is gone for good.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResultWithPostInfo,
+	ensure,
+	traits::{EnsureOrigin, Get, UnfilteredDispatchable},
+	weights::{extract_actual_weight, GetDispatchInfo},
+	Parameter,
+};
+use frame_system::{ensure_root, RawOrigin};
+use sp_runtime::{
+	traits::{Dispatchable, Hash, Saturating, StaticLookup},
+	DispatchResult, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub type RootCallApprovalOf<T> = RootCallApproval<<T as frame_system::Trait>::BlockNumber>;
+
+pub trait Trait: pallet_sudo::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	type Call: Parameter + Dispatchable<Origin = Self::Origin> + GetDispatchInfo;
+
+	/// The council origin which approves the root calls.
+	type CouncilOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The technical committee origin which approves the root calls.
+	type TechnicalCommitteeOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The blocks the other collective has to approve a root call, from its first approval.
+	type ApprovalPeriod: Get<Self::BlockNumber>;
+}
+
+/// The collectives which approved a root call.
+#[derive(Clone, Default, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct RootCallApproval<BlockNumber> {
+	/// Approved by the council.
+	pub council: bool,
+	/// Approved by the technical committee.
+	pub technical_committee: bool,
+	/// The last block the call can be dispatched at, after which its approvals start over.
+	pub expires_at: BlockNumber,
+}
+
+decl_event! {
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Hash = <T as frame_system::Trait>::Hash,
+		RootCallApproval = RootCallApprovalOf<T>,
+	{
+		/// A root call was approved by one of the collectives. [call hash, approval]
+		RootCallApproved(Hash, RootCallApproval),
+		/// A root call was approved by both collectives and dispatched. [call hash, result]
+		RootCallDispatched(Hash, DispatchResult),
+		/// The approvals of a root call were cancelled. [call hash]
+		RootCallCancelled(Hash),
+		/// The sudo key was removed for good. [former sudo key]
+		SudoRetired(AccountId),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Origin - NOT A COLLECTIVE
+		NotCollective,
+		/// Root Call - ALREADY APPROVED
+		RootCallAA,
+		/// Root Call - NOT EXISTED
+		RootCallNE,
+		/// Sudo - ALREADY RETIRED
+		SudoAR,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DarwiniaSudoRetirement {
+		/// The approvals of the root calls, by call hash.
+		pub RootCallApprovals get(fn root_call_approval):
+			map hasher(identity) T::Hash => Option<RootCallApprovalOf<T>>;
+
+		/// Whether the sudo key was retired.
+		pub SudoRetired get(fn sudo_retired): bool;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Approve dispatching `call` as _Root_. The call is dispatched as soon as both the
+		/// council and the technical committee approved it, within `ApprovalPeriod` of the first
+		/// approval. An expired approval is dropped, and the call is approved anew.
+		///
+		/// The dispatch origin for this call must be `CouncilOrigin` or
+		/// `TechnicalCommitteeOrigin`.
+		///
+		/// The weight of `call` is only charged by the approval which dispatches it.
+		#[weight = {
+			let dispatch_info = call.get_dispatch_info();
+
+			(
+				dispatch_info.weight.saturating_add(T::DbWeight::get().reads_writes(1, 1)),
+				dispatch_info.class,
+			)
+		}]
+		pub fn approve_root_call(
+			origin,
+			call: Box<<T as Trait>::Call>,
+		) -> DispatchResultWithPostInfo {
+			let by_council = Self::ensure_collective(origin)?;
+			let call_hash = T::Hashing::hash_of(&call);
+			let now = <frame_system::Module<T>>::block_number();
+			let mut approval = match Self::root_call_approval(call_hash) {
+				Some(approval) if approval.expires_at >= now => approval,
+				_ => RootCallApproval {
+					expires_at: now.saturating_add(T::ApprovalPeriod::get()),
+					..Default::default()
+				},
+			};
+
+			if by_council {
+				ensure!(!approval.council, <Error<T>>::RootCallAA);
+
+				approval.council = true;
+			} else {
+				ensure!(!approval.technical_committee, <Error<T>>::RootCallAA);
+
+				approval.technical_committee = true;
+			}
+
+			let approval_weight = T::DbWeight::get().reads_writes(1, 1);
+
+			if approval.council && approval.technical_committee {
+				<RootCallApprovals<T>>::remove(call_hash);
+
+				let dispatch_info = call.get_dispatch_info();
+				let result = call.dispatch(RawOrigin::Root.into());
+				let call_weight = extract_actual_weight(&result, &dispatch_info);
+
+				Self::deposit_event(RawEvent::RootCallDispatched(
+					call_hash,
+					result.map(|_| ()).map_err(|e| e.error),
+				));
+
+				Ok(Some(call_weight.saturating_add(approval_weight)).into())
+			} else {
+				<RootCallApprovals<T>>::insert(call_hash, &approval);
+
+				Self::deposit_event(RawEvent::RootCallApproved(call_hash, approval));
+
+				Ok(Some(approval_weight).into())
+			}
+		}
+
+		/// Cancel the approvals of a root call which is not dispatched yet.
+		///
+		/// The dispatch origin for this call must be `CouncilOrigin` or
+		/// `TechnicalCommitteeOrigin`.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		pub fn cancel_root_call(origin, call_hash: T::Hash) {
+			Self::ensure_collective(origin)?;
+
+			ensure!(<RootCallApprovals<T>>::contains_key(call_hash), <Error<T>>::RootCallNE);
+
+			<RootCallApprovals<T>>::remove(call_hash);
+
+			Self::deposit_event(RawEvent::RootCallCancelled(call_hash));
+		}
+
+		/// Remove the sudo key for good.
+		///
+		/// The dispatch origin for this call must be _Root_, i.e. the sudo key itself or a root
+		/// call approved by both collectives.
+		#[weight = T::DbWeight::get().reads_writes(2, 2)]
+		pub fn retire_sudo(origin) {
+			ensure_root(origin)?;
+
+			ensure!(!Self::sudo_retired(), <Error<T>>::SudoAR);
+
+			let sudo_key = <pallet_sudo::Module<T>>::key();
+
+			pallet_sudo::Call::<T>::set_key(T::Lookup::unlookup(Default::default()))
+				.dispatch_bypass_filter(RawOrigin::Signed(sudo_key.clone()).into())
+				.map_err(|e| e.error)?;
+
+			<SudoRetired>::put(true);
+
+			Self::deposit_event(RawEvent::SudoRetired(sudo_key));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Ensure `origin` is one of the collectives, `true` if it is the council.
+	fn ensure_collective(origin: T::Origin) -> Result<bool, Error<T>> {
+		match T::CouncilOrigin::try_origin(origin) {
+			Ok(_) => Ok(true),
+			Err(origin) => T::TechnicalCommitteeOrigin::try_origin(origin)
+				.map(|_| false)
+				.map_err(|_| <Error<T>>::NotCollective),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+		traits::Contains, weights::Weight,
+	};
+	use frame_system::EnsureSignedBy;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError, Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::sudo_retirement;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			frame_system::System,
+			pallet_sudo::Sudo,
+			sudo_retirement::SudoRetirement,
+		}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Sudo = pallet_sudo::Module<Test>;
+	type SudoRetirement = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	impl pallet_sudo::Trait for Test {
+		type Event = ();
+		type Call = Call;
+	}
+
+	const SUDO_KEY: u64 = 42;
+	const COUNCIL: u64 = 1;
+	const TECHNICAL_COMMITTEE: u64 = 2;
+	const APPROVAL_PERIOD: u64 = 10;
+
+	/// Stands for the council motions.
+	pub struct Council;
+	impl Contains<u64> for Council {
+		fn sorted_members() -> Vec<u64> {
+			vec![COUNCIL]
+		}
+	}
+
+	/// Stands for the technical committee motions.
+	pub struct TechnicalCommittee;
+	impl Contains<u64> for TechnicalCommittee {
+		fn sorted_members() -> Vec<u64> {
+			vec![TECHNICAL_COMMITTEE]
+		}
+	}
+
+	parameter_types! {
+		pub const ApprovalPeriod: u64 = APPROVAL_PERIOD;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type CouncilOrigin = EnsureSignedBy<Council, u64>;
+		type TechnicalCommitteeOrigin = EnsureSignedBy<TechnicalCommittee, u64>;
+		type ApprovalPeriod = ApprovalPeriod;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		pallet_sudo::GenesisConfig::<Test> { key: SUDO_KEY }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+
+		let mut ext = sp_io::TestExternalities::from(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	fn retire_sudo() -> Box<Call> {
+		Box::new(Call::SudoRetirement(sudo_retirement::Call::retire_sudo()))
+	}
+
+	fn retire_sudo_hash() -> H256 {
+		BlakeTwo256::hash_of(&retire_sudo())
+	}
+
+	#[test]
+	fn root_call_should_be_dispatched_once_both_collectives_approve() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(COUNCIL),
+				retire_sudo()
+			));
+			assert_eq!(
+				SudoRetirement::root_call_approval(retire_sudo_hash()),
+				Some(RootCallApproval {
+					council: true,
+					technical_committee: false,
+					expires_at: 1 + APPROVAL_PERIOD,
+				})
+			);
+			assert_eq!(Sudo::key(), SUDO_KEY);
+
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(TECHNICAL_COMMITTEE),
+				retire_sudo()
+			));
+			assert_eq!(SudoRetirement::root_call_approval(retire_sudo_hash()), None);
+			assert_eq!(Sudo::key(), 0);
+			assert!(SudoRetirement::sudo_retired());
+		});
+	}
+
+	#[test]
+	fn retired_sudo_key_should_not_sudo() {
+		new_test_ext().execute_with(|| {
+			let sudo_remark = Call::Sudo(pallet_sudo::Call::sudo(Box::new(Call::System(
+				frame_system::Call::remark(vec![]),
+			))));
+
+			assert_ok!(sudo_remark.clone().dispatch(Origin::signed(SUDO_KEY)));
+			assert_ok!(SudoRetirement::retire_sudo(Origin::root()));
+			assert_noop!(
+				sudo_remark.dispatch(Origin::signed(SUDO_KEY)),
+				pallet_sudo::Error::<Test>::RequireSudo
+			);
+			assert_noop!(
+				SudoRetirement::retire_sudo(Origin::root()),
+				<Error<Test>>::SudoAR
+			);
+		});
+	}
+
+	#[test]
+	fn approve_root_call_should_only_accept_each_collective_once() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				SudoRetirement::approve_root_call(Origin::signed(SUDO_KEY), retire_sudo()),
+				<Error<Test>>::NotCollective
+			);
+			assert_noop!(
+				SudoRetirement::approve_root_call(Origin::root(), retire_sudo()),
+				<Error<Test>>::NotCollective
+			);
+			assert_noop!(
+				SudoRetirement::retire_sudo(Origin::signed(COUNCIL)),
+				DispatchError::BadOrigin
+			);
+
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(TECHNICAL_COMMITTEE),
+				retire_sudo()
+			));
+			assert_noop!(
+				SudoRetirement::approve_root_call(
+					Origin::signed(TECHNICAL_COMMITTEE),
+					retire_sudo()
+				),
+				<Error<Test>>::RootCallAA
+			);
+			assert_eq!(Sudo::key(), SUDO_KEY);
+		});
+	}
+
+	#[test]
+	fn expired_approval_should_start_over() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(COUNCIL),
+				retire_sudo()
+			));
+
+			System::set_block_number(2 + APPROVAL_PERIOD);
+
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(TECHNICAL_COMMITTEE),
+				retire_sudo()
+			));
+			assert_eq!(Sudo::key(), SUDO_KEY);
+			assert_eq!(
+				SudoRetirement::root_call_approval(retire_sudo_hash()),
+				Some(RootCallApproval {
+					council: false,
+					technical_committee: true,
+					expires_at: 2 + 2 * APPROVAL_PERIOD,
+				})
+			);
+
+			System::set_block_number(2 + 2 * APPROVAL_PERIOD);
+
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(COUNCIL),
+				retire_sudo()
+			));
+			assert_eq!(Sudo::key(), 0);
+		});
+	}
+
+	#[test]
+	fn cancel_root_call_should_drop_its_approvals() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				SudoRetirement::cancel_root_call(Origin::signed(COUNCIL), retire_sudo_hash()),
+				<Error<Test>>::RootCallNE
+			);
+
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(COUNCIL),
+				retire_sudo()
+			));
+			assert_noop!(
+				SudoRetirement::cancel_root_call(Origin::signed(SUDO_KEY), retire_sudo_hash()),
+				<Error<Test>>::NotCollective
+			);
+			assert_ok!(SudoRetirement::cancel_root_call(
+				Origin::signed(TECHNICAL_COMMITTEE),
+				retire_sudo_hash()
+			));
+			assert_eq!(SudoRetirement::root_call_approval(retire_sudo_hash()), None);
+
+			assert_ok!(SudoRetirement::approve_root_call(
+				Origin::signed(TECHNICAL_COMMITTEE),
+				retire_sudo()
+			));
+			assert_eq!(Sudo::key(), SUDO_KEY);
+		});
+	}
+}
@@ -0,0 +1,676 @@
+//! Vesting of the RING and KTON balances.
+//!
+//! A port of `pallet_vesting` which is instantiable, one instance per currency, and locks through
+//! the Darwinia `LockableCurrency`. The vesting lock only restricts transfers and reserves, so it
+//! overlaps with the staking lock and the still-vesting funds can be bonded.
+
+// --- crates ---
+use codec::{Decode, Encode};
+// --- substrate ---
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{
+		Currency, EnsureOrigin, ExistenceRequirement, Get, LockIdentifier, WithdrawReason,
+		WithdrawReasons,
+	},
+	weights::Weight,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Convert, One, Saturating, StaticLookup, Zero},
+	DispatchResult, RuntimeDebug,
+};
+// --- darwinia ---
+use darwinia_support::balance::lock::{LockFor, LockableCurrency};
+
+pub type BalanceOf<T, I> =
+	<<T as Trait<I>>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+const VESTING_ID: LockIdentifier = *b"vesting ";
+
+pub trait WeightInfo {
+	fn vest() -> Weight;
+	fn vest_other() -> Weight;
+	fn vested_transfer() -> Weight;
+	fn force_vested_transfer() -> Weight;
+}
+impl WeightInfo for () {
+	fn vest() -> Weight {
+		50_000_000
+	}
+	fn vest_other() -> Weight {
+		50_000_000
+	}
+	fn vested_transfer() -> Weight {
+		100_000_000
+	}
+	fn force_vested_transfer() -> Weight {
+		100_000_000
+	}
+}
+
+pub trait Trait<I: Instance = DefaultInstance>: frame_system::Trait {
+	type Event: From<Event<Self, I>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The currency trait.
+	type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+	/// Convert the block number into a balance.
+	type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self, I>>;
+
+	/// The minimum amount transferred to call `vested_transfer`.
+	type MinVestedTransfer: Get<BalanceOf<Self, I>>;
+
+	/// The origin which may create a vesting schedule from any account.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	type WeightInfo: WeightInfo;
+}
+
+/// Struct to encode the vesting schedule of an individual account.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingInfo<Balance, BlockNumber> {
+	/// Locked amount at genesis.
+	pub locked: Balance,
+	/// Amount that gets unlocked every block after `starting_block`.
+	pub per_block: Balance,
+	/// Starting block for unlocking(vesting).
+	pub starting_block: BlockNumber,
+}
+impl<Balance, BlockNumber> VestingInfo<Balance, BlockNumber>
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	/// Amount locked at block `n`.
+	pub fn locked_at<BlockNumberToBalance>(&self, n: BlockNumber) -> Balance
+	where
+		BlockNumberToBalance: Convert<BlockNumber, Balance>,
+	{
+		// Number of blocks that count toward vesting
+		// Saturating to 0 when n < starting_block
+		let vested_block_count = n.saturating_sub(self.starting_block);
+		let vested_block_count = BlockNumberToBalance::convert(vested_block_count);
+		// Return amount that is still locked in vesting
+		let maybe_balance = vested_block_count.checked_mul(&self.per_block);
+
+		if let Some(balance) = maybe_balance {
+			self.locked.saturating_sub(balance)
+		} else {
+			Zero::zero()
+		}
+	}
+}
+
+decl_event! {
+	pub enum Event<T, I: Instance = DefaultInstance>
+	where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T, I>,
+	{
+		/// The amount vested has been updated. This could indicate more funds are available. The
+		/// balance given is the amount which is left unvested (and thus locked). [account, unvested]
+		VestingUpdated(AccountId, Balance),
+		/// An account has become fully vested. No further vesting can happen. [account]
+		VestingCompleted(AccountId),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait<I>, I: Instance> {
+		/// Vesting Schedule - NOT EXISTED
+		NotVesting,
+		/// Vesting Schedule - ALREADY EXISTED
+		ExistingVestingSchedule,
+		/// Vested Transfer - AMOUNT TOO LOW
+		AmountLow,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait<I>, I: Instance = DefaultInstance> as DarwiniaVesting {
+		/// Information regarding the vesting of a given account.
+		pub Vesting get(fn vesting):
+			map hasher(blake2_128_concat) T::AccountId
+			=> Option<VestingInfo<BalanceOf<T, I>, T::BlockNumber>>;
+	}
+	add_extra_genesis {
+		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T, I>)>;
+		build(|config: &GenesisConfig<T, I>| {
+			// Generate initial vesting configuration
+			// * who - Account which we are generating vesting configuration for
+			// * begin - Block when the account will start to vest
+			// * length - Number of blocks from `begin` until fully vested
+			// * liquid - Number of units which can be spent before vesting begins
+			for &(ref who, begin, length, liquid) in config.vesting.iter() {
+				let balance = T::Currency::free_balance(who);
+				assert!(!balance.is_zero(), "Currencies must be init'd before vesting");
+				// Total genesis `balance` minus `liquid` equals funds locked for vesting
+				let locked = balance.saturating_sub(liquid);
+				let length_as_balance = T::BlockNumberToBalance::convert(length);
+				let per_block = locked / length_as_balance.max(One::one());
+
+				<Vesting<T, I>>::insert(who, VestingInfo {
+					locked,
+					per_block,
+					starting_block: begin
+				});
+				T::Currency::set_lock(
+					VESTING_ID,
+					who,
+					LockFor::Common { amount: locked },
+					vesting_reasons(),
+				);
+			}
+		})
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait<I>, I: Instance = DefaultInstance> for enum Call
+	where
+		origin: T::Origin
+	{
+		type Error = Error<T, I>;
+
+		/// The minimum amount to be transferred to create a new vesting schedule.
+		const MinVestedTransfer: BalanceOf<T, I> = T::MinVestedTransfer::get();
+
+		fn deposit_event() = default;
+
+		/// Unlock any vested funds of the sender account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this module.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		#[weight = T::WeightInfo::vest()]
+		pub fn vest(origin) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::update_lock(who)
+		}
+
+		/// Unlock any vested funds of a `target` account.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: The account whose vested funds should be unlocked. Must have funds still
+		/// locked under this module.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		#[weight = T::WeightInfo::vest_other()]
+		pub fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			Self::update_lock(T::Lookup::lookup(target)?)
+		}
+
+		/// Create a vested transfer.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestingUpdated`.
+		#[weight = T::WeightInfo::vested_transfer()]
+		pub fn vested_transfer(
+			origin,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T, I>, T::BlockNumber>,
+		) -> DispatchResult {
+			let transactor = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+
+			Self::do_vested_transfer(transactor, target, schedule)
+		}
+
+		/// Force a vested transfer.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `source`: The account whose funds should be transferred.
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		#[weight = T::WeightInfo::force_vested_transfer()]
+		pub fn force_vested_transfer(
+			origin,
+			source: <T::Lookup as StaticLookup>::Source,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T, I>, T::BlockNumber>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let source = T::Lookup::lookup(source)?;
+			let target = T::Lookup::lookup(target)?;
+
+			Self::do_vested_transfer(source, target, schedule)
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
+	/// current unvested amount.
+	fn update_lock(who: T::AccountId) -> DispatchResult {
+		let vesting = Self::vesting(&who).ok_or(<Error<T, I>>::NotVesting)?;
+		let now = <frame_system::Module<T>>::block_number();
+		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+
+		if locked_now.is_zero() {
+			T::Currency::remove_lock(VESTING_ID, &who);
+			<Vesting<T, I>>::remove(&who);
+			Self::deposit_event(RawEvent::VestingCompleted(who));
+		} else {
+			T::Currency::set_lock(
+				VESTING_ID,
+				&who,
+				LockFor::Common { amount: locked_now },
+				vesting_reasons(),
+			);
+			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
+		}
+
+		Ok(())
+	}
+
+	fn do_vested_transfer(
+		source: T::AccountId,
+		target: T::AccountId,
+		schedule: VestingInfo<BalanceOf<T, I>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(
+			schedule.locked >= T::MinVestedTransfer::get(),
+			<Error<T, I>>::AmountLow
+		);
+		ensure!(
+			!<Vesting<T, I>>::contains_key(&target),
+			<Error<T, I>>::ExistingVestingSchedule
+		);
+
+		T::Currency::transfer(
+			&source,
+			&target,
+			schedule.locked,
+			ExistenceRequirement::AllowDeath,
+		)?;
+
+		Self::add_vesting_schedule(
+			&target,
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+		)?;
+
+		Ok(())
+	}
+
+	/// Get the amount that is currently being vested and cannot be transferred out of this
+	/// account.
+	pub fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T, I>> {
+		if let Some(v) = Self::vesting(who) {
+			let now = <frame_system::Module<T>>::block_number();
+			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+
+			Some(T::Currency::free_balance(who).min(locked_now))
+		} else {
+			None
+		}
+	}
+
+	/// Adds a vesting schedule to a given account.
+	///
+	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// and nothing is updated.
+	///
+	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
+	/// reduction of the lock over time as it diminishes, the account owner must use `vest` or
+	/// `vest_other`.
+	///
+	/// Is a no-op if the amount to be vested is zero.
+	pub fn add_vesting_schedule(
+		who: &T::AccountId,
+		locked: BalanceOf<T, I>,
+		per_block: BalanceOf<T, I>,
+		starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		if locked.is_zero() {
+			return Ok(());
+		}
+		if <Vesting<T, I>>::contains_key(who) {
+			Err(<Error<T, I>>::ExistingVestingSchedule)?
+		}
+
+		let vesting_schedule = VestingInfo {
+			locked,
+			per_block,
+			starting_block,
+		};
+
+		<Vesting<T, I>>::insert(who, vesting_schedule);
+		// it can't fail, but even if somehow it did, we don't really care.
+		let _ = Self::update_lock(who.clone());
+
+		Ok(())
+	}
+
+	/// Remove a vesting schedule for a given account.
+	pub fn remove_vesting_schedule(who: &T::AccountId) {
+		<Vesting<T, I>>::remove(who);
+		T::Currency::remove_lock(VESTING_ID, who);
+	}
+}
+
+/// The vesting lock only restricts transfers and reserves, the funds can still pay the fees and
+/// be bonded.
+fn vesting_reasons() -> WithdrawReasons {
+	WithdrawReason::Transfer | WithdrawReason::Reserve
+}
+
+#[cfg(test)]
+mod tests {
+	// --- substrate ---
+	use frame_support::{
+		assert_noop, assert_ok, impl_outer_origin, parameter_types, traits::Currency,
+		weights::Weight,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+		DispatchError, Perbill,
+	};
+	// --- darwinia ---
+	use super::*;
+	use crate::{AccountData, KtonInstance, RingInstance};
+	use darwinia_primitives::Balance;
+
+	impl_outer_origin! {
+		pub enum Origin for Test where system = frame_system {}
+	}
+
+	type System = frame_system::Module<Test>;
+	type Ring = darwinia_balances::Module<Test, RingInstance>;
+	type Kton = darwinia_balances::Module<Test, KtonInstance>;
+	type Vesting = Module<Test>;
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl frame_system::Trait for Test {
+		type BaseCallFilter = ();
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = AccountData<Balance>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+	impl darwinia_balances::Trait<RingInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Kton,);
+		type WeightInfo = ();
+	}
+	impl darwinia_balances::Trait<KtonInstance> for Test {
+		type Balance = Balance;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type BalanceInfo = AccountData<Balance>;
+		type AccountStore = System;
+		type DustCollector = (Ring,);
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const MinVestedTransfer: Balance = 10;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = Ring;
+		type BlockNumberToBalance = ConvertInto;
+		type MinVestedTransfer = MinVestedTransfer;
+		type ForceOrigin = EnsureRoot<u64>;
+		type WeightInfo = ();
+	}
+
+	/// Account 1 vests 50 of its 100 over 10 blocks from block 0, account 2 vests its 200 over 20
+	/// blocks from block 10.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		darwinia_balances::GenesisConfig::<Test, RingInstance> {
+			balances: vec![(1, 100), (2, 200), (3, 300), (4, 10)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		GenesisConfig::<Test> {
+			vesting: vec![(1, 0, 10, 50), (2, 10, 20, 0)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	fn transfer(from: u64, value: Balance) -> DispatchResult {
+		<Ring as Currency<_>>::transfer(&from, &99, value, ExistenceRequirement::AllowDeath)
+	}
+
+	#[test]
+	fn genesis_should_lock_the_vesting_funds() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				Vesting::vesting(&1),
+				Some(VestingInfo {
+					locked: 50,
+					per_block: 5,
+					starting_block: 0,
+				})
+			);
+			assert_eq!(
+				Vesting::vesting(&2),
+				Some(VestingInfo {
+					locked: 200,
+					per_block: 10,
+					starting_block: 10,
+				})
+			);
+			assert_eq!(Vesting::vesting(&3), None);
+
+			assert!(transfer(1, 51).is_err());
+			assert_ok!(transfer(1, 50));
+			assert!(transfer(2, 1).is_err());
+		});
+	}
+
+	#[test]
+	fn vesting_balance_should_decay_linearly() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(Vesting::vesting_balance(&1), Some(45));
+			assert_eq!(Vesting::vesting_balance(&2), Some(200));
+			assert_eq!(Vesting::vesting_balance(&3), None);
+
+			System::set_block_number(5);
+
+			assert_eq!(Vesting::vesting_balance(&1), Some(25));
+			assert_eq!(Vesting::vesting_balance(&2), Some(200));
+
+			System::set_block_number(20);
+
+			assert_eq!(Vesting::vesting_balance(&1), Some(0));
+			assert_eq!(Vesting::vesting_balance(&2), Some(100));
+
+			System::set_block_number(30);
+
+			assert_eq!(Vesting::vesting_balance(&2), Some(0));
+		});
+	}
+
+	#[test]
+	fn vest_should_shrink_the_lock() {
+		new_test_ext().execute_with(|| {
+			// The lock only shrinks once `vest` is called.
+			assert!(transfer(1, 55).is_err());
+
+			assert_ok!(Vesting::vest(Origin::signed(1)));
+
+			assert!(transfer(1, 56).is_err());
+			assert_ok!(transfer(1, 55));
+		});
+	}
+
+	#[test]
+	fn vest_other_should_shrink_the_lock_of_the_target() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(5);
+
+			assert_ok!(Vesting::vest_other(Origin::signed(3), 1));
+
+			assert!(transfer(1, 76).is_err());
+			assert_ok!(transfer(1, 75));
+		});
+	}
+
+	#[test]
+	fn vest_should_remove_the_completed_schedule() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(10);
+
+			assert_ok!(Vesting::vest(Origin::signed(1)));
+
+			assert_eq!(Vesting::vesting(&1), None);
+			assert_ok!(transfer(1, 100));
+
+			assert_noop!(
+				Vesting::vest(Origin::signed(1)),
+				<Error<Test, DefaultInstance>>::NotVesting
+			);
+			assert_noop!(
+				Vesting::vest_other(Origin::signed(1), 3),
+				<Error<Test, DefaultInstance>>::NotVesting
+			);
+		});
+	}
+
+	#[test]
+	fn vested_transfer_should_lock_the_transferred_funds() {
+		new_test_ext().execute_with(|| {
+			let schedule = VestingInfo {
+				locked: 100,
+				per_block: 10,
+				starting_block: 1,
+			};
+
+			assert_ok!(Vesting::vested_transfer(Origin::signed(3), 4, schedule));
+
+			assert_eq!(Ring::free_balance(&3), 200);
+			assert_eq!(Ring::free_balance(&4), 110);
+			assert_eq!(Vesting::vesting(&4), Some(schedule));
+			assert_eq!(Vesting::vesting_balance(&4), Some(100));
+			assert!(transfer(4, 11).is_err());
+			assert_ok!(transfer(4, 10));
+
+			System::set_block_number(4);
+
+			assert_ok!(Vesting::vest(Origin::signed(4)));
+			assert_eq!(Vesting::vesting_balance(&4), Some(70));
+			assert_ok!(transfer(4, 30));
+			assert!(transfer(4, 1).is_err());
+		});
+	}
+
+	#[test]
+	fn vested_transfer_should_check_the_schedule() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Vesting::vested_transfer(
+					Origin::signed(3),
+					4,
+					VestingInfo {
+						locked: 9,
+						per_block: 1,
+						starting_block: 1,
+					}
+				),
+				<Error<Test, DefaultInstance>>::AmountLow
+			);
+			assert_noop!(
+				Vesting::vested_transfer(
+					Origin::signed(3),
+					1,
+					VestingInfo {
+						locked: 10,
+						per_block: 1,
+						starting_block: 1,
+					}
+				),
+				<Error<Test, DefaultInstance>>::ExistingVestingSchedule
+			);
+		});
+	}
+
+	#[test]
+	fn force_vested_transfer_should_require_the_force_origin() {
+		new_test_ext().execute_with(|| {
+			let schedule = VestingInfo {
+				locked: 100,
+				per_block: 10,
+				starting_block: 1,
+			};
+
+			assert_noop!(
+				Vesting::force_vested_transfer(Origin::signed(3), 3, 4, schedule),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Vesting::force_vested_transfer(
+				Origin::root(),
+				3,
+				4,
+				schedule
+			));
+
+			assert_eq!(Vesting::vesting(&4), Some(schedule));
+		});
+	}
+}
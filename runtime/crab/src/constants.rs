@@ -94,45 +94,128 @@ pub mod fee {
 
 pub mod relay {
 	// --- darwinia ---
-	use super::currency::*;
 	use crate::*;
 	use darwinia_relay_primitives::*;
+	use darwinia_runtime_common::fisherman::{
+		ethereum_fraud_proof, verify_ethereum_header_chain, EthereumHeaderChain, RelayedHeader,
+		RelayedHeaders,
+	};
 
-	pub struct EthereumRelayerGameAdjustor;
-	impl AdjustableRelayerGame for EthereumRelayerGameAdjustor {
-		type Moment = BlockNumber;
-		type Balance = Balance;
-		type TcBlockNumber = <<EthereumRelay as Relayable>::HeaderThing as HeaderThing>::Number;
-
-		fn challenge_time(round: Round) -> Self::Moment {
-			match round {
-				// 3 mins
-				0 => 30,
-				// 1 mins
-				_ => 10,
-			}
+	/// Play the Ethereum relayer game with the parameters in `EthereumRelayerGameParams`.
+	///
+	/// Swap `relayer_game_adjustor::Linear` for `relayer_game_adjustor::ExponentialBackoff` to
+	/// settle the long disputes in fewer rounds, for up to twice the bonds.
+	pub type EthereumRelayerGameAdjustor = relayer_game_adjustor::RelayerGameAdjustor<
+		Runtime,
+		relayer_game_params::Instance0,
+		<<EthereumRelay as Relayable>::HeaderThing as HeaderThing>::Number,
+		relayer_game_adjustor::Linear,
+	>;
+
+	/// The Ethereum headers proposed in the ongoing games of `EthereumRelayerGame`, proven fraud
+	/// with the Ethereum headers up to one confirmed by `EthereumRelay`.
+	pub struct EthereumRelayedHeaders;
+	impl EthereumRelayedHeaders {
+		fn relayed_headers_in(
+			game_id: u64,
+			proposal_index: u32,
+			relayer: AccountId,
+			bonded_proposal: Vec<(Balance, <EthereumRelay as Relayable>::HeaderThing)>,
+		) -> impl Iterator<Item = RelayedHeader<AccountId, Balance>> {
+			bonded_proposal
+				.into_iter()
+				.map(move |(bond, header_thing)| RelayedHeader {
+					game_id,
+					proposal_index,
+					relayer: relayer.clone(),
+					bond,
+					number: header_thing.number(),
+					hash: header_thing.hash(),
+				})
+		}
+	}
+	impl RelayedHeaders<AccountId, Balance> for EthereumRelayedHeaders {
+		type FraudProof = EthereumHeaderChain;
+
+		fn relayed_headers() -> Vec<RelayedHeader<AccountId, Balance>> {
+			darwinia_relayer_game::Proposals::<Runtime, EthereumRelayerGameInstance>::iter()
+				.flat_map(|(game_id, proposals)| {
+					proposals
+						.into_iter()
+						.enumerate()
+						.flat_map(move |(proposal_index, proposal)| {
+							Self::relayed_headers_in(
+								game_id,
+								proposal_index as _,
+								proposal.relayer,
+								proposal.bonded_proposal,
+							)
+						})
+				})
+				.collect()
+		}
+
+		fn relayed_headers_of(
+			game_id: u64,
+			proposal_index: u32,
+		) -> Vec<RelayedHeader<AccountId, Balance>> {
+			darwinia_relayer_game::Proposals::<Runtime, EthereumRelayerGameInstance>::get(game_id)
+				.into_iter()
+				.nth(proposal_index as _)
+				.into_iter()
+				.flat_map(|proposal| {
+					Self::relayed_headers_in(
+						game_id,
+						proposal_index,
+						proposal.relayer,
+						proposal.bonded_proposal,
+					)
+				})
+				.collect()
 		}
 
-		fn round_of_samples_count(chain_len: u64) -> Round {
-			chain_len - 1
+		fn is_ongoing(game_id: u64) -> bool {
+			darwinia_relayer_game::Proposals::<Runtime, EthereumRelayerGameInstance>::contains_key(
+				game_id,
+			)
 		}
 
-		fn samples_count_of_round(round: Round) -> u64 {
-			round + 1
+		fn verify_fraud_proof(
+			relayed_header: &RelayedHeader<AccountId, Balance>,
+			fraud_proof: &EthereumHeaderChain,
+		) -> bool {
+			verify_ethereum_header_chain(relayed_header, fraud_proof, |number| {
+				EthereumRelay::confirmed_header(number).map(|header| header.hash())
+			})
 		}
 
-		fn update_samples(samples: &mut Vec<Vec<Self::TcBlockNumber>>) {
-			samples.push(vec![samples.last().unwrap().last().unwrap() - 1]);
+		fn verify_fraud_proof_weight(fraud_proof: &EthereumHeaderChain) -> Weight {
+			// Decoding and hashing each header, then reading the confirmed one.
+			fraud_proof.len() as Weight * 10_000_000 + RocksDbWeight::get().reads(1)
 		}
 
-		fn estimate_bond(round: Round, proposals_count: u64) -> Self::Balance {
-			match round {
-				0 => match proposals_count {
-					0 => 1000 * COIN,
-					_ => 1500 * COIN,
-				},
-				_ => 100 * COIN,
+		fn fraud_proof_of(
+			endpoint: &str,
+			relayed_header: &RelayedHeader<AccountId, Balance>,
+		) -> Result<Option<EthereumHeaderChain>, &'static str> {
+			match EthereumRelay::confirmed_block_numbers()
+				.into_iter()
+				.filter(|number| *number >= relayed_header.number)
+				.min()
+			{
+				Some(confirmed_number) => {
+					ethereum_fraud_proof(endpoint, relayed_header, confirmed_number)
+				}
+				None => Ok(None),
 			}
 		}
 	}
+
+	/// The part of a slash paid to its reporters, as set in `Staking`.
+	pub struct SlashRewardFraction;
+	impl frame_support::traits::Get<Perbill> for SlashRewardFraction {
+		fn get() -> Perbill {
+			Staking::slash_reward_fraction()
+		}
+	}
 }
@@ -47,9 +47,7 @@ use static_assertions::const_assert;
 // --- substrate ---
 use frame_support::{
 	construct_runtime, debug, parameter_types,
-	traits::{
-		Imbalance, InstanceFilter, KeyOwnerProofSystem, LockIdentifier, OnUnbalanced, Randomness,
-	},
+	traits::{Filter, InstanceFilter, KeyOwnerProofSystem, LockIdentifier, Randomness},
 	weights::Weight,
 };
 use frame_system::{EnsureOneOf, EnsureRoot};
@@ -68,10 +66,12 @@ use sp_core::{
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		BlakeTwo256, Block as BlockT, Extrinsic as ExtrinsicT, IdentityLookup, NumberFor,
-		OpaqueKeys, SaturatedConversion,
+		BlakeTwo256, Block as BlockT, ConvertInto, Extrinsic as ExtrinsicT, IdentityLookup,
+		NumberFor, OpaqueKeys, SaturatedConversion,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
 	},
-	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, KeyTypeId, ModuleId, Perbill, Percent, Permill, RuntimeDebug,
 };
 use sp_staking::SessionIndex;
@@ -84,6 +84,9 @@ use constants::{currency::*, fee::*, relay::*, time::*};
 use darwinia_balances_rpc_runtime_api::RuntimeDispatchInfo as BalancesRuntimeDispatchInfo;
 use darwinia_header_mmr_rpc_runtime_api::RuntimeDispatchInfo as HeaderMMRRuntimeDispatchInfo;
 use darwinia_primitives::*;
+use darwinia_rpc_runtime_api::fee_payment::{
+	FeeCurrency, RuntimeDispatchInfo as FeePaymentRuntimeDispatchInfo,
+};
 use darwinia_runtime_common::*;
 use darwinia_staking::EraIndex;
 use darwinia_staking_rpc_runtime_api::RuntimeDispatchInfo as StakingRuntimeDispatchInfo;
@@ -106,7 +109,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	fee_payment::ChargeTransactionPayment<Runtime>,
 	darwinia_ethereum_relay::CheckEthereumRelayHeaderHash<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
@@ -132,13 +135,13 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("Crab"),
 	impl_name: create_runtime_str!("Darwinia Crab"),
 	authoring_version: 0,
-	spec_version: 9,
+	spec_version: 10,
 	impl_version: 0,
 	#[cfg(not(feature = "disable-runtime-api"))]
 	apis: RUNTIME_API_VERSIONS,
 	#[cfg(feature = "disable-runtime-api")]
 	apis: sp_version::create_apis_vec![[]],
-	transaction_version: 3,
+	transaction_version: 4,
 };
 
 /// Native version.
@@ -154,7 +157,7 @@ parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
 }
 impl frame_system::Trait for Runtime {
-	type BaseCallFilter = ();
+	type BaseCallFilter = CallFilter;
 	type Origin = Origin;
 	type Call = Call;
 	type Index = Nonce;
@@ -249,31 +252,79 @@ impl darwinia_balances::Trait<KtonInstance> for Runtime {
 	type WeightInfo = weights::darwinia_balances::WeightInfo;
 }
 
-pub struct DealWithFees;
-impl OnUnbalanced<NegativeImbalance<Runtime>> for DealWithFees {
-	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance<Runtime>>) {
-		if let Some(fees) = fees_then_tips.next() {
-			// for fees, 80% to treasury, 20% to author
-			let mut split = fees.ration(80, 20);
-			if let Some(tips) = fees_then_tips.next() {
-				// for tips, if any, 80% to treasury, 20% to author (though this can be anything)
-				tips.ration_merge_into(80, 20, &mut split);
-			}
-			Treasury::on_unbalanced(split.0);
-			ToAuthor::on_unbalanced(split.1);
-		}
-	}
+parameter_types! {
+	pub const RingMinVestedTransfer: Balance = 1 * COIN;
+	pub const KtonMinVestedTransfer: Balance = 1 * MILLI;
 }
+impl vesting::Trait<vesting::Instance0> for Runtime {
+	type Event = Event;
+	type Currency = Ring;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = RingMinVestedTransfer;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = ();
+}
+impl vesting::Trait<vesting::Instance1> for Runtime {
+	type Event = Event;
+	type Currency = Kton;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = KtonMinVestedTransfer;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = ();
+}
+
+impl fee_split::Trait for Runtime {
+	type Event = Event;
+	type RingCurrency = Ring;
+	type RingTreasury = Treasury;
+	type RingAuthor = ToAuthor<Runtime>;
+	type KtonCurrency = Kton;
+	type KtonTreasury = Treasury;
+	type KtonAuthor = ToAuthor<Runtime, KtonInstance>;
+	type SetOrigin = EnsureRootOrHalfCouncil;
+}
+
+parameter_types! {
+	pub UnpausablePallets: Vec<&'static str> = vec![
+		"System",
+		"Babe",
+		"Timestamp",
+		"Authorship",
+		"FinalityTracker",
+		"Grandpa",
+		"ImOnline",
+		"Council",
+		"TechnicalCommittee",
+		"TechnicalMembership",
+		"ElectionsPhragmen",
+		"Scheduler",
+		"Sudo",
+		"CallFilter",
+		"SudoRetirement",
+	];
+}
+impl call_filter::Trait for Runtime {
+	type Event = Event;
+	type PauseOrigin = EnsureRootOrHalfTechnicalComittee;
+	type UnpausablePallets = UnpausablePallets;
+}
+
 parameter_types! {
 	pub const TransactionByteFee: Balance = 10 * MILLI;
 }
 impl pallet_transaction_payment::Trait for Runtime {
 	type Currency = Ring;
-	type OnTransactionPayment = DealWithFees;
+	type OnTransactionPayment = FeeSplit;
 	type TransactionByteFee = TransactionByteFee;
 	type WeightToFee = WeightToFee;
 	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
 }
+impl fee_payment::Trait for Runtime {
+	type Event = Event;
+	type KtonCurrency = Kton;
+	type OnKtonTransactionPayment = fee_split::DealWithKtonFees<Runtime>;
+	type SetOrigin = EnsureRootOrHalfCouncil;
+}
 
 parameter_types! {
 	pub const UncleGenerations: BlockNumber = 0;
@@ -296,7 +347,7 @@ parameter_types! {
 		/ (SESSIONS_PER_ERA as BlockNumber * BLOCKS_PER_SESSION) - 1;
 	// quarter of the last session will be for election.
 	pub const ElectionLookahead: BlockNumber = BLOCKS_PER_SESSION / 4;
-	pub const MaxIterations: u32 = 5;
+	pub const DefaultMaxIterations: u32 = 5;
 	pub MinSolutionScoreBump: Perbill = Perbill::from_rational_approximation(5u32, 10_000);
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
 	pub const StakingUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
@@ -317,7 +368,7 @@ impl darwinia_staking::Trait for Runtime {
 	type NextNewSession = Session;
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
-	type MaxIterations = MaxIterations;
+	type MaxIterations = offchain_election::MaxIterations<DefaultMaxIterations>;
 	type MinSolutionScoreBump = MinSolutionScoreBump;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = StakingUnsignedPriority;
@@ -524,6 +575,36 @@ impl darwinia_treasury::Trait for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const BountyDepositBase: Balance = 1 * COIN;
+	pub const BountyDepositPayoutDelay: BlockNumber = 4 * DAYS;
+	pub const BountyUpdatePeriod: BlockNumber = 90 * DAYS;
+	pub const MaximumReasonLength: u32 = 16384;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const RingBountyValueMinimum: Balance = 10 * COIN;
+	pub const KtonBountyValueMinimum: Balance = 1 * COIN;
+	pub const DataDepositPerByte: Balance = 1 * MILLI;
+}
+impl bounties::Trait for Runtime {
+	type Event = Event;
+	type TreasuryModuleId = TreasuryModuleId;
+	type RingCurrency = Ring;
+	type KtonCurrency = Kton;
+	type ApproveOrigin = ApproveOrigin;
+	type RejectOrigin = EnsureRootOrHalfCouncil;
+	type BountyDepositBase = BountyDepositBase;
+	type DataDepositPerByte = DataDepositPerByte;
+	type MaximumReasonLength = MaximumReasonLength;
+	type RingBountyValueMinimum = RingBountyValueMinimum;
+	type KtonBountyValueMinimum = KtonBountyValueMinimum;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type SpendPeriod = SpendPeriod;
+	type OnSlash = Treasury;
+	type OnKtonSlash = Treasury;
+}
+
 parameter_types! {
 	pub const ClaimsModuleId: ModuleId = ModuleId(*b"da/claim");
 	pub Prefix: &'static [u8] = b"Pay RINGs to the Crab account:";
@@ -609,13 +690,20 @@ impl pallet_recovery::Trait for Runtime {
 	type RecoveryDeposit = RecoveryDeposit;
 }
 
+/// The scheduled calls are dispatched with the origin which scheduled them, so the collectives
+/// can't schedule anything they couldn't dispatch right away.
+type EnsureRootOrHalfCouncilOrHalfTechnicalComittee = EnsureOneOf<
+	AccountId,
+	EnsureRootOrHalfCouncil,
+	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, TechnicalCollective>,
+>;
 impl pallet_scheduler::Trait for Runtime {
 	type Event = Event;
 	type Origin = Origin;
 	type PalletsOrigin = OriginCaller;
 	type Call = Call;
 	type MaximumWeight = MaximumBlockWeight;
-	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type ScheduleOrigin = EnsureRootOrHalfCouncilOrHalfTechnicalComittee;
 	type WeightInfo = ();
 }
 
@@ -669,6 +757,10 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Recovery(pallet_recovery::Call::close_recovery(..)) |
 				Call::Recovery(pallet_recovery::Call::remove_recovery(..)) |
 				Call::Recovery(pallet_recovery::Call::cancel_recovered(..)) |
+				Call::Vesting(vesting::Call::vest(..)) |
+				Call::Vesting(vesting::Call::vest_other(..)) |
+				Call::KtonVesting(vesting::Call::vest(..)) |
+				Call::KtonVesting(vesting::Call::vest_other(..)) |
 				// Specifically omitting Vesting `vested_transfer`, and `force_vested_transfer`
 				Call::Scheduler(..) |
 				Call::Proxy(..) |
@@ -676,7 +768,16 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::EthereumBacking(..) |
 				Call::EthereumRelay(..) |
 				Call::EthereumRelayerGame(..) |
-				Call::HeaderMMR(..)
+				Call::HeaderMMR(..) |
+				Call::FeeSplit(..) |
+				Call::FeePayment(..) |
+				Call::CallFilter(..) |
+				Call::Bounties(..) |
+				Call::EthereumRelayerGameParams(..) |
+				Call::Fisherman(..)
+				// Specifically omitting the entire NftBridge pallet
+				// Specifically omitting the entire Dex pallet
+				// Specifically omitting the entire CrabMapping pallet
 			),
 			ProxyType::Staking => matches!(c, Call::Staking(..) | Call::Utility(..)),
 			ProxyType::IdentityJudgement => matches!(
@@ -757,6 +858,68 @@ impl darwinia_ethereum_backing::Trait for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const DexModuleId: ModuleId = ModuleId(*b"da/dexpl");
+	pub const DexMinimumLiquidity: Balance = 1 * COIN;
+	pub const DexMaxFeeRate: Permill = Permill::from_percent(5);
+}
+impl dex::Trait for Runtime {
+	type ModuleId = DexModuleId;
+	type Event = Event;
+	type RingCurrency = Ring;
+	type KtonCurrency = Kton;
+	type RingTreasury = Treasury;
+	type KtonTreasury = Treasury;
+	type MinimumLiquidity = DexMinimumLiquidity;
+	type MaxFeeRate = DexMaxFeeRate;
+	type SetOrigin = EnsureRootOrHalfCouncil;
+}
+
+/// The `TotalMappedRing` of `darwinia_crab_issuing`.
+pub struct CrabIssuingTotalMappedRing;
+impl frame_support::traits::Get<Balance> for CrabIssuingTotalMappedRing {
+	fn get() -> Balance {
+		CrabIssuing::total_mapped_ring()
+	}
+}
+parameter_types! {
+	pub const MinimumMapping: Balance = 1 * COIN;
+}
+impl crab_mapping::Trait for Runtime {
+	type Event = Event;
+	type RingCurrency = Ring;
+	type TotalMappable = CrabIssuingTotalMappedRing;
+	type MinimumMapping = MinimumMapping;
+}
+
+impl nft_bridge::Trait for Runtime {
+	type Event = Event;
+	type RingCurrency = Ring;
+	type EthereumRelay = EthereumRelay;
+	type RedeemAccountId = AccountId;
+}
+
+parameter_types! {
+	pub const RootCallApprovalPeriod: BlockNumber = 7 * DAYS;
+}
+impl sudo_retirement::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type CouncilOrigin =
+		pallet_collective::EnsureProportionAtLeast<_3, _5, AccountId, CouncilCollective>;
+	type TechnicalCommitteeOrigin =
+		pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, TechnicalCollective>;
+	type ApprovalPeriod = RootCallApprovalPeriod;
+}
+
+impl darwinia_runtime_common::fisherman::Trait for Runtime {
+	type Event = Event;
+	type AuthorityId = darwinia_primitives::fisherman::FishermanAppCrypto;
+	type RingCurrency = Ring;
+	type SlashRewardFraction = SlashRewardFraction;
+	type RelayedHeaders = EthereumRelayedHeaders;
+}
+
 type EnsureRootOrHalfTechnicalComittee = EnsureOneOf<
 	AccountId,
 	EnsureRoot<AccountId>,
@@ -777,19 +940,48 @@ impl darwinia_ethereum_relay::Trait for Runtime {
 }
 
 type EthereumRelayerGameInstance = darwinia_relayer_game::Instance0;
-parameter_types! {
-	pub const ConfirmPeriod: BlockNumber = 200;
-}
 impl darwinia_relayer_game::Trait<EthereumRelayerGameInstance> for Runtime {
 	type Event = Event;
 	type RingCurrency = Ring;
 	type RingSlash = Treasury;
 	type RelayerGameAdjustor = EthereumRelayerGameAdjustor;
 	type TargetChain = EthereumRelay;
-	type ConfirmPeriod = ConfirmPeriod;
+	type ConfirmPeriod =
+		relayer_game_params::ConfirmPeriod<Runtime, relayer_game_params::Instance0>;
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const EthereumRelayerGameDefaultParameters:
+		relayer_game_params::RelayerGameParameters<Balance, BlockNumber> =
+		relayer_game_params::RelayerGameParameters {
+			// 3 mins
+			first_round_challenge_time: 30,
+			// 1 mins
+			challenge_time: 10,
+			first_proposal_bond: 1000 * COIN,
+			competing_proposal_bond: 1500 * COIN,
+			round_bond: 100 * COIN,
+			confirm_period: 200,
+		};
+	pub const RelayerGameMinChallengeTime: BlockNumber = 5;
+	pub const RelayerGameMaxChallengeTime: BlockNumber = 1 * DAYS;
+	pub const RelayerGameMinBond: Balance = 1 * COIN;
+	pub const RelayerGameMinConfirmPeriod: BlockNumber = 10;
+	pub const RelayerGameMaxConfirmPeriod: BlockNumber = 7 * DAYS;
+}
+impl relayer_game_params::Trait<relayer_game_params::Instance0> for Runtime {
+	type Event = Event;
+	type RingCurrency = Ring;
+	type DefaultParameters = EthereumRelayerGameDefaultParameters;
+	type MinChallengeTime = RelayerGameMinChallengeTime;
+	type MaxChallengeTime = RelayerGameMaxChallengeTime;
+	type MinBond = RelayerGameMinBond;
+	type MinConfirmPeriod = RelayerGameMinConfirmPeriod;
+	type MaxConfirmPeriod = RelayerGameMaxConfirmPeriod;
+	type SetOrigin = EnsureRootOrHalfTechnicalComittee;
+}
+
 impl darwinia_header_mmr::Trait for Runtime {}
 
 parameter_types! {
@@ -882,6 +1074,31 @@ construct_runtime!(
 		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
 
 		CrabIssuing: darwinia_crab_issuing::{Module, Call, Storage, Config, Event<T>},
+
+		// Transaction payment support.
+		FeeSplit: fee_split::{Module, Call, Storage, Event<T>},
+		FeePayment: fee_payment::{Module, Call, Storage, Event<T>},
+
+		// Vesting. Usable initially, but removed once all vesting is finished.
+		Vesting: vesting::<Instance0>::{Module, Call, Storage, Event<T>, Config<T>},
+		KtonVesting: vesting::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
+
+		// Governance stuff.
+		CallFilter: call_filter::{Module, Call, Storage, Event},
+		Bounties: bounties::{Module, Call, Storage, Event<T>},
+		EthereumRelayerGameParams: relayer_game_params::<Instance0>::{Module, Call, Storage, Event<T>},
+
+		NftBridge: nft_bridge::{Module, Call, Storage, Config, Event<T>},
+
+		// DEX module.
+		Dex: dex::{Module, Call, Storage, Event<T>},
+
+		// Governance stuff; the way out of sudo.
+		SudoRetirement: sudo_retirement::{Module, Call, Storage, Event<T>},
+
+		CrabMapping: crab_mapping::{Module, Call, Storage, Event<T>},
+
+		Fisherman: darwinia_runtime_common::fisherman::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -911,7 +1128,7 @@ where
 			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
 			frame_system::CheckNonce::<Runtime>::from(nonce),
 			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+			fee_payment::ChargeTransactionPayment::<Runtime>::from(tip),
 			darwinia_ethereum_relay::CheckEthereumRelayHeaderHash::<Runtime>::new(),
 		);
 		let raw_payload = SignedPayload::new(call, extra)
@@ -988,13 +1205,18 @@ impl_runtime_apis! {
 		fn validate_transaction(
 			source: TransactionSource,
 			tx: <Block as BlockT>::Extrinsic) -> TransactionValidity {
+			// The paused calls would only pay their fees to be filtered at dispatch.
+			if !<CallFilter as Filter<Call>>::filter(&tx.function) {
+				return Err(InvalidTransaction::Call.into());
+			}
+
 			Executive::validate_transaction(source, tx)
 		}
 	}
 
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(header: &<Block as BlockT>::Header) {
-			Executive::offchain_worker(header)
+			offchain_election::with_node_settings(|| Executive::offchain_worker(header))
 		}
 	}
 
@@ -1012,7 +1234,7 @@ impl_runtime_apis! {
 		) -> Option<()> {
 			let key_owner_proof = key_owner_proof.decode()?;
 
-			Grandpa::submit_unsigned_equivocation_report(
+			Fisherman::submit_grandpa_equivocation_report(
 				equivocation_proof,
 				key_owner_proof,
 			)
@@ -1064,7 +1286,7 @@ impl_runtime_apis! {
 		) -> Option<()> {
 			let key_owner_proof = key_owner_proof.decode()?;
 
-			Babe::submit_unsigned_equivocation_report(
+			Fisherman::submit_babe_equivocation_report(
 				equivocation_proof,
 				key_owner_proof,
 			)
@@ -1105,6 +1327,79 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl darwinia_rpc_runtime_api::fee_payment::FeePaymentApi<Block, Balance> for Runtime {
+		fn query_info(
+			uxt: <Block as BlockT>::Extrinsic,
+			len: u32
+		) -> FeePaymentRuntimeDispatchInfo<Balance> {
+			let sender = uxt
+				.signature
+				.as_ref()
+				.map(|(sender, _, extra)| (sender.clone(), extra.6.tip()));
+			let ring_fee = TransactionPayment::query_info(uxt, len).partial_fee;
+
+			match sender
+				.and_then(|(sender, tip)| FeePayment::kton_fee_for(&sender, ring_fee, tip))
+			{
+				Some(kton_fee) => FeePaymentRuntimeDispatchInfo {
+					currency: FeeCurrency::Kton,
+					partial_fee: kton_fee,
+				},
+				None => FeePaymentRuntimeDispatchInfo {
+					currency: FeeCurrency::Ring,
+					partial_fee: ring_fee,
+				},
+			}
+		}
+	}
+
+	impl darwinia_rpc_runtime_api::dex::DexApi<Block, Balance> for Runtime {
+		fn quote_swap(
+			asset_in: dex::DexAsset,
+			amount_in: Balance
+		) -> Option<dex::SwapQuote<Balance>> {
+			Dex::quote(asset_in, amount_in)
+		}
+
+		fn pool_reserves() -> dex::PoolReserves<Balance> {
+			Dex::pool_reserves()
+		}
+	}
+
+	impl darwinia_rpc_runtime_api::crab_mapping::CrabMappingApi<Block, Balance> for Runtime {
+		fn mapping_allowance() -> crab_mapping::MappingAllowance<Balance> {
+			CrabMapping::mapping_allowance()
+		}
+	}
+
+	impl darwinia_rpc_runtime_api::scheduler::SchedulerApi<Block, BlockNumber> for Runtime {
+		fn agenda(blocks: BlockNumber) -> Vec<scheduler::ScheduledTask<BlockNumber>> {
+			scheduler::agenda::<Runtime>(blocks)
+		}
+	}
+
+	impl darwinia_rpc_runtime_api::metrics::MetricsApi<Block, Balance> for Runtime {
+		fn chain_metrics() -> metrics::ChainMetrics<Balance> {
+			// --- substrate ---
+			use frame_support::traits::Currency;
+
+			let treasury = Treasury::account_id();
+
+			metrics::ChainMetrics {
+				ethereum_confirmed_block: EthereumRelay::best_confirmed_block_number(),
+				relayer_games: darwinia_relayer_game::Proposals::<
+					Runtime,
+					EthereumRelayerGameInstance,
+				>::iter().count() as _,
+				staking_era: Staking::current_era().unwrap_or_default(),
+				validators: Session::validators().len() as _,
+				mmr_leaves: metrics::mmr_leaf_count(HeaderMMR::mmr_counter()),
+				treasury_ring: Ring::free_balance(&treasury),
+				treasury_kton: Kton::free_balance(&treasury),
+			}
+		}
+	}
+
 	impl darwinia_balances_rpc_runtime_api::BalancesApi<Block, AccountId, Balance> for Runtime {
 		fn usable_balance(
 			instance: u8,